@@ -0,0 +1,133 @@
+//! Derive macros for [combine](https://docs.rs/combine).
+//!
+//! These are re-exported by `combine` when its `derive` feature is enabled and should be used
+//! through that re-export.
+
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+/// `choice` is implemented for arrays of up to this many parsers.
+const MAX_CHOICE: usize = 32;
+
+/// Derives a parser and a `Display` implementation for an enum of unit variants where each
+/// variant is annotated with the literal it is parsed from, `#[token("let")]`.
+#[proc_macro_derive(Parser, attributes(token))]
+pub fn derive_parser(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`Parser` can not be derived for generic enums",
+        ));
+    }
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`Parser` can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut tokens = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`Parser` can only be derived for enums without fields",
+            ));
+        }
+        tokens.push((
+            &variant.ident,
+            token_literal(&variant.ident, &variant.attrs)?,
+        ));
+    }
+    if tokens.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`Parser` can not be derived for an enum without variants",
+        ));
+    }
+
+    let display_arms = tokens.iter().map(|(variant, literal)| {
+        quote! { #name::#variant => #literal }
+    });
+
+    // Try longer tokens first so that a token which is a prefix of another (`<` and `<=`) does
+    // not prevent the longer one from being parsed
+    let mut sorted = tokens.clone();
+    sorted.sort_by_key(|(_, literal)| std::cmp::Reverse(literal.value().len()));
+    let mut choices = sorted.chunks(MAX_CHOICE).map(|chunk| {
+        let alternatives = chunk.iter().map(|(variant, literal)| {
+            quote! {
+                ::combine::Parser::map(
+                    ::combine::parser::combinator::attempt(
+                        ::combine::parser::char::string(#literal),
+                    ),
+                    (|_| #name::#variant) as fn(&'static str) -> #name,
+                )
+            }
+        });
+        quote! { ::combine::parser::choice::choice([#(#alternatives),*]) }
+    });
+    let first = choices.next().unwrap();
+    let parser = choices.fold(first, |parser, choice| {
+        quote! { ::combine::Parser::or(#parser, #choice) }
+    });
+
+    Ok(quote! {
+        impl #name {
+            /// Parses one of the tokens of this enum.
+            pub fn parser<Input>() -> impl ::combine::Parser<Input, Output = #name>
+            where
+                Input: ::combine::Stream<Token = char>,
+                Input::Error: ::combine::error::ParseError<
+                    char,
+                    Input::Range,
+                    Input::Position,
+                >,
+            {
+                #parser
+            }
+        }
+
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(match *self {
+                    #(#display_arms,)*
+                })
+            }
+        }
+    })
+}
+
+fn token_literal(variant: &Ident, attrs: &[syn::Attribute]) -> Result<LitStr, Error> {
+    let mut literal = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("token")) {
+        if literal.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `token` attribute"));
+        }
+        let lit: LitStr = attr.parse_args()?;
+        if lit.value().is_empty() {
+            return Err(Error::new_spanned(lit, "tokens may not be empty"));
+        }
+        literal = Some(lit);
+    }
+    literal.ok_or_else(|| {
+        Error::new_spanned(
+            variant,
+            "variants must have a `#[token(\"...\")]` attribute",
+        )
+    })
+}
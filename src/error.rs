@@ -40,7 +40,8 @@ macro_rules! ctry {
 /// Trait for types which can be used to construct error information.
 ///
 /// To call functions expecting this trait, use the wrapper types defined in this module
-/// `Token`, `Range`, `Format` or `Static`/`&'static str`
+/// `Token`, `Range`, `Format`, `LazyFormat` or `Static`/`&'static str`. Owned messages can be
+/// passed as a `String` or `Cow<'static, str>` directly.
 pub trait ErrorInfo<'s, T, R> {
     type Format: fmt::Display;
     fn into_info(&'s self) -> Info<T, R, Self::Format>;
@@ -202,6 +203,59 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+impl<'s, T, R> ErrorInfo<'s, T, R> for String {
+    type Format = &'s str;
+    fn into_info(&'s self) -> Info<T, R, Self::Format> {
+        Info::Format(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'s, T, R> ErrorInfo<'s, T, R> for std::borrow::Cow<'static, str> {
+    type Format = &'s str;
+    fn into_info(&'s self) -> Info<T, R, Self::Format> {
+        match self {
+            std::borrow::Cow::Borrowed(s) => Info::Static(s),
+            std::borrow::Cow::Owned(s) => Info::Format(s),
+        }
+    }
+}
+
+/// Newtype which constructs an `Info::Format` through `ErrorInfo` by calling `F`.
+///
+/// `F` is only called when the error is actually created, so any allocation needed to build the
+/// message is skipped when parsing succeeds.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::error::LazyFormat;
+/// # use combine::parser::char::{letter, string};
+/// # fn main() {
+/// let name = "div";
+/// let mut parser = string("</")
+///     .with(many1::<String, _, _>(letter()))
+///     .skip(token('>'))
+///     .expected(LazyFormat(|| format!("closing tag </{}>", name)));
+/// let err = parser.easy_parse("text").unwrap_err();
+/// assert!(err.to_string().contains("closing tag </div>"), "{}", err);
+/// # }
+/// ```
+pub struct LazyFormat<F>(pub F);
+
+impl<'s, T, R, F, D> ErrorInfo<'s, T, R> for LazyFormat<F>
+where
+    F: Fn() -> D,
+    D: fmt::Display,
+{
+    type Format = D;
+    fn into_info(&'s self) -> Info<T, R, Self::Format> {
+        Info::Format((self.0)())
+    }
+}
+
 /// Enum used to indicate if a parser committed any items of the stream it was given as an input.
 ///
 /// This is used by parsers such as `or` and `choice` to determine if they should try to parse
@@ -342,7 +396,7 @@ impl<T> Commit<T> {
         match self {
             Commit::Commit(x) => match f(x) {
                 PeekOk(v) => CommitOk(v),
-                PeekErr(err) => CommitErr(err.error),
+                PeekErr(err) => CommitErr(err.into_error()),
                 y => y,
             },
             Commit::Peek(x) => f(x),
@@ -358,6 +412,54 @@ pub type StdParseResult<O, Input> =
     Result<(O, Commit<()>), Commit<Tracked<<Input as StreamOnce>::Error>>>;
 pub type StdParseResult2<O, E> = Result<(O, Commit<()>), Commit<Tracked<E>>>;
 
+/// Coarse classification of an error, returned by [`ParseError::kind`] and [`StreamError::kind`].
+///
+/// Lets code driving a parser (such as a codec or a retry loop) decide whether to wait for more
+/// input, skip ahead or abort without inspecting the error messages.
+///
+/// [`ParseError::kind`]: trait.ParseError.html#method.kind
+/// [`StreamError::kind`]: trait.StreamError.html#method.kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input ended before the parser could finish. More input may let parsing succeed.
+    Eof,
+    /// An unexpected token or range was encountered (or something else was expected).
+    Unexpected,
+    /// A message was emitted by a parser, for instance through `message` or `and_then`.
+    Message,
+    /// An `std::io::Error` occurred while reading the input.
+    Io,
+    /// Some other error type was stored in the error (see [`StreamError::other`]).
+    ///
+    /// [`StreamError::other`]: trait.StreamError.html#method.other
+    Custom,
+}
+
+impl ErrorKind {
+    /// Returns the most significant kind out of `kinds`, preferring in order `Eof`, `Io`, `Custom`
+    /// and `Message`. Returns `Unexpected` if none of those are found.
+    pub(crate) fn most_significant<I>(kinds: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        kinds.into_iter().fold(ErrorKind::Unexpected, |acc, kind| {
+            let rank = |kind| match kind {
+                ErrorKind::Eof => 4,
+                ErrorKind::Io => 3,
+                ErrorKind::Custom => 2,
+                ErrorKind::Message => 1,
+                ErrorKind::Unexpected => 0,
+            };
+            if rank(kind) > rank(acc) {
+                kind
+            } else {
+                acc
+            }
+        })
+    }
+}
+
 /// `StreamError` represents a single error returned from a `Stream` or a `Parser`.
 ///
 /// Usually multiple instances of `StreamError` is composed into a `ParseError` to build the final
@@ -437,6 +539,18 @@ pub trait StreamError<Item, Range>: Sized {
 
     fn is_unexpected_end_of_input(&self) -> bool;
 
+    /// Classifies `self`.
+    ///
+    /// The default implementation only distinguishes between `ErrorKind::Eof` and
+    /// `ErrorKind::Unexpected`.
+    fn kind(&self) -> ErrorKind {
+        if self.is_unexpected_end_of_input() {
+            ErrorKind::Eof
+        } else {
+            ErrorKind::Unexpected
+        }
+    }
+
     /// Converts `self` into a different `StreamError` type.
     ///
     /// This should aim to preserve as much information as possible into the returned `T` value but
@@ -466,6 +580,19 @@ pub trait ParseError<Item, Range, Position>: Sized + PartialEq {
         errors
     }
 
+    /// Creates the error of a token being requested at the end of the (complete) input.
+    ///
+    /// Errors which allocate may leave the end of input error out of the returned error and add it
+    /// through `Tracked::pending` instead, as `or`, `many` and other parsers which backtrack
+    /// usually discard these errors.
+    ///
+    /// Internal API: This should not be implemented explicitly outside of combine.
+    #[doc(hidden)]
+    #[inline]
+    fn from_end_of_input(position: Position) -> Tracked<Self> {
+        Self::from_error(position, StreamError::end_of_input()).into()
+    }
+
     /// Sets the position of this `ParseError`
     fn set_position(&mut self, position: Position);
 
@@ -513,6 +640,29 @@ pub trait ParseError<Item, Range, Position>: Sized + PartialEq {
 
     fn is_unexpected_end_of_input(&self) -> bool;
 
+    /// Classifies `self`. If `self` is composed of multiple errors the most significant kind is
+    /// returned, with `ErrorKind::Eof` taking precedence over the others.
+    ///
+    /// The default implementation only distinguishes between `ErrorKind::Eof` and
+    /// `ErrorKind::Unexpected`.
+    ///
+    /// ```
+    /// use combine::{EasyParser, error::{ErrorKind, ParseError}};
+    /// use combine::parser::char::{digit, string};
+    ///
+    /// let err = string("let").easy_parse("le").unwrap_err();
+    /// assert_eq!(ParseError::<char, &str, _>::kind(&err), ErrorKind::Eof);
+    /// let err = digit().easy_parse("a").unwrap_err();
+    /// assert_eq!(ParseError::<char, &str, _>::kind(&err), ErrorKind::Unexpected);
+    /// ```
+    fn kind(&self) -> ErrorKind {
+        if self.is_unexpected_end_of_input() {
+            ErrorKind::Eof
+        } else {
+            ErrorKind::Unexpected
+        }
+    }
+
     /// Does a best-effort conversion of `self` into another `ParseError`
     fn into_other<T>(self) -> T
     where
@@ -697,6 +847,14 @@ impl StringStreamError {
             CharacterBoundary => CHAR_BOUNDARY_ERROR_MESSAGE,
         }
     }
+
+    fn as_kind(&self) -> ErrorKind {
+        match *self {
+            StringStreamError::UnexpectedParse => ErrorKind::Unexpected,
+            StringStreamError::Eoi => ErrorKind::Eof,
+            StringStreamError::CharacterBoundary => ErrorKind::Message,
+        }
+    }
 }
 
 impl<Item, Range> StreamError<Item, Range> for StringStreamError {
@@ -762,6 +920,10 @@ impl<Item, Range> StreamError<Item, Range> for StringStreamError {
         *self == StringStreamError::Eoi
     }
     #[inline]
+    fn kind(&self) -> ErrorKind {
+        self.as_kind()
+    }
+    #[inline]
     fn into_other<T>(self) -> T
     where
         T: StreamError<Item, Range>,
@@ -812,6 +974,11 @@ where
         *self == StringStreamError::Eoi
     }
 
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        self.as_kind()
+    }
+
     #[inline]
     fn into_other<T>(self) -> T
     where
@@ -824,23 +991,111 @@ where
 /// Error wrapper which lets parsers track which parser in a sequence of sub-parsers has emitted
 /// the error. `Tracked::from` can be used to construct this and it should otherwise be
 /// ignored outside of combine.
-#[derive(Clone, PartialEq, Debug, Copy)]
+///
+/// Parsers which turn a `PeekErr` into a `CommitErr` (or otherwise return the error) must do so
+/// with `into_error` rather than by moving out `error`, as `error` may not be complete yet.
+#[derive(Clone, Copy)]
 pub struct Tracked<E> {
     /// The error returned
+    ///
+    /// This may leave out errors which are only added once the error is reported, such as the end
+    /// of input error of `easy::Errors`, use `into_error` to retrieve the complete error.
     pub error: E,
     #[doc(hidden)]
     pub offset: ErrorOffset,
+    /// Adds the errors which `error` leaves out for as long as it may be discarded, see
+    /// `ParseError::from_end_of_input`.
+    pending: Option<fn(&mut E)>,
 }
 
-impl<E> From<E> for Tracked<E> {
-    fn from(error: E) -> Self {
+impl<E> Tracked<E> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn new(error: E, offset: ErrorOffset) -> Self {
         Tracked {
-            error: error,
-            offset: ErrorOffset(1),
+            error,
+            offset,
+            pending: None,
+        }
+    }
+
+    /// Defers `f` until the error is reported, see `ParseError::from_end_of_input`.
+    #[inline]
+    pub(crate) fn set_pending(&mut self, f: Option<fn(&mut E)>) {
+        self.pending = f;
+    }
+
+    #[inline]
+    pub(crate) fn pending(&self) -> Option<fn(&mut E)> {
+        self.pending
+    }
+
+    /// Adds the errors which were left out of `self.error` while it could still be discarded.
+    #[doc(hidden)]
+    #[inline]
+    pub fn add_pending(&mut self) {
+        if let Some(f) = self.pending.take() {
+            f(&mut self.error);
+        }
+    }
+
+    /// Returns the complete error.
+    ///
+    /// Unlike the `error` field this includes the errors which are added lazily, such as the end
+    /// of input error of `easy::Errors`.
+    #[inline]
+    pub fn into_error(mut self) -> E {
+        self.add_pending();
+        self.error
+    }
+
+    /// Merges `self` and `other` into the error of `other`'s offset, keeping the pending errors
+    /// pending if both errors have them.
+    #[doc(hidden)]
+    pub fn merge<Item, Range, Position>(mut self, mut other: Self) -> Self
+    where
+        E: ParseError<Item, Range, Position>,
+    {
+        let pending = match (self.pending, other.pending) {
+            (Some(pending), Some(_)) => Some(pending),
+            _ => {
+                self.add_pending();
+                other.add_pending();
+                None
+            }
+        };
+        Tracked {
+            error: self.error.merge(other.error),
+            offset: other.offset,
+            pending,
         }
     }
 }
 
+impl<E> From<E> for Tracked<E> {
+    fn from(error: E) -> Self {
+        Tracked::new(error, ErrorOffset(1))
+    }
+}
+
+impl<E: PartialEq> PartialEq for Tracked<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.error == other.error
+            && self.offset == other.offset
+            && self.pending.is_some() == other.pending.is_some()
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Tracked<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracked")
+            .field("error", &self.error)
+            .field("offset", &self.offset)
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
+
 /// A `Result` type which has the committed status flattened into the result.
 /// Conversions to and from `std::result::Result` can be done using `result.into()` or
 /// `From::from(result)`
@@ -872,15 +1127,30 @@ impl<T, E> ParseResult<T, E> {
         !self.is_ok()
     }
 
+    /// Converts `&ParseResult<T, E>` to `ParseResult<&T, &E>`.
+    ///
+    /// The errors of a `PeekErr` which have not been added yet (see `Tracked::into_error`) are not
+    /// included, use `as_mut` to complete the error first.
     pub fn as_ref(&self) -> ParseResult<&T, &E> {
         match *self {
             CommitOk(ref t) => CommitOk(t),
             PeekOk(ref t) => PeekOk(t),
             CommitErr(ref e) => CommitErr(e),
-            PeekErr(ref e) => PeekErr(Tracked {
-                error: &e.error,
-                offset: e.offset,
-            }),
+            PeekErr(ref e) => PeekErr(Tracked::new(&e.error, e.offset)),
+        }
+    }
+
+    /// Converts `&mut ParseResult<T, E>` to `ParseResult<&mut T, &mut E>`, adding the pending
+    /// errors of a `PeekErr` so that the returned error is complete.
+    pub fn as_mut(&mut self) -> ParseResult<&mut T, &mut E> {
+        match *self {
+            CommitOk(ref mut t) => CommitOk(t),
+            PeekOk(ref mut t) => PeekOk(t),
+            CommitErr(ref mut e) => CommitErr(e),
+            PeekErr(ref mut e) => {
+                e.add_pending();
+                PeekErr(Tracked::new(&mut e.error, e.offset))
+            }
         }
     }
 
@@ -891,7 +1161,7 @@ impl<T, E> ParseResult<T, E> {
         match self {
             CommitOk(t) => match f(t) {
                 CommitOk(t2) | PeekOk(t2) => CommitOk(t2),
-                PeekErr(e) => CommitErr(e.error),
+                PeekErr(e) => CommitErr(e.into_error()),
                 CommitErr(e) => CommitErr(e),
             },
             PeekOk(t) => f(t),
@@ -908,10 +1178,10 @@ impl<T, E> ParseResult<T, E> {
             CommitOk(t) => CommitOk(t),
             PeekOk(t) => PeekOk(t),
             CommitErr(e) => CommitErr(f(e)),
-            PeekErr(e) => PeekErr(Tracked {
-                error: f(e.error),
-                offset: e.offset,
-            }),
+            PeekErr(e) => {
+                let offset = e.offset;
+                PeekErr(Tracked::new(f(e.into_error()), offset))
+            }
         }
     }
 
@@ -968,7 +1238,7 @@ impl<O, E> From<StdParseResult2<O, E>> for ParseResult<O, E> {
         match result {
             Ok((t, Commit::Commit(()))) => CommitOk(t),
             Ok((t, Commit::Peek(()))) => PeekOk(t),
-            Err(Commit::Commit(e)) => CommitErr(e.error),
+            Err(Commit::Commit(e)) => CommitErr(e.into_error()),
             Err(Commit::Peek(e)) => PeekErr(e),
         }
     }
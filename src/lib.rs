@@ -169,7 +169,17 @@
 //! }
 //! ```
 //!
+//! # `no_std`
+//!
+//! With `default-features = false` the `std` feature is disabled and `combine` neither uses `std`
+//! nor `alloc`, so it can be used on bare-metal targets. The token and choice parsers, the range
+//! parsers, [`count_exact`] (which collects into an array) and the non-allocating
+//! [`fixed::Errors`] error type are all available in that configuration. Parsers which collect
+//! into a `Vec` or `String`, and the [`easy`] errors, require the `std` feature.
+//!
 //! [`combinator`]: combinator/index.html
+//! [`count_exact`]: parser/repeat/fn.count_exact.html
+//! [`fixed::Errors`]: stream/fixed/struct.Errors.html
 //! [mod parser]: parser/index.html
 //! [`easy`]: easy/index.html
 //! [`error`]: error/index.html
@@ -208,13 +218,13 @@ pub use crate::stream::{Positioned, RangeStream, RangeStreamOnce, Stream, Stream
 
 #[doc(inline)]
 pub use crate::parser::{
-    choice::optional,
+    choice::{optional, optional_or},
     combinator::{attempt, look_ahead, not_followed_by},
     error::{unexpected, unexpected_any},
     function::parser,
     repeat::{
-        chainl1, chainr1, count, count_min_max, many, many1, sep_by, sep_by1, sep_end_by,
-        sep_end_by1, skip_count, skip_count_min_max, skip_many, skip_many1,
+        chainl1, chainr1, count, count_min_max, fold_many, many, many1, sep_by, sep_by1,
+        sep_end_by, sep_end_by1, skip_count, skip_count_min_max, skip_many, skip_many1,
     },
     sequence::between,
     token::{
@@ -231,6 +241,37 @@ pub use crate::parser::combinator::from_str;
 #[doc(inline)]
 pub use crate::parser::token::tokens_cmp;
 
+/// Derives a parser for an enum of literal tokens.
+///
+/// Each variant must be a unit variant annotated with the literal it is parsed from. The derive
+/// adds a `parser()` function to the enum which parses any of the literals (longest first) on a
+/// stream of `char`s, as well as a `Display` implementation which writes the literal.
+///
+/// ```
+/// # extern crate combine;
+/// use combine::Parser;
+///
+/// #[derive(Debug, PartialEq, Parser)]
+/// enum Op {
+///     #[token("<")]
+///     Lt,
+///     #[token("<=")]
+///     Le,
+///     #[token("=")]
+///     Eq,
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Op::parser().parse("<=1"), Ok((Op::Le, "1")));
+/// assert_eq!(Op::parser().parse("<1"), Ok((Op::Lt, "1")));
+/// assert!(Op::parser().parse("!").is_err());
+/// assert_eq!(Op::Le.to_string(), "<=");
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use combine_derive::Parser;
+
 /// Declares a named parser which can easily be reused.
 ///
 /// The expression which creates the parser should have no side effects as it may be called
@@ -241,6 +282,32 @@ pub use crate::parser::token::tokens_cmp;
 ///
 /// [json parser]:https://github.com/Marwes/combine/blob/master/benches/json.rs
 ///
+/// Writing `impl` before the output type makes the function return `impl Parser` directly instead
+/// of a generated struct. Any generics can then be declared in the brackets, including lifetimes,
+/// bounds and const parameters, at the cost of not being able to name the returned type. Since
+/// the returned type contains itself, a recursive call must be made through a function parser
+/// such as `parser(|input| expr().parse_stream(input).into_result())`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate combine;
+/// use combine::parser::char::digit;
+/// use combine::{count_min_max, Parser, Stream};
+///
+/// parser!{
+///     fn digits[Input, F: Extend<char> + Default, const N: usize]()(Input) -> impl F
+///     where [Input: Stream<Token = char>]
+///     {
+///         count_min_max(N, N, digit())
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(digits::<_, String, 2>().parse("123"), Ok(("12".to_string(), "3")));
+///     assert!(digits::<_, Vec<char>, 4>().parse("123").is_err());
+/// }
+/// ```
+///
 /// ```
 /// #[macro_use]
 /// extern crate combine;
@@ -319,6 +386,28 @@ pub use crate::parser::token::tokens_cmp;
 /// ```
 #[macro_export]
 macro_rules! parser {
+    (
+        $(#[$attr:meta])*
+        $fn_vis: vis fn $name: ident [$($type_params: tt)*]( $($arg: ident :  $arg_type: ty),*)
+            ($input_type: ty) -> impl $output_type: ty
+            where [$($where_clause: tt)*]
+        $parser: block
+    ) => {
+        $(#[$attr])*
+        #[inline]
+        $fn_vis fn $name< $($type_params)* >(
+                $($arg : $arg_type),*
+            ) -> impl $crate::Parser<$input_type, Output = $output_type>
+            where <$input_type as $crate::stream::StreamOnce>::Error:
+                    $crate::error::ParseError<
+                        <$input_type as $crate::stream::StreamOnce>::Token,
+                        <$input_type as $crate::stream::StreamOnce>::Range,
+                        <$input_type as $crate::stream::StreamOnce>::Position
+                        >,
+                $input_type: $crate::stream::Stream,
+                $($where_clause)*
+        $parser
+    };
     (
         type PartialState = $partial_state: ty;
         $(#[$attr:meta])*
@@ -467,7 +556,15 @@ macro_rules! combine_parser_impl {
             where M: $crate::parser::ParseMode
             {
                 let $type_name { $( $arg: ref mut $arg,)* .. } = *self;
-                $crate::combine_parse_partial!(($($partial_state)*) mode input state $parser)
+                let span = $crate::parser::combinator::RuleSpan::enter_rule(
+                    module_path!(),
+                    stringify!($name),
+                    $crate::parser::ParseMode::is_first(mode),
+                );
+                let result =
+                    $crate::combine_parse_partial!(($($partial_state)*) mode input state $parser);
+                span.exit(&result);
+                result
             }
 
             #[inline]
@@ -498,6 +595,47 @@ macro_rules! combine_parser_impl {
                 }
                 parser.add_committed_expected_error(errors)
             }
+
+            fn parse_many_tokens(
+                &mut self,
+                input: &mut $input_type,
+                f: &mut dyn FnMut($output_type),
+            ) -> Option<$crate::error::ParseResult<(), <$input_type as $crate::stream::StreamOnce>::Error>>
+            {
+                let $type_name { $( $arg : ref mut $arg,)*  .. } = *self;
+                let mut parser = $parser;
+                {
+                    let _: &mut dyn $crate::Parser< $input_type, Output = $output_type, PartialState = _> = &mut parser;
+                }
+                let span = $crate::parser::combinator::RuleSpan::enter_rule(
+                    module_path!(),
+                    stringify!($name),
+                    true,
+                );
+                let result = parser.parse_many_tokens(input, f);
+                if let Some(result) = &result {
+                    span.exit(result);
+                }
+                result
+            }
+
+            fn reflect(
+                &mut self,
+                visitor: &mut dyn $crate::parser::reflect::Visitor<$input_type>)
+            {
+                let $type_name { $( $arg : ref mut $arg,)*  .. } = *self;
+                let mut parser = $parser;
+                {
+                    let _: &mut dyn $crate::Parser< $input_type, Output = $output_type, PartialState = _> = &mut parser;
+                }
+                let args: &[&str] = &[$(stringify!($arg)),*];
+                let node = $crate::parser::reflect::Node::Rule {
+                    module: module_path!(),
+                    name: stringify!($name),
+                    parameterized: !args.is_empty(),
+                };
+                $crate::parser::reflect::visit(visitor, node, |visitor| parser.reflect(visitor))
+            }
         }
 
         $(#[$attr])*
@@ -579,13 +717,27 @@ macro_rules! forward_parser {
             self.$($field)+.add_committed_expected_error(error)
         }
     };
+    ($input: ty, parse_many_tokens $($field: tt)+) => {
+        fn parse_many_tokens(
+            &mut self,
+            input: &mut $input,
+            f: &mut dyn FnMut(Self::Output),
+        ) -> Option<ParseResult<(), <$input as $crate::StreamOnce>::Error>> {
+            self.$($field)+.parse_many_tokens(input, f)
+        }
+    };
     ($input: ty, parser_count $($field: tt)+) => {
         fn parser_count(&self) -> $crate::ErrorOffset {
             self.$($field)+.parser_count()
         }
     };
+    ($input: ty, reflect $($field: tt)+) => {
+        fn reflect(&mut self, visitor: &mut dyn $crate::parser::reflect::Visitor<$input>) {
+            self.$($field)+.reflect(visitor)
+        }
+    };
     ($input: ty, $field: tt) => {
-        forward_parser!($input, parse_lazy parse_first parse_partial add_error add_committed_expected_error parser_count, $field);
+        forward_parser!($input, parse_lazy parse_first parse_partial add_error add_committed_expected_error parser_count reflect, $field);
     };
     ($input: ty, $($field: tt)+) => {
     };
@@ -613,6 +765,9 @@ pub mod error;
 pub mod stream;
 #[macro_use]
 pub mod parser;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod test;
 
 #[doc(hidden)]
 #[derive(Clone, PartialOrd, PartialEq, Debug, Copy)]
@@ -4,7 +4,7 @@ use crate::{
     error::{self, ParseError, ParseResult::*},
     parser::{
         combinator::no_partial,
-        range::{take_fn, TakeRange},
+        range::{take_fn, utf8, TakeRange},
         repeat::skip_many,
         token::{satisfy, token, tokens_cmp, Token},
     },
@@ -303,6 +303,67 @@ where [
 }
 }
 
+parser! {
+/// Parses the bytes `s`, ignoring ASCII case differences. Returns `s` on success.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::byte::bytes_caseless;
+/// # fn main() {
+/// let result = bytes_caseless(&b"Content-Length"[..])
+///     .parse(&b"CONTENT-LENGTH: 3"[..]);
+/// assert_eq!(result, Ok((&b"Content-Length"[..], &b": 3"[..])));
+/// # }
+/// ```
+pub fn bytes_caseless['a, 'b, Input](s: &'static [u8])(Input) -> &'a [u8]
+where [
+    Input: Stream<Token = u8, Range = &'b [u8]>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+]
+{
+    bytes_cmp(s, |l: u8, r: u8| l.eq_ignore_ascii_case(&r))
+}
+}
+
+/// Parses a decimal floating point number, such as `-1.5e3`, into an `f32`. Fails if the number is
+/// too large to be represented.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::byte::float;
+/// assert_eq!(float().parse(&b"1.5e3!"[..]), Ok((1500.0, &b"!"[..])));
+/// assert!(float().parse(&b"1e39"[..]).is_err());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn float<Input>() -> impl Parser<Input, Output = f32>
+where
+    Input: Stream<Token = u8>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    crate::parser::char::float_value()
+}
+
+/// Parses a decimal floating point number, such as `-1.5e3`, into an `f64`. Fails if the number is
+/// too large to be represented.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::byte::double;
+/// assert_eq!(double().parse(&b"-0.25 "[..]), Ok((-0.25, &b" "[..])));
+/// assert!(double().parse(&b"1e309"[..]).is_err());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn double<Input>() -> impl Parser<Input, Output = f64>
+where
+    Input: Stream<Token = u8>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    crate::parser::char::float_value()
+}
+
 macro_rules! take_until {
     (
         $(#[$attr:meta])*
@@ -425,6 +486,47 @@ where [
 
 }
 
+/// Zero-copy parser which reads a null-terminated string, returning the bytes before the `0`
+/// byte and consuming the terminator.
+///
+/// If no `0` byte is found, the parser will return an error.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::byte::c_str;
+/// assert_eq!(c_str().parse(&b"name\0rest"[..]), Ok((&b"name"[..], &b"rest"[..])));
+/// assert_eq!(c_str().parse(&b"\0"[..]), Ok((&b""[..], &b""[..])));
+/// assert!(c_str().parse(&b"name"[..]).is_err());
+/// ```
+pub fn c_str<Input>() -> impl Parser<Input, Output = Input::Range>
+where
+    Input: RangeStream<Token = u8>,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    take_until_byte(0).skip(byte(0))
+}
+
+/// Zero-copy parser which reads a null-terminated string like [`c_str`], failing unless the
+/// bytes before the terminator are valid UTF-8 (see [`range::utf8`]).
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::byte::c_str_utf8;
+/// assert_eq!(c_str_utf8().parse(&b"name\0rest"[..]), Ok(("name", &b"rest"[..])));
+/// assert!(c_str_utf8().parse(&b"\xff\0"[..]).is_err());
+/// ```
+///
+/// [`c_str`]: fn.c_str.html
+/// [`range::utf8`]: ../range/fn.utf8.html
+pub fn c_str_utf8<'a, Input>() -> impl Parser<Input, Output = &'a str>
+where
+    Input: RangeStream<Token = u8, Range = &'a [u8]>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    utf8(c_str())
+}
+
 fn memslice(needle: &[u8], haystack: &[u8]) -> Option<usize> {
     let (&prefix, suffix) = match needle.split_first() {
         Some(x) => x,
@@ -442,15 +544,136 @@ fn memslice(needle: &[u8], haystack: &[u8]) -> Option<usize> {
 /// Parsers for decoding numbers in big-endian or little-endian order.
 pub mod num {
 
-    use crate::{error::ResultExt, lib::mem::size_of, parser::function::parser, stream::uncons};
+    use crate::{
+        error::{ParseResult, ResultExt, StreamError},
+        lib::{convert::TryFrom, mem::size_of},
+        parser::function::parser,
+        stream::{uncons, StreamErrorFor},
+    };
 
     use super::*;
 
+    /// Byte order used by [`num`] to decode a number.
+    ///
+    /// [`num`]: fn.num.html
+    pub trait Endianness {
+        /// Converts `bytes`, stored in this byte order, into a number.
+        fn from_bytes<T>(bytes: T::Bytes) -> T
+        where
+            T: FromBytes;
+    }
+
+    /// Big-endian (network) byte order.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum BigEndian {}
+
+    /// Little-endian byte order.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum LittleEndian {}
+
+    /// The byte order of the target platform.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum NativeEndian {}
+
+    impl Endianness for BigEndian {
+        fn from_bytes<T>(bytes: T::Bytes) -> T
+        where
+            T: FromBytes,
+        {
+            T::from_be_bytes(bytes)
+        }
+    }
+
+    impl Endianness for LittleEndian {
+        fn from_bytes<T>(bytes: T::Bytes) -> T
+        where
+            T: FromBytes,
+        {
+            T::from_le_bytes(bytes)
+        }
+    }
+
+    impl Endianness for NativeEndian {
+        fn from_bytes<T>(bytes: T::Bytes) -> T
+        where
+            T: FromBytes,
+        {
+            T::from_ne_bytes(bytes)
+        }
+    }
+
+    /// Numbers which can be read from a fixed number of bytes by [`num`].
+    ///
+    /// [`num`]: fn.num.html
+    pub trait FromBytes: Sized {
+        /// The byte array this number is stored in.
+        type Bytes: Default + AsMut<[u8]>;
+
+        fn from_be_bytes(bytes: Self::Bytes) -> Self;
+        fn from_le_bytes(bytes: Self::Bytes) -> Self;
+        fn from_ne_bytes(bytes: Self::Bytes) -> Self;
+    }
+
+    macro_rules! impl_from_bytes {
+        ($($t: ident)*) => {
+            $(
+                impl FromBytes for $t {
+                    type Bytes = [u8; size_of::<$t>()];
+
+                    fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                        $t::from_be_bytes(bytes)
+                    }
+                    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                        $t::from_le_bytes(bytes)
+                    }
+                    fn from_ne_bytes(bytes: Self::Bytes) -> Self {
+                        $t::from_ne_bytes(bytes)
+                    }
+                }
+            )*
+        }
+    }
+
+    impl_from_bytes! { u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 }
+
+    /// Reads a number of type `T` stored in the byte order `E` out of the byte stream.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::{num, BigEndian, LittleEndian};
+    ///
+    /// assert_eq!(num::<u32, BigEndian, _>().parse(&b"\0\0\0\x01"[..]), Ok((1, &b""[..])));
+    /// assert_eq!(num::<i16, LittleEndian, _>().parse(&b"\xff\xffa"[..]), Ok((-1, &b"a"[..])));
+    /// assert!(num::<u64, BigEndian, _>().parse(&b"\0\0"[..]).is_err());
+    /// ```
+    pub fn num<T, E, Input>() -> impl Parser<Input, Output = T, PartialState = ()>
+    where
+        T: FromBytes,
+        E: Endianness,
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        parser(|input: &mut Input| {
+            let checkpoint = input.checkpoint();
+            let result = (|input: &mut Input| {
+                let mut buffer = T::Bytes::default();
+                for elem in buffer.as_mut() {
+                    *elem = ctry!(uncons(input)).0;
+                }
+                CommitOk(E::from_bytes::<T>(buffer))
+            })(input);
+            if result.is_err() {
+                input.reset(checkpoint).committed().into_result()?;
+            }
+            result.into_result()
+        })
+    }
+
     macro_rules! integer_parser {
         (
             $(#[$attr:meta])*
             pub $type_name: ident,
-            $output_type: ident, $be_name: ident, $le_name: ident, $read_name: ident
+            $output_type: ident, $be_name: ident, $le_name: ident, $ne_name: ident, $read_name: ident
         ) => {
             $(#[$attr])*
             pub fn $be_name<'a, Input>() -> impl Parser<Input, Output = $output_type, PartialState = ()>
@@ -458,20 +681,7 @@ pub mod num {
                 Input: Stream<Token = u8>,
                 Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
             {
-                parser(|input: &mut Input| {
-                    let checkpoint = input.checkpoint();
-                    let result = (|input: &mut Input| {
-                        let mut buffer = [0u8; size_of::<$output_type>()];
-                        for elem in &mut buffer[..] {
-                            *elem = ctry!(uncons(input)).0;
-                        }
-                        CommitOk($output_type::from_be_bytes(buffer))
-                    })(input);
-                    if result.is_err() {
-                        input.reset(checkpoint).committed().into_result()?;
-                    }
-                    result.into_result()
-                })
+                num::<$output_type, BigEndian, Input>()
             }
 
             $(#[$attr])*
@@ -480,20 +690,16 @@ pub mod num {
                 Input: Stream<Token = u8>,
                 Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
             {
-                parser(|input: &mut Input| {
-                    let checkpoint = input.checkpoint();
-                    let result = (|input: &mut Input| {
-                        let mut buffer = [0u8; size_of::<$output_type>()];
-                        for elem in &mut buffer[..] {
-                            *elem = ctry!(uncons(input)).0;
-                        }
-                        CommitOk($output_type::from_le_bytes(buffer))
-                    })(input);
-                    if result.is_err() {
-                        input.reset(checkpoint).committed().into_result()?;
-                    }
-                    result.into_result()
-                })
+                num::<$output_type, LittleEndian, Input>()
+            }
+
+            $(#[$attr])*
+            pub fn $ne_name<Input>() -> impl Parser<Input, Output = $output_type, PartialState = ()>
+            where
+                Input: Stream<Token = u8>,
+                Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+            {
+                num::<$output_type, NativeEndian, Input>()
             }
         }
     }
@@ -508,7 +714,7 @@ pub mod num {
         /// assert_eq!(le_u16().parse(&b"\x01\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_u16().parse(&b"\0"[..]).is_err());
         /// ```
-        pub U16, u16, be_u16, le_u16, read_u16
+        pub U16, u16, be_u16, le_u16, ne_u16, read_u16
     );
     integer_parser!(
         /// Reads a u32 out of the byte stream with the specified endianess
@@ -520,7 +726,7 @@ pub mod num {
         /// assert_eq!(le_u32().parse(&b"\x01\0\0\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_u32().parse(&b"\x01\0\0"[..]).is_err());
         /// ```
-        pub U32, u32, be_u32, le_u32, read_u32
+        pub U32, u32, be_u32, le_u32, ne_u32, read_u32
     );
     integer_parser!(
         /// Reads a u64 out of the byte stream with the specified endianess
@@ -532,7 +738,7 @@ pub mod num {
         /// assert_eq!(le_u64().parse(&b"\x01\0\0\0\0\0\0\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_u64().parse(&b"\x01\0\0\0\0\0\0"[..]).is_err());
         /// ```
-        pub U64, u64, be_u64, le_u64, read_u64
+        pub U64, u64, be_u64, le_u64, ne_u64, read_u64
     );
 
     integer_parser!(
@@ -545,7 +751,7 @@ pub mod num {
         /// assert_eq!(le_i16().parse(&b"\x01\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_i16().parse(&b"\x01"[..]).is_err());
         /// ```
-        pub I16, i16, be_i16, le_i16, read_i16
+        pub I16, i16, be_i16, le_i16, ne_i16, read_i16
     );
 
     integer_parser!(
@@ -558,7 +764,7 @@ pub mod num {
         /// assert_eq!(le_i32().parse(&b"\x01\0\0\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_i32().parse(&b"\x01\0\0"[..]).is_err());
         /// ```
-        pub I32, i32, be_i32, le_i32, read_i32
+        pub I32, i32, be_i32, le_i32, ne_i32, read_i32
     );
     integer_parser!(
         /// Reads a i64 out of the byte stream with the specified endianess
@@ -570,7 +776,33 @@ pub mod num {
         /// assert_eq!(le_i64().parse(&b"\x01\0\0\0\0\0\0\0"[..]), Ok((1, &b""[..])));
         /// assert!(le_i64().parse(&b"\x01\0\0\0\0\0\0"[..]).is_err());
         /// ```
-        pub I64, i64, be_i64, le_i64, read_i64
+        pub I64, i64, be_i64, le_i64, ne_i64, read_i64
+    );
+    integer_parser!(
+        /// Reads a u128 out of the byte stream with the specified endianess
+        ///
+        /// ```
+        /// use combine::Parser;
+        /// use combine::parser::byte::num::be_u128;
+        ///
+        /// let buf = 1u128.to_be_bytes();
+        /// assert_eq!(be_u128().parse(&buf[..]), Ok((1, &b""[..])));
+        /// assert!(be_u128().parse(&buf[1..]).is_err());
+        /// ```
+        pub U128, u128, be_u128, le_u128, ne_u128, read_u128
+    );
+    integer_parser!(
+        /// Reads a i128 out of the byte stream with the specified endianess
+        ///
+        /// ```
+        /// use combine::Parser;
+        /// use combine::parser::byte::num::ne_i128;
+        ///
+        /// let buf = (-1i128).to_ne_bytes();
+        /// assert_eq!(ne_i128().parse(&buf[..]), Ok((-1, &b""[..])));
+        /// assert!(ne_i128().parse(&buf[1..]).is_err());
+        /// ```
+        pub I128, i128, be_i128, le_i128, ne_i128, read_i128
     );
 
     integer_parser!(
@@ -584,7 +816,7 @@ pub mod num {
         /// assert_eq!(le_f32().parse(&buf[..]), Ok((123.45, &b""[..])));
         /// assert!(le_f32().parse(&b"\x01\0\0"[..]).is_err());
         /// ```
-        pub F32, f32, be_f32, le_f32, read_f32
+        pub F32, f32, be_f32, le_f32, ne_f32, read_f32
     );
     integer_parser!(
         /// Reads a i64 out of the byte stream with the specified endianess
@@ -597,9 +829,223 @@ pub mod num {
         /// assert_eq!(le_f64().parse(&buf[..]), Ok((123.45, &b""[..])));
         /// assert!(le_f64().parse(&b"\x01\0\0\0\0\0\0"[..]).is_err());
         /// ```
-        pub F64, f64, be_f64, le_f64, read_f64
+        pub F64, f64, be_f64, le_f64, ne_f64, read_f64
     );
 
+    /// Maximum number of bytes a LEB128 encoded 64-bit integer occupies.
+    const MAX_VARINT_BYTES: usize = 10;
+
+    /// Reads the 7-bit groups of a LEB128 integer, returning the bits (sign extended if `signed`
+    /// is set).
+    fn varint<Input>(
+        input: &mut Input,
+        max_bytes: usize,
+        signed: bool,
+    ) -> ParseResult<u64, Input::Error>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        let position = input.position();
+        let mut bits = 0u64;
+        for i in 0..max_bytes {
+            let byte = if i == 0 {
+                ctry!(uncons(input)).0
+            } else {
+                match uncons(input) {
+                    CommitOk(byte) | PeekOk(byte) => byte,
+                    PeekErr(err) => return CommitErr(err.into_error()),
+                    CommitErr(err) => return CommitErr(err),
+                }
+            };
+            let group = u64::from(byte & 0x7f);
+            let shift = 7 * i as u32;
+            let overflow = if shift >= 64 {
+                // Groups past the end may only repeat the sign (or zero) bits
+                let extension = if signed && (bits as i64) < 0 { 0x7f } else { 0 };
+                group != extension
+            } else if signed && shift == 63 {
+                group != 0 && group != 0x7f
+            } else {
+                shift + 7 > 64 && group >> (64 - shift) != 0
+            };
+            if overflow {
+                return CommitErr(Input::Error::from_error(
+                    position,
+                    StreamError::message_static_message("varint overflows a 64-bit integer"),
+                ));
+            }
+            if shift < 64 {
+                bits |= group << shift;
+            }
+            if byte & 0x80 == 0 {
+                if signed && shift + 7 < 64 && byte & 0x40 != 0 {
+                    bits |= !0 << (shift + 7);
+                }
+                return CommitOk(bits);
+            }
+        }
+        CommitErr(Input::Error::from_error(
+            position,
+            StreamError::message_static_message("varint exceeds the maximum number of bytes"),
+        ))
+    }
+
+    fn varint_parser<Input>(
+        max_bytes: usize,
+        signed: bool,
+    ) -> impl Parser<Input, Output = u64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        parser(move |input: &mut Input| {
+            let checkpoint = input.checkpoint();
+            let result = varint(input, max_bytes, signed);
+            if result.is_err() {
+                input.reset(checkpoint).committed().into_result()?;
+            }
+            result.into_result()
+        })
+    }
+
+    /// Reads an unsigned LEB128 integer (as used by protobuf and WebAssembly) of at most 10
+    /// bytes.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::unsigned_varint;
+    ///
+    /// assert_eq!(unsigned_varint().parse(&b"\x01"[..]), Ok((1, &b""[..])));
+    /// assert_eq!(unsigned_varint().parse(&b"\xac\x02rest"[..]), Ok((300, &b"rest"[..])));
+    /// assert_eq!(
+    ///     unsigned_varint().parse(&b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"[..]),
+    ///     Ok((u64::max_value(), &b""[..]))
+    /// );
+    /// assert!(unsigned_varint().parse(&b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02"[..]).is_err());
+    /// assert!(unsigned_varint().parse(&b"\x80"[..]).is_err());
+    /// ```
+    pub fn unsigned_varint<Input>() -> impl Parser<Input, Output = u64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        unsigned_varint_max_bytes(MAX_VARINT_BYTES)
+    }
+
+    /// Reads an unsigned LEB128 integer of at most `max_bytes` bytes, failing if the
+    /// continuation bit is still set on the last allowed byte.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::unsigned_varint_max_bytes;
+    ///
+    /// assert_eq!(unsigned_varint_max_bytes(2).parse(&b"\xac\x02"[..]), Ok((300, &b""[..])));
+    /// assert!(unsigned_varint_max_bytes(2).parse(&b"\x80\x80\x01"[..]).is_err());
+    /// ```
+    pub fn unsigned_varint_max_bytes<Input>(
+        max_bytes: usize,
+    ) -> impl Parser<Input, Output = u64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        varint_parser(max_bytes, false)
+    }
+
+    /// Reads a signed (sign extended) LEB128 integer of at most 10 bytes, as used by
+    /// WebAssembly.
+    ///
+    /// Protobuf `sint` fields are zigzag encoded instead and should be read with
+    /// [`zigzag_i32`](fn.zigzag_i32.html) or [`zigzag_i64`](fn.zigzag_i64.html).
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::signed_varint;
+    ///
+    /// assert_eq!(signed_varint().parse(&b"\x02"[..]), Ok((2, &b""[..])));
+    /// assert_eq!(signed_varint().parse(&b"\x7e"[..]), Ok((-2, &b""[..])));
+    /// assert_eq!(signed_varint().parse(&b"\xc0\xbb\x78"[..]), Ok((-123456, &b""[..])));
+    /// assert_eq!(
+    ///     signed_varint().parse(&b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x7f"[..]),
+    ///     Ok((i64::min_value(), &b""[..]))
+    /// );
+    /// assert!(signed_varint().parse(&b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x01"[..]).is_err());
+    /// ```
+    pub fn signed_varint<Input>() -> impl Parser<Input, Output = i64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        signed_varint_max_bytes(MAX_VARINT_BYTES)
+    }
+
+    /// Reads a signed LEB128 integer of at most `max_bytes` bytes, failing if the continuation
+    /// bit is still set on the last allowed byte.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::signed_varint_max_bytes;
+    ///
+    /// assert_eq!(signed_varint_max_bytes(5).parse(&b"\x7f"[..]), Ok((-1, &b""[..])));
+    /// assert!(signed_varint_max_bytes(1).parse(&b"\x80\x7f"[..]).is_err());
+    /// ```
+    pub fn signed_varint_max_bytes<Input>(
+        max_bytes: usize,
+    ) -> impl Parser<Input, Output = i64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        varint_parser(max_bytes, true).map(|bits| bits as i64)
+    }
+
+    /// Reads a zigzag encoded varint as used by protobuf `sint32` fields.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::zigzag_i32;
+    ///
+    /// assert_eq!(zigzag_i32().parse(&b"\x00"[..]), Ok((0, &b""[..])));
+    /// assert_eq!(zigzag_i32().parse(&b"\x01"[..]), Ok((-1, &b""[..])));
+    /// assert_eq!(zigzag_i32().parse(&b"\x02"[..]), Ok((1, &b""[..])));
+    /// assert_eq!(zigzag_i32().parse(&b"\xff\xff\xff\xff\x0f"[..]), Ok((i32::min_value(), &b""[..])));
+    /// assert!(zigzag_i32().parse(&b"\xff\xff\xff\xff\x1f"[..]).is_err());
+    /// ```
+    pub fn zigzag_i32<Input>() -> impl Parser<Input, Output = i32, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        unsigned_varint_max_bytes(5).and_then(|n| match u32::try_from(n) {
+            Ok(n) => Ok((n >> 1) as i32 ^ -((n & 1) as i32)),
+            Err(_) => Err(StreamErrorFor::<Input>::message_static_message(
+                "varint overflows a 32-bit integer",
+            )),
+        })
+    }
+
+    /// Reads a zigzag encoded varint as used by protobuf `sint64` fields.
+    ///
+    /// ```
+    /// use combine::Parser;
+    /// use combine::parser::byte::num::zigzag_i64;
+    ///
+    /// assert_eq!(zigzag_i64().parse(&b"\x03"[..]), Ok((-2, &b""[..])));
+    /// assert_eq!(zigzag_i64().parse(&b"\xd8\x04"[..]), Ok((300, &b""[..])));
+    /// assert_eq!(
+    ///     zigzag_i64().parse(&b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"[..]),
+    ///     Ok((i64::min_value(), &b""[..]))
+    /// );
+    /// ```
+    pub fn zigzag_i64<Input>() -> impl Parser<Input, Output = i64, PartialState = ()>
+    where
+        Input: Stream<Token = u8>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        unsigned_varint().map(|n| (n >> 1) as i64 ^ -((n & 1) as i64))
+    }
+
     #[cfg(test)]
     mod tests {
 
@@ -0,0 +1,369 @@
+//! Sets of bytes which zero-copy parsers can scan for many bytes at a time.
+//!
+//! Scanning a range with [`take_while`][] calls its predicate once for every token, which makes
+//! it the hottest loop of most text parsers (identifiers, whitespace, numbers). When the predicate
+//! is a set of bytes, [`take_while_set`][] and [`take_while1_set`][] scan the range with a
+//! [`ByteSet`][] instead, which with the `simd` feature checks 16 bytes per iteration using SSSE3
+//! (detected at runtime when `std` is enabled) or NEON. The NEON implementation uses intrinsics
+//! which were stabilized in Rust 1.59 so `simd` requires Rust 1.59 on `aarch64`.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::byte_set::ByteSet;
+//! # use combine::parser::range::{take_while1_set, take_while_set};
+//! # fn main() {
+//! const IDENT: ByteSet = ByteSet::ASCII_ALPHANUMERIC.insert(b'_');
+//!
+//! let mut assignment = (
+//!     take_while1_set(IDENT),
+//!     take_while_set(ByteSet::ASCII_WHITESPACE),
+//!     token('='),
+//! );
+//! assert_eq!(assignment.parse("snake_case2 = 1"), Ok((("snake_case2", " ", '='), " 1")));
+//! # }
+//! ```
+//!
+//! [`take_while`]: ../range/fn.take_while.html
+//! [`take_while_set`]: ../range/fn.take_while_set.html
+//! [`take_while1_set`]: ../range/fn.take_while1_set.html
+//! [`ByteSet`]: struct.ByteSet.html
+
+/// A set of bytes, stored as a 256-bit table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ByteSet {
+    // Byte `b` is in the set if bit `(b >> 4) & 7` of `low[b & 0xF]` (for `b < 0x80`) or of
+    // `high[b & 0xF]` (for `b >= 0x80`) is set, which is the layout the SIMD lookups need
+    low: [u8; 16],
+    high: [u8; 16],
+}
+
+impl Default for ByteSet {
+    fn default() -> Self {
+        ByteSet::EMPTY
+    }
+}
+
+impl ByteSet {
+    pub const EMPTY: ByteSet = ByteSet {
+        low: [0; 16],
+        high: [0; 16],
+    };
+
+    /// `0-9`, the bytes of `u8::is_ascii_digit`.
+    pub const ASCII_DIGIT: ByteSet = ByteSet::range(b'0', b'9');
+
+    /// `0-9`, `a-f` and `A-F`, the bytes of `u8::is_ascii_hexdigit`.
+    pub const ASCII_HEXDIGIT: ByteSet = ByteSet::ASCII_DIGIT
+        .union(ByteSet::range(b'a', b'f'))
+        .union(ByteSet::range(b'A', b'F'));
+
+    /// `a-z` and `A-Z`, the bytes of `u8::is_ascii_alphabetic`.
+    pub const ASCII_ALPHABETIC: ByteSet =
+        ByteSet::range(b'a', b'z').union(ByteSet::range(b'A', b'Z'));
+
+    /// `0-9`, `a-z` and `A-Z`, the bytes of `u8::is_ascii_alphanumeric`.
+    pub const ASCII_ALPHANUMERIC: ByteSet = ByteSet::ASCII_ALPHABETIC.union(ByteSet::ASCII_DIGIT);
+
+    /// Space, `\t`, `\n`, `\x0C` and `\r`, the bytes of `u8::is_ascii_whitespace`.
+    pub const ASCII_WHITESPACE: ByteSet = ByteSet::from_bytes(b" \t\n\x0C\r");
+
+    /// Returns the set containing `bytes`.
+    pub const fn from_bytes(bytes: &[u8]) -> ByteSet {
+        let mut set = ByteSet::EMPTY;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.insert(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Returns the set containing the bytes from `start` to `end` (inclusive).
+    pub const fn range(start: u8, end: u8) -> ByteSet {
+        let mut set = ByteSet::EMPTY;
+        let mut b = start as usize;
+        while b <= end as usize {
+            set = set.insert(b as u8);
+            b += 1;
+        }
+        set
+    }
+
+    /// Returns the set containing the bytes which satisfy `f`.
+    pub fn from_fn<F>(mut f: F) -> ByteSet
+    where
+        F: FnMut(u8) -> bool,
+    {
+        (0..=255u8)
+            .filter(|&b| f(b))
+            .fold(ByteSet::EMPTY, ByteSet::insert)
+    }
+
+    /// Returns this set with `b` added to it.
+    pub const fn insert(mut self, b: u8) -> ByteSet {
+        let bit = 1 << ((b >> 4) & 7);
+        if b < 0x80 {
+            self.low[(b & 0xF) as usize] |= bit;
+        } else {
+            self.high[(b & 0xF) as usize] |= bit;
+        }
+        self
+    }
+
+    /// Returns the set containing the bytes of both `self` and `other`.
+    pub const fn union(mut self, other: ByteSet) -> ByteSet {
+        let mut i = 0;
+        while i < 16 {
+            self.low[i] |= other.low[i];
+            self.high[i] |= other.high[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// Returns the set containing the bytes which are not in `self`.
+    pub const fn complement(mut self) -> ByteSet {
+        let mut i = 0;
+        while i < 16 {
+            self.low[i] = !self.low[i];
+            self.high[i] = !self.high[i];
+            i += 1;
+        }
+        self
+    }
+
+    pub const fn contains(&self, b: u8) -> bool {
+        let bits = if b < 0x80 { self.low } else { self.high };
+        bits[(b & 0xF) as usize] & (1 << ((b >> 4) & 7)) != 0
+    }
+
+    /// Returns the number of bytes at the start of `bytes` which are in the set.
+    ///
+    /// ```
+    /// # use combine::parser::byte_set::ByteSet;
+    /// assert_eq!(ByteSet::ASCII_DIGIT.scan(b"2021-01-01"), 4);
+    /// assert_eq!(ByteSet::ASCII_DIGIT.scan(b"x"), 0);
+    /// ```
+    #[inline]
+    pub fn scan(&self, bytes: &[u8]) -> usize {
+        simd::scan(self, bytes)
+    }
+
+    fn scan_scalar(&self, bytes: &[u8]) -> usize {
+        bytes
+            .iter()
+            .position(|&b| !self.contains(b))
+            .unwrap_or(bytes.len())
+    }
+
+    /// Returns the ASCII bytes of the set, so that scanning a `&str` never stops inside a
+    /// character.
+    const fn ascii(mut self) -> ByteSet {
+        self.high = [0; 16];
+        self
+    }
+}
+
+/// Ranges which can be scanned with a [`ByteSet`][].
+///
+/// [`ByteSet`]: struct.ByteSet.html
+pub trait ScanBytes {
+    /// Returns the length of the longest prefix of `self` which only contains bytes of `set`.
+    ///
+    /// `&str` only looks at the ASCII bytes of `set` so the prefix always ends at a character
+    /// boundary.
+    fn scan_bytes(&self, set: &ByteSet) -> usize;
+}
+
+impl ScanBytes for &[u8] {
+    #[inline]
+    fn scan_bytes(&self, set: &ByteSet) -> usize {
+        set.scan(self)
+    }
+}
+
+impl ScanBytes for &str {
+    #[inline]
+    fn scan_bytes(&self, set: &ByteSet) -> usize {
+        set.ascii().scan(self.as_bytes())
+    }
+}
+
+#[cfg(all(feature = "simd", any(target_arch = "x86", target_arch = "x86_64")))]
+mod simd {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::ByteSet;
+
+    #[cfg(feature = "std")]
+    fn has_ssse3() -> bool {
+        std::is_x86_feature_detected!("ssse3")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn has_ssse3() -> bool {
+        cfg!(target_feature = "ssse3")
+    }
+
+    #[inline]
+    pub(super) fn scan(set: &ByteSet, bytes: &[u8]) -> usize {
+        if bytes.len() >= 16 && has_ssse3() {
+            // SAFETY: The CPU supports SSSE3
+            unsafe { scan_ssse3(set, bytes) }
+        } else {
+            set.scan_scalar(bytes)
+        }
+    }
+
+    #[target_feature(enable = "ssse3")]
+    unsafe fn scan_ssse3(set: &ByteSet, bytes: &[u8]) -> usize {
+        let low = _mm_loadu_si128(set.low.as_ptr() as *const __m128i);
+        let high = _mm_loadu_si128(set.high.as_ptr() as *const __m128i);
+        let bits = _mm_setr_epi8(1, 2, 4, 8, 16, 32, 64, -128, 1, 2, 4, 8, 16, 32, 64, -128);
+        let top_bit = _mm_set1_epi8(-128);
+        let seven = _mm_set1_epi8(7);
+
+        let mut i = 0;
+        while bytes.len() - i >= 16 {
+            let v = _mm_loadu_si128(bytes.as_ptr().add(i) as *const __m128i);
+            // `pshufb` returns 0 for indices with the top bit set so each byte is only looked up
+            // in the table of its half
+            let table = _mm_or_si128(
+                _mm_shuffle_epi8(low, v),
+                _mm_shuffle_epi8(high, _mm_xor_si128(v, top_bit)),
+            );
+            let bit = _mm_shuffle_epi8(bits, _mm_and_si128(_mm_srli_epi16(v, 4), seven));
+            let outside = _mm_cmpeq_epi8(_mm_and_si128(table, bit), _mm_setzero_si128());
+            let mask = _mm_movemask_epi8(outside);
+            if mask != 0 {
+                return i + mask.trailing_zeros() as usize;
+            }
+            i += 16;
+        }
+        i + set.scan_scalar(&bytes[i..])
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64", target_feature = "neon"))]
+mod simd {
+    use core::arch::aarch64::*;
+
+    use super::ByteSet;
+
+    #[inline]
+    pub(super) fn scan(set: &ByteSet, bytes: &[u8]) -> usize {
+        if bytes.len() >= 16 {
+            // SAFETY: NEON is enabled for the target
+            unsafe { scan_neon(set, bytes) }
+        } else {
+            set.scan_scalar(bytes)
+        }
+    }
+
+    unsafe fn scan_neon(set: &ByteSet, bytes: &[u8]) -> usize {
+        let low = vld1q_u8(set.low.as_ptr());
+        let high = vld1q_u8(set.high.as_ptr());
+        let bits = vld1q_u8([1u8, 2, 4, 8, 16, 32, 64, 128, 1, 2, 4, 8, 16, 32, 64, 128].as_ptr());
+        // `tbl` returns 0 for indices above 15, which keeping the top bit ensures for the bytes
+        // of the other half
+        let index = vdupq_n_u8(0x8F);
+        let top_bit = vdupq_n_u8(0x80);
+        let seven = vdupq_n_u8(7);
+
+        let mut i = 0;
+        while bytes.len() - i >= 16 {
+            let v = vld1q_u8(bytes.as_ptr().add(i));
+            let table = vorrq_u8(
+                vqtbl1q_u8(low, vandq_u8(v, index)),
+                vqtbl1q_u8(high, vandq_u8(veorq_u8(v, top_bit), index)),
+            );
+            let bit = vqtbl1q_u8(bits, vandq_u8(vshrq_n_u8::<4>(v), seven));
+            let inside = vtstq_u8(table, bit);
+            if vminvq_u8(inside) == 0 {
+                // Narrows the mask to 4 bits for each byte, set for the bytes outside of the set
+                let outside = vshrn_n_u16::<4>(vreinterpretq_u16_u8(vmvnq_u8(inside)));
+                let mask = vget_lane_u64::<0>(vreinterpret_u64_u8(outside));
+                return i + (mask.trailing_zeros() / 4) as usize;
+            }
+            i += 16;
+        }
+        i + set.scan_scalar(&bytes[i..])
+    }
+}
+
+#[cfg(not(all(
+    feature = "simd",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "aarch64", target_feature = "neon")
+    )
+)))]
+mod simd {
+    use super::ByteSet;
+
+    #[inline]
+    pub(super) fn scan(set: &ByteSet, bytes: &[u8]) -> usize {
+        set.scan_scalar(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_matches_ascii_predicates() {
+        for b in 0..=255u8 {
+            assert_eq!(ByteSet::ASCII_DIGIT.contains(b), b.is_ascii_digit());
+            assert_eq!(ByteSet::ASCII_HEXDIGIT.contains(b), b.is_ascii_hexdigit());
+            assert_eq!(
+                ByteSet::ASCII_ALPHABETIC.contains(b),
+                b.is_ascii_alphabetic()
+            );
+            assert_eq!(
+                ByteSet::ASCII_ALPHANUMERIC.contains(b),
+                b.is_ascii_alphanumeric()
+            );
+            assert_eq!(
+                ByteSet::ASCII_WHITESPACE.contains(b),
+                b.is_ascii_whitespace()
+            );
+            assert_eq!(
+                ByteSet::ASCII_DIGIT.complement().contains(b),
+                !b.is_ascii_digit()
+            );
+        }
+    }
+
+    #[test]
+    fn scan_stops_at_every_byte_and_position() {
+        let sets = [
+            ByteSet::ASCII_ALPHANUMERIC,
+            ByteSet::from_fn(|b| b >= 0x80 || b == b'a'),
+            ByteSet::from_fn(|b| b != 0 && b % 3 != 0),
+        ];
+        for set in &sets {
+            let member = (0..=255u8).find(|&b| set.contains(b)).unwrap();
+            for stop in (0..=255u8).filter(|&b| !set.contains(b)) {
+                for len in 0..40 {
+                    let mut bytes = vec![member; 40];
+                    bytes[len] = stop;
+                    assert_eq!(set.scan(&bytes), len, "{:?} {} {}", set, stop, len);
+                }
+            }
+            assert_eq!(set.scan(&[member; 37]), 37);
+        }
+    }
+
+    #[test]
+    fn scan_str_stops_at_non_ascii() {
+        let set = ByteSet::ASCII_ALPHABETIC.complement();
+        assert_eq!("12 åb".scan_bytes(&set), 3);
+        assert_eq!("12 åb".scan_bytes(&ByteSet::ASCII_ALPHABETIC), 0);
+    }
+}
@@ -1,16 +1,50 @@
 //! Module containing parsers specialized on character streams.
 
 use crate::{
-    error::ParseError,
+    error::{ParseError, ParseResult, ResultExt, StreamError, Tracked},
+    lib::marker::PhantomData,
     parser::{
-        combinator::no_partial,
-        repeat::skip_many,
-        token::{satisfy, token, tokens_cmp, Token},
+        choice::{choice, optional},
+        combinator::{attempt, no_partial, not_followed_by, Either},
+        repeat::{fold_many, skip_many, skip_many1, skip_until},
+        token::{satisfy, satisfy_map, token, tokens_cmp, Token},
     },
-    stream::Stream,
+    stream::{uncons, ResetStream, Stream, StreamErrorFor, StreamOnce},
     Parser,
 };
 
+#[cfg(feature = "unicode-xid")]
+use crate::{
+    parser::range::{recognize as recognize_range, take_while},
+    stream::RangeStream,
+};
+#[cfg(feature = "unicode-xid")]
+use unicode_xid::UnicodeXID;
+
+#[cfg(feature = "unicode-general-category")]
+use unicode_general_category::get_general_category;
+#[cfg(feature = "unicode-general-category")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-general-category")))]
+pub use unicode_general_category::GeneralCategory;
+
+#[cfg(feature = "unicode-script")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-script")))]
+pub use unicode_script::Script;
+#[cfg(feature = "unicode-script")]
+use unicode_script::UnicodeScript;
+
+#[cfg(feature = "unicode-case")]
+use crate::lib::iter;
+#[cfg(feature = "unicode-case")]
+use caseless::Caseless;
+
+#[cfg(feature = "std")]
+use crate::parser::{
+    combinator::recognize,
+    repeat::{count_min_max, many},
+    sequence::between,
+};
+
 /// Parses a character and succeeds if the character is equal to `c`.
 ///
 /// ```
@@ -48,15 +82,20 @@ parser! {
 
 /// Parse a single whitespace according to [`std::char::is_whitespace`].
 ///
-/// This includes space characters, tabs and newlines.
+/// This includes space characters, tabs and newlines as well as the other characters with the
+/// Unicode `White_Space` property such as the no-break space (`U+00A0`) and the ideographic space
+/// (`U+3000`). Use [`hspace`] to skip whitespace without skipping line breaks.
 ///
 /// [`std::char::is_whitespace`]: https://doc.rust-lang.org/std/primitive.char.html#method.is_whitespace
+/// [`hspace`]: fn.hspace.html
 ///
 /// ```
 /// use combine::Parser;
 /// use combine::parser::char::space;
 /// assert_eq!(space().parse(" "), Ok((' ', "")));
 /// assert_eq!(space().parse("  "), Ok((' ', " ")));
+/// assert_eq!(space().parse("\u{a0}"), Ok(('\u{a0}', "")));
+/// assert_eq!(space().parse("\u{3000}"), Ok(('\u{3000}', "")));
 /// assert!(space().parse("!").is_err());
 /// assert!(space().parse("").is_err());
 /// ```
@@ -89,6 +128,94 @@ where
     skip_many(space()).expected("whitespaces")
 }
 
+/// Parses a single character with the Unicode `White_Space` property, such as the no-break space
+/// (`U+00A0`) or the ideographic space (`U+3000`).
+///
+/// This is the same parser as [`space`], which already accepts all Unicode whitespace, under a
+/// name which makes that explicit.
+///
+/// [`space`]: fn.space.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::unicode_space;
+/// assert_eq!(unicode_space().parse("\u{a0}"), Ok(('\u{a0}', "")));
+/// assert_eq!(unicode_space().parse("\u{3000}x"), Ok(('\u{3000}', "x")));
+/// assert!(unicode_space().parse("x").is_err());
+/// ```
+pub fn unicode_space<Input>() -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    space()
+}
+
+/// Skips over zero or more [`unicode_space`] characters, the same as [`spaces`].
+///
+/// [`unicode_space`]: fn.unicode_space.html
+/// [`spaces`]: fn.spaces.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::unicode_spaces;
+/// assert_eq!(unicode_spaces().parse(" \u{2003}\n\u{3000}x"), Ok(((), "x")));
+/// ```
+pub fn unicode_spaces<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    spaces()
+}
+
+/// Parses a single whitespace character which does not break a line, that is, any
+/// [`space`] except `\n`, `\r`, vertical tab, form feed and the Unicode line and paragraph
+/// separators.
+///
+/// [`space`]: fn.space.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::hspace;
+/// assert_eq!(hspace().parse("\t"), Ok(('\t', "")));
+/// assert_eq!(hspace().parse("\u{3000}"), Ok(('\u{3000}', "")));
+/// assert!(hspace().parse("\n").is_err());
+/// assert!(hspace().parse("\u{2028}").is_err());
+/// ```
+pub fn hspace<Input>() -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(|ch: char| {
+        ch.is_whitespace()
+            && !matches!(
+                ch,
+                '\n' | '\x0B' | '\x0C' | '\r' | '\u{85}' | '\u{2028}' | '\u{2029}'
+            )
+    })
+    .expected("horizontal whitespace")
+}
+
+/// Skips over zero or more [`hspace`] characters, stopping at line breaks.
+///
+/// [`hspace`]: fn.hspace.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::hspaces;
+/// assert_eq!(hspaces().parse(" \t\u{a0}x"), Ok(((), "x")));
+/// assert_eq!(hspaces().parse("  \n "), Ok(((), "\n ")));
+/// ```
+pub fn hspaces<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    skip_many(hspace()).expected("horizontal whitespaces")
+}
+
 /// Parses a newline character (`'\n'`).
 ///
 /// ```
@@ -122,6 +249,69 @@ where
     no_partial(satisfy(|ch: char| ch == '\r').with(newline())).expected("crlf newline")
 }
 
+#[derive(Copy, Clone)]
+pub struct Eol<Input>(PhantomData<Input>);
+
+impl<Input> Parser<Input> for Eol<Input>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = char;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<char, Input::Error> {
+        let position = input.position();
+        match uncons(input) {
+            ParseResult::PeekOk('\n') | ParseResult::CommitOk('\n') => {
+                return ParseResult::CommitOk('\n')
+            }
+            ParseResult::PeekOk('\r') | ParseResult::CommitOk('\r') => (),
+            ParseResult::PeekOk(_) | ParseResult::CommitOk(_) => {
+                return ParseResult::PeekErr(Input::Error::empty(position).into())
+            }
+            ParseResult::PeekErr(err) => return ParseResult::PeekErr(err),
+            ParseResult::CommitErr(err) => return ParseResult::CommitErr(err),
+        }
+        let after_cr = input.checkpoint();
+        match input.uncons() {
+            Ok('\n') => ParseResult::CommitOk('\n'),
+            // A `\n` may still follow once more input is available
+            Err(err) if input.is_partial() && err.is_unexpected_end_of_input() => {
+                ParseResult::CommitErr(Input::Error::from_error(input.position(), err))
+            }
+            _ => {
+                ctry!(input.reset(after_cr).committed());
+                ParseResult::CommitOk('\n')
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected("newline");
+    }
+}
+
+/// Parses a line ending, either `"\n"`, `"\r\n"` or a lone `"\r"`, returning `'\n'`.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::eol;
+/// assert_eq!(eol().parse("\n"), Ok(('\n', "")));
+/// assert_eq!(eol().parse("\r\nx"), Ok(('\n', "x")));
+/// assert_eq!(eol().parse("\rx"), Ok(('\n', "x")));
+/// assert_eq!(eol().parse("\r"), Ok(('\n', "")));
+/// assert!(eol().parse("x").is_err());
+/// ```
+pub fn eol<Input>() -> Eol<Input>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    Eol(PhantomData)
+}
+
 /// Parses a tab character (`'\t'`).
 ///
 /// ```
@@ -212,6 +402,146 @@ where
     satisfy(|ch: char| ch.is_alphabetic()).expected("letter")
 }
 
+/// Parses a character which may start a Unicode identifier (`XID_Start`).
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::xid_start;
+/// assert_eq!(xid_start().parse("λ"), Ok(('λ', "")));
+/// assert!(xid_start().parse("1").is_err());
+/// ```
+#[cfg(feature = "unicode-xid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-xid")))]
+pub fn xid_start<Input>() -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(UnicodeXID::is_xid_start).expected("identifier start")
+}
+
+/// Parses a character which may continue a Unicode identifier (`XID_Continue`).
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::xid_continue;
+/// assert_eq!(xid_continue().parse("1"), Ok(('1', "")));
+/// assert_eq!(xid_continue().parse("_"), Ok(('_', "")));
+/// assert!(xid_continue().parse("-").is_err());
+/// ```
+#[cfg(feature = "unicode-xid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-xid")))]
+pub fn xid_continue<Input>() -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(UnicodeXID::is_xid_continue).expected("identifier character")
+}
+
+/// Zero-copy parser which parses an identifier as defined by
+/// [UAX #31](https://www.unicode.org/reports/tr31/), that is, an `XID_Start` character followed by
+/// any number of `XID_Continue` characters.
+///
+/// Note that `_` is `XID_Continue` but not `XID_Start`, use `xid_start().or(char('_'))` together
+/// with [`recognize`] for languages which allow identifiers to start with `_`.
+///
+/// [`recognize`]: ../range/fn.recognize.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::identifier;
+/// assert_eq!(identifier().parse("größe = 1"), Ok(("größe", " = 1")));
+/// assert_eq!(identifier().parse("x_1+y"), Ok(("x_1", "+y")));
+/// assert!(identifier().parse("1x").is_err());
+/// ```
+#[cfg(feature = "unicode-xid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-xid")))]
+pub fn identifier<'a, Input>() -> impl Parser<Input, Output = &'a str>
+where
+    Input: RangeStream<Token = char, Range = &'a str>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    recognize_range((xid_start(), take_while(UnicodeXID::is_xid_continue))).expected("identifier")
+}
+
+/// Parses a character in the Unicode general category `category`.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::{category, GeneralCategory};
+/// assert_eq!(category(GeneralCategory::UppercaseLetter).parse("Ä"), Ok(('Ä', "")));
+/// assert!(category(GeneralCategory::UppercaseLetter).parse("ä").is_err());
+/// assert_eq!(category(GeneralCategory::CurrencySymbol).parse("€"), Ok(('€', "")));
+/// ```
+#[cfg(feature = "unicode-general-category")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-general-category")))]
+pub fn category<Input>(
+    category: GeneralCategory,
+) -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(move |ch: char| get_general_category(ch) == category).expected(category.abbreviation())
+}
+
+/// Parses a character in one of the Unicode letter categories (`Lu`, `Ll`, `Lt`, `Lm` or `Lo`).
+///
+/// Unlike [`letter`], which uses the derived `Alphabetic` property, this does not accept
+/// letter numbers such as `Ⅻ` or combining marks.
+///
+/// [`letter`]: fn.letter.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::alphabetic;
+/// assert_eq!(alphabetic().parse("ж"), Ok(('ж', "")));
+/// assert_eq!(alphabetic().parse("カ"), Ok(('カ', "")));
+/// assert!(alphabetic().parse("Ⅻ").is_err());
+/// assert!(alphabetic().parse("1").is_err());
+/// ```
+#[cfg(feature = "unicode-general-category")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-general-category")))]
+pub fn alphabetic<Input>() -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(|ch: char| {
+        matches!(
+            get_general_category(ch),
+            GeneralCategory::UppercaseLetter
+                | GeneralCategory::LowercaseLetter
+                | GeneralCategory::TitlecaseLetter
+                | GeneralCategory::ModifierLetter
+                | GeneralCategory::OtherLetter
+        )
+    })
+    .expected("letter")
+}
+
+/// Parses a character of the Unicode script `script`.
+///
+/// Characters which are used by several scripts, such as digits and punctuation, are in the
+/// `Common` script.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::{script, Script};
+/// assert_eq!(script(Script::Greek).parse("λ"), Ok(('λ', "")));
+/// assert!(script(Script::Greek).parse("l").is_err());
+/// ```
+#[cfg(feature = "unicode-script")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-script")))]
+pub fn script<Input>(script: Script) -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(move |ch: char| ch.script() == script).expected(script.full_name())
+}
+
 /// Parses an octal digit.
 ///
 /// ```
@@ -288,6 +618,715 @@ where
     tokens_cmp(s.chars(), cmp).map(move |_| s).expected(s)
 }
 
+/// Parses the string `s`, ignoring ASCII case differences. Returns `s` on success.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string_caseless;
+/// # fn main() {
+/// let result = string_caseless("select")
+///     .parse("SeLeCt *")
+///     .map(|x| x.0);
+/// assert_eq!(result, Ok("select"));
+/// # }
+/// ```
+pub fn string_caseless<'a, Input>(s: &'static str) -> impl Parser<Input, Output = &'a str>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    string_cmp(s, |l, r| l.eq_ignore_ascii_case(&r))
+}
+
+/// Parses a character which is equal to `c` after Unicode case folding.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::char_ci;
+/// assert_eq!(char_ci('ä').parse("Ä"), Ok(('Ä', "")));
+/// assert_eq!(char_ci('σ').parse("ς"), Ok(('ς', "")));
+/// assert!(char_ci('a').parse("b").is_err());
+/// ```
+#[cfg(feature = "unicode-case")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-case")))]
+pub fn char_ci<Input>(c: char) -> impl Parser<Input, Output = char, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(move |ch: char| iter::once(ch).default_caseless_match(iter::once(c))).expected(c)
+}
+
+#[cfg(feature = "unicode-case")]
+#[derive(Clone)]
+pub struct StringUnicodeCi<Input> {
+    s: &'static str,
+    folded: Vec<char>,
+    _marker: PhantomData<Input>,
+}
+
+#[cfg(feature = "unicode-case")]
+impl<Input> Parser<Input> for StringUnicodeCi<Input>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = &'static str;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<&'static str, Input::Error> {
+        let start = input.position();
+        let mut committed = false;
+        let mut matched = 0;
+        while matched < self.folded.len() {
+            match uncons(input) {
+                ParseResult::CommitOk(c) | ParseResult::PeekOk(c) => {
+                    // A single character may fold to several (`ß` to `ss`), all of which must
+                    // match
+                    for folded in iter::once(c).default_case_fold() {
+                        if self.folded.get(matched) != Some(&folded) {
+                            return if committed {
+                                ParseResult::CommitErr(Input::Error::from_error(
+                                    start,
+                                    StreamError::unexpected_token(c),
+                                ))
+                            } else {
+                                ParseResult::PeekErr(Input::Error::empty(start).into())
+                            };
+                        }
+                        matched += 1;
+                    }
+                    committed = true;
+                }
+                ParseResult::PeekErr(mut error) => {
+                    error.error.set_position(start);
+                    return if committed {
+                        ParseResult::CommitErr(error.into_error())
+                    } else {
+                        ParseResult::PeekErr(error)
+                    };
+                }
+                ParseResult::CommitErr(mut error) => {
+                    error.set_position(start);
+                    return ParseResult::CommitErr(error);
+                }
+            }
+        }
+        if committed {
+            ParseResult::CommitOk(self.s)
+        } else {
+            ParseResult::PeekOk(self.s)
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected(self.s);
+    }
+}
+
+/// Parses the string `s`, comparing it to the input using Unicode case folding so that, unlike
+/// [`string_caseless`], non-ASCII letters are matched regardless of their case as well. Returns
+/// `s` on success.
+///
+/// The input must have the same normalization form as `s`.
+///
+/// [`string_caseless`]: fn.string_caseless.html
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string_unicode_ci;
+/// # fn main() {
+/// assert_eq!(string_unicode_ci("straße").parse("STRASSE"), Ok(("straße", "")));
+/// assert_eq!(string_unicode_ci("Ωmega").parse("ωMEGA!"), Ok(("Ωmega", "!")));
+/// assert!(string_unicode_ci("straße").parse("strase").is_err());
+/// # }
+/// ```
+#[cfg(feature = "unicode-case")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-case")))]
+pub fn string_unicode_ci<Input>(s: &'static str) -> StringUnicodeCi<Input>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    StringUnicodeCi {
+        s,
+        folded: s.chars().default_case_fold().collect(),
+        _marker: PhantomData,
+    }
+}
+
+/// Parses the keyword `s`, failing if it is immediately followed by a letter, digit or `_` so that
+/// `if` does not match the start of the identifier `iffy`. Fails without consuming input if the
+/// keyword does not match.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::keyword;
+/// # fn main() {
+/// assert_eq!(keyword("if").parse("if x"), Ok(("if", " x")));
+/// assert_eq!(keyword("if").parse("if(x)"), Ok(("if", "(x)")));
+/// assert!(keyword("if").parse("iffy").is_err());
+/// assert_eq!(
+///     keyword("if").or(keyword("iffy")).parse("iffy"),
+///     Ok(("iffy", ""))
+/// );
+/// # }
+/// ```
+pub fn keyword<'a, Input>(s: &'static str) -> impl Parser<Input, Output = &'a str>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let identifier_char = satisfy(|c: char| c.is_alphanumeric() || c == '_');
+    attempt(string(s).skip(not_followed_by(identifier_char))).expected(s)
+}
+
+/// Describes what counts as trivia (whitespace and comments) between the tokens of a language.
+///
+/// By default only whitespace is trivia, comments are added with [`line_comment`][] and
+/// [`block_comment`][].
+///
+/// ```
+/// use combine::{many, Parser};
+/// use combine::parser::char::{letter, Lexer};
+///
+/// let lexer = Lexer::new().line_comment("//").block_comment("/*", "*/");
+/// let mut parser = lexer.trivia().with(many::<Vec<_>, _, _>(lexer.lex(letter())));
+/// assert_eq!(
+///     parser.parse(" /* start */ a // first\n b /**/c"),
+///     Ok((vec!['a', 'b', 'c'], ""))
+/// );
+/// ```
+///
+/// [`line_comment`]: struct.Lexer.html#method.line_comment
+/// [`block_comment`]: struct.Lexer.html#method.block_comment
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lexer {
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+impl Lexer {
+    /// Creates a `Lexer` which only treats whitespace as trivia.
+    pub fn new() -> Lexer {
+        Lexer::default()
+    }
+
+    /// Treats everything from `start` to the end of the line as trivia.
+    pub fn line_comment(mut self, start: &'static str) -> Lexer {
+        self.line_comment = Some(start);
+        self
+    }
+
+    /// Treats everything from `start` up to and including the next `end` as trivia.
+    pub fn block_comment(mut self, start: &'static str, end: &'static str) -> Lexer {
+        self.block_comment = Some((start, end));
+        self
+    }
+
+    /// Skips any trivia. Fails if a block comment is not closed.
+    pub fn trivia<Input>(self) -> impl Parser<Input, Output = ()>
+    where
+        Input: Stream<Token = char>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        let line_comment = match self.line_comment {
+            Some(start) => {
+                Either::Left(attempt(string(start)).with(skip_many(satisfy(|c: char| c != '\n'))))
+            }
+            None => Either::Right(satisfy(|_| false).map(|_| ())),
+        };
+        let block_comment = match self.block_comment {
+            Some((start, end)) => Either::Left(
+                attempt(string(start))
+                    .with(skip_until(attempt(string(end))))
+                    .with(string(end))
+                    .map(|_| ()),
+            ),
+            None => Either::Right(satisfy(|_| false).map(|_| ())),
+        };
+        skip_many(choice((skip_many1(space()), line_comment, block_comment)))
+            .expected("whitespaces")
+    }
+
+    /// Parses `parser` and then skips any trivia following it.
+    pub fn lex<Input, P>(self, parser: P) -> impl Parser<Input, Output = P::Output>
+    where
+        Input: Stream<Token = char>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+        P: Parser<Input>,
+    {
+        parser.skip(self.trivia())
+    }
+}
+
+/// Parses `parser` and then skips any whitespace following it. Use a [`Lexer`][] to skip comments
+/// as well.
+///
+/// ```
+/// use combine::{many1, Parser};
+/// use combine::parser::char::{digit, lex};
+///
+/// let mut parser = many1::<Vec<_>, _, _>(lex(digit()));
+/// assert_eq!(parser.parse("1 2\n3 "), Ok((vec!['1', '2', '3'], "")));
+/// ```
+///
+/// [`Lexer`]: struct.Lexer.html
+pub fn lex<Input, P>(parser: P) -> impl Parser<Input, Output = P::Output>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input>,
+{
+    Lexer::new().lex(parser)
+}
+
+/// Integer types which can be parsed by [`integer`][].
+///
+/// [`integer`]: fn.integer.html
+pub trait Integral: Copy + Default {
+    #[doc(hidden)]
+    const SIGNED: bool;
+
+    #[doc(hidden)]
+    fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self>;
+}
+
+macro_rules! integral {
+    ($signed: expr => $($t: ty),*) => {$(
+        impl Integral for $t {
+            const SIGNED: bool = $signed;
+
+            fn push_digit(self, radix: u32, digit: u32, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(radix as $t)?;
+                // Negative numbers are accumulated downwards so that `MIN` can be parsed
+                if negative {
+                    shifted.checked_sub(digit as $t)
+                } else {
+                    shifted.checked_add(digit as $t)
+                }
+            }
+        }
+    )*}
+}
+
+integral!(true => i8, i16, i32, i64, i128, isize);
+integral!(false => u8, u16, u32, u64, u128, usize);
+
+/// Parser created by [`integer`][].
+///
+/// [`integer`]: fn.integer.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Integer<T> {
+    radix: u32,
+    sign: bool,
+    separator: Option<char>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Integer<T>
+where
+    T: Integral,
+{
+    /// Sets the radix of the digits, the default radix is 10.
+    ///
+    /// Panics if `radix` is not in the range `2..=36`.
+    pub fn radix(mut self, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+        self.radix = radix;
+        self
+    }
+
+    /// Sets whether a leading `+` (or `-` for signed types) is accepted, the default is `true`.
+    pub fn sign(mut self, sign: bool) -> Self {
+        self.sign = sign;
+        self
+    }
+
+    /// Allows a single `separator` to appear between two digits, as in `1_000_000`. Separators
+    /// which are not followed by a digit (`1__0`, `1_`) end the integer before the separator.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    fn parser<Input>(&self) -> impl Parser<Input, Output = T>
+    where
+        Input: Stream<Token = char>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        let Integer {
+            radix,
+            sign,
+            separator,
+            ..
+        } = *self;
+        let negative_allowed = sign && T::SIGNED;
+        let digit = move || satisfy_map(move |c: char| c.to_digit(radix));
+
+        optional(satisfy(move |c: char| {
+            (sign && c == '+') || (negative_allowed && c == '-')
+        }))
+        .map(|c| c == Some('-'))
+        .then(move |negative| {
+            digit().then(move |first| {
+                let rest = choice((
+                    digit(),
+                    attempt(satisfy(move |c: char| Some(c) == separator).with(digit())),
+                ));
+                fold_many(
+                    T::default().push_digit(radix, first, negative),
+                    rest,
+                    move |acc: Option<T>, digit| {
+                        acc.and_then(|acc| acc.push_digit(radix, digit, negative))
+                    },
+                )
+            })
+        })
+        .and_then(|value: Option<T>| {
+            value.ok_or_else(|| StreamErrorFor::<Input>::message_static_message("number too large"))
+        })
+        .expected("integer")
+    }
+}
+
+impl<Input, T> Parser<Input> for Integer<T>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    T: Integral,
+{
+    type Output = T;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, <Input as StreamOnce>::Error> {
+        self.parser().parse_lazy(input)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser::<Input>().add_error(errors)
+    }
+}
+
+/// Parses an integer of type `T` in base 10 with an optional sign. The radix, sign and digit
+/// separators can be configured using the methods of [`Integer`][]. Fails with an error instead of
+/// overflowing if the number does not fit in `T`.
+///
+/// NOTE: The resulting parser does not support partial parsing.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::integer;
+/// assert_eq!(integer::<i32>().parse("-123 "), Ok((-123, " ")));
+/// assert_eq!(integer::<i8>().parse("-128"), Ok((-128, "")));
+/// assert!(integer::<i8>().parse("128").is_err());
+/// assert!(integer::<u32>().parse("-1").is_err());
+/// assert_eq!(integer::<u32>().radix(16).parse("fF"), Ok((255, "")));
+/// assert_eq!(integer::<u64>().separator('_').parse("1_000_000"), Ok((1_000_000, "")));
+/// assert_eq!(integer::<u64>().separator('_').parse("1_000_"), Ok((1_000, "_")));
+/// assert_eq!(integer::<u64>().separator('_').parse("1__0"), Ok((1, "__0")));
+/// assert!(integer::<i32>().sign(false).parse("+1").is_err());
+/// ```
+///
+/// [`Integer`]: struct.Integer.html
+pub fn integer<T>() -> Integer<T>
+where
+    T: Integral,
+{
+    Integer {
+        radix: 10,
+        sign: true,
+        separator: None,
+        _marker: PhantomData,
+    }
+}
+
+/// Configuration for [`string_literal`][].
+///
+/// The default configuration parses double quoted strings using `\` as the escape character
+/// with the escapes `\n`, `\r`, `\t`, `\0`, `\\`, `\"` and `\'`, hexadecimal escapes
+/// (`\x7F`) and unicode escapes (`\u{1F600}`). Raw newlines are not allowed.
+///
+/// [`string_literal`]: fn.string_literal.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StringLiteral {
+    quote: char,
+    escape: char,
+    escapes: &'static [(char, char)],
+    hex_escapes: bool,
+    unicode_escapes: bool,
+    newlines: bool,
+}
+
+#[cfg(feature = "std")]
+impl Default for StringLiteral {
+    fn default() -> Self {
+        StringLiteral {
+            quote: '"',
+            escape: '\\',
+            escapes: &[
+                ('n', '\n'),
+                ('r', '\r'),
+                ('t', '\t'),
+                ('0', '\0'),
+                ('\\', '\\'),
+                ('"', '"'),
+                ('\'', '\''),
+            ],
+            hex_escapes: true,
+            unicode_escapes: true,
+            newlines: false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl StringLiteral {
+    /// Creates the default configuration.
+    pub fn new() -> StringLiteral {
+        StringLiteral::default()
+    }
+
+    /// Sets the character which starts and ends the string.
+    pub fn quote(mut self, quote: char) -> StringLiteral {
+        self.quote = quote;
+        self
+    }
+
+    /// Sets the character which starts an escape sequence.
+    pub fn escape(mut self, escape: char) -> StringLiteral {
+        self.escape = escape;
+        self
+    }
+
+    /// Sets the single character escapes as pairs of the character following the escape
+    /// character and the character it produces.
+    pub fn escapes(mut self, escapes: &'static [(char, char)]) -> StringLiteral {
+        self.escapes = escapes;
+        self
+    }
+
+    /// Enables or disables escapes of the form `\xNN`, where `NN` are two hexadecimal digits.
+    pub fn hex_escapes(mut self, enabled: bool) -> StringLiteral {
+        self.hex_escapes = enabled;
+        self
+    }
+
+    /// Enables or disables escapes of the form `\u{N}`, where `N` are one to six hexadecimal
+    /// digits.
+    pub fn unicode_escapes(mut self, enabled: bool) -> StringLiteral {
+        self.unicode_escapes = enabled;
+        self
+    }
+
+    /// Allows or disallows unescaped newlines inside the string.
+    pub fn newlines(mut self, allowed: bool) -> StringLiteral {
+        self.newlines = allowed;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+fn char_from_hex<R, E>(digits: String) -> Result<char, E>
+where
+    E: StreamError<char, R>,
+{
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(std::char::from_u32)
+        .ok_or_else(|| E::message_static_message("invalid character escape"))
+}
+
+/// Parses a quoted string literal as described by `config`, returning the unescaped contents.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::{string_literal, StringLiteral};
+///
+/// let mut parser = string_literal(StringLiteral::new());
+/// assert_eq!(
+///     parser.parse(r#""a\"b\n\x41\u{1F600}" rest"#),
+///     Ok(("a\"b\nA\u{1F600}".to_string(), " rest"))
+/// );
+/// assert!(parser.parse("\"a\nb\"").is_err());
+///
+/// let mut parser = string_literal(StringLiteral::new().quote('\'').escapes(&[('\'', '\'')]));
+/// assert_eq!(parser.parse(r"'it\'s'"), Ok(("it's".to_string(), "")));
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn string_literal<Input>(config: StringLiteral) -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let StringLiteral {
+        quote,
+        escape,
+        escapes,
+        hex_escapes,
+        unicode_escapes,
+        newlines,
+    } = config;
+
+    let normal = satisfy(move |c: char| c != quote && c != escape && (newlines || c != '\n'));
+
+    let simple = satisfy_map(move |c: char| {
+        escapes
+            .iter()
+            .find(|&&(from, _)| from == c)
+            .map(|&(_, to)| to)
+    });
+    let hex = satisfy(move |c: char| hex_escapes && c == 'x')
+        .with(count_min_max(2, 2, hex_digit()))
+        .and_then(char_from_hex::<Input::Range, StreamErrorFor<Input>>);
+    let unicode = satisfy(move |c: char| unicode_escapes && c == 'u')
+        .with(between(
+            token('{'),
+            token('}'),
+            count_min_max(1, 6, hex_digit()),
+        ))
+        .and_then(char_from_hex::<Input::Range, StreamErrorFor<Input>>);
+    let escape_sequence =
+        token(escape).with(choice((simple, hex, unicode)).expected("escape sequence"));
+
+    between(
+        token(quote),
+        token(quote),
+        many(choice((normal, escape_sequence))),
+    )
+    .expected("string literal")
+}
+
+// Collects the tokens of a float into a `String` for both `char` and `u8` streams
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct FloatText(String);
+
+#[cfg(feature = "std")]
+impl<T> Extend<T> for FloatText
+where
+    T: Into<char>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.0.extend(iter.into_iter().map(Into::into))
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) trait Float: std::str::FromStr {
+    fn is_infinite(&self) -> bool;
+}
+
+#[cfg(feature = "std")]
+impl Float for f32 {
+    fn is_infinite(&self) -> bool {
+        f32::is_infinite(*self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Float for f64 {
+    fn is_infinite(&self) -> bool {
+        f64::is_infinite(*self)
+    }
+}
+
+// Parses `[+-]? (digits ('.' digits?)? | '.' digits) ([eE] [+-]? digits)?` on streams of either
+// `char` or `u8`
+#[cfg(feature = "std")]
+pub(crate) fn float_value<Input, T>() -> impl Parser<Input, Output = T>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    T: Float,
+{
+    fn is<Input>(
+        f: fn(char) -> bool,
+    ) -> impl Parser<Input, Output = Input::Token, PartialState = ()>
+    where
+        Input: Stream,
+        Input::Token: Into<char>,
+    {
+        satisfy(move |c: Input::Token| f(c.into()))
+    }
+    let digits = || skip_many1(is(|c| c.is_ascii_digit()));
+    let sign = || optional(is(|c| c == '+' || c == '-'));
+
+    recognize::<FloatText, _, _>((
+        sign(),
+        choice((
+            (
+                digits(),
+                optional((is(|c| c == '.'), skip_many(is(|c| c.is_ascii_digit())))),
+            )
+                .map(|_| ()),
+            (is(|c| c == '.'), digits()).map(|_| ()),
+        )),
+        optional(attempt((is(|c| c == 'e' || c == 'E'), sign(), digits()))),
+    ))
+    .and_then(|text| match text.0.parse::<T>() {
+        Ok(value) if !value.is_infinite() => Ok(value),
+        Ok(_) => Err(StreamErrorFor::<Input>::message_static_message(
+            "number too large",
+        )),
+        Err(_) => Err(StreamErrorFor::<Input>::message_static_message(
+            "invalid float",
+        )),
+    })
+    .expected("float")
+}
+
+/// Parses a decimal floating point number, such as `-1.5e3`, into an `f32`. Fails if the number is
+/// too large to be represented.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::float;
+/// assert_eq!(float().parse("1.5e3!"), Ok((1500.0, "!")));
+/// assert_eq!(float().parse("-.5"), Ok((-0.5, "")));
+/// assert!(float().parse("1e39").is_err());
+/// assert!(float().parse("e1").is_err());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn float<Input>() -> impl Parser<Input, Output = f32>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    float_value()
+}
+
+/// Parses a decimal floating point number, such as `-1.5e3`, into an `f64`. Fails if the number is
+/// too large to be represented.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::double;
+/// assert_eq!(double().parse("0.1"), Ok((0.1, "")));
+/// assert_eq!(double().parse("2e"), Ok((2.0, "e")));
+/// assert!(double().parse("1e309").is_err());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn double<Input>() -> impl Parser<Input, Output = f64>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    float_value()
+}
+
 #[cfg(all(feature = "std", test))]
 mod tests {
 
@@ -332,4 +1371,82 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn string_caseless_error() {
+        let result = string_caseless("abc").easy_parse(position::Stream::new("xBC"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![Error::Unexpected('x'.into()), Error::Expected("abc".into())],
+            })
+        );
+    }
+
+    #[test]
+    fn lexer_unclosed_block_comment() {
+        let lexer = Lexer::new().block_comment("/*", "*/");
+        let mut parser = lexer.lex(char('a'));
+        assert_eq!(
+            parser
+                .easy_parse(position::Stream::new("a /* b */"))
+                .map(|(c, rest)| (c, rest.input)),
+            Ok(('a', ""))
+        );
+
+        let result = parser.easy_parse(position::Stream::new("a /* b"));
+        assert_eq!(
+            result.map_err(|err| err.position),
+            Err(SourcePosition { line: 1, column: 7 })
+        );
+    }
+
+    #[test]
+    fn string_literal_errors() {
+        let mut parser = string_literal(StringLiteral::new());
+        assert_eq!(
+            parser.easy_parse(position::Stream::new(r#""a\q""#)),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 4 },
+                errors: vec![
+                    Error::Unexpected('q'.into()),
+                    Error::Expected("escape sequence".into()),
+                ],
+            })
+        );
+        assert!(parser
+            .easy_parse(position::Stream::new(r#""\u{D800}""#))
+            .is_err());
+
+        let mut parser = string_literal(StringLiteral::new().unicode_escapes(false));
+        assert!(parser.easy_parse(r#""\u{41}""#).is_err());
+
+        let mut parser = string_literal(StringLiteral::new().newlines(true));
+        assert_eq!(parser.easy_parse("\"a\nb\""), Ok(("a\nb".to_string(), "")));
+    }
+
+    #[test]
+    fn integer_errors() {
+        let result = integer::<u8>().easy_parse(position::Stream::new("256"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![Error::Message("number too large".into())],
+            })
+        );
+
+        let result = integer::<i32>().easy_parse(position::Stream::new("x"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Unexpected('x'.into()),
+                    Error::Expected("integer".into())
+                ],
+            })
+        );
+    }
 }
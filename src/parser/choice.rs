@@ -3,11 +3,14 @@
 
 use crate::{
     error::{
-        ParseError,
+        ErrorInfo, ParseError,
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
-    parser::ParseMode,
+    parser::{
+        reflect::{visit, Node, Visitor},
+        FirstMode, ParseMode,
+    },
     ErrorOffset, Parser, Stream, StreamOnce,
 };
 
@@ -94,6 +97,11 @@ pub trait ChoiceParser<Input: Stream> {
         Self: Sized;
 
     fn add_error_choice(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>);
+
+    /// Describes each alternative to `visitor`.
+    fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visitor.opaque()
+    }
 }
 
 impl<'a, Input, P> ChoiceParser<Input> for &'a mut P
@@ -125,6 +133,10 @@ where
     fn add_error_choice(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
         (**self).add_error_choice(error)
     }
+
+    fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+        (**self).reflect_choice(visitor)
+    }
 }
 
 macro_rules! merge {
@@ -146,7 +158,19 @@ macro_rules! do_choice {
         ( )
         $($parser: ident $error: ident)+
     ) => { {
+        // Keep the end of input errors pending if all of the parsers left them pending, otherwise
+        // they need to be added before the errors are merged
+        let all_pending = true $(&& $error.pending().is_some())+;
+        let mut pending = None;
+        $(
+            if all_pending {
+                pending = pending.or($error.pending());
+            } else {
+                $error.add_pending();
+            }
+        )+
         let mut error = Tracked::from(merge!($($error)+));
+        error.set_pending(pending);
         // If offset != 1 then the nested parser is a sequence of parsers where 1 or
         // more parsers returned `PeekOk` before the parser finally failed with
         // `PeekErr`. Since we lose the offsets of the nested parsers when we merge
@@ -186,7 +210,7 @@ macro_rules! do_choice {
                 }
                 CommitErr(err)
             }
-            PeekErr($head) => {
+            PeekErr(mut $head) => {
                 ctry!($input.reset($before.clone()).committed());
                 do_choice!(
                     $input
@@ -296,11 +320,18 @@ macro_rules! tuple_choice_parser_inner {
                     )+
                 }
             }
+
+            fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+                let ($(ref mut $id,)+) = *self;
+                $(
+                    $id.reflect(visitor);
+                )+
+            }
         }
     }
 }
 
-tuple_choice_parser!(A B C D E F G H I J K L M N O P Q R S T U V X Y Z);
+tuple_choice_parser!(A B C D E F G H I J K L M N O P Q R S T U V W X Y Z A1 A2 A3 A4 A5 A6);
 
 macro_rules! array_choice_parser {
     ($($t: tt)+) => {
@@ -337,6 +368,10 @@ macro_rules! array_choice_parser {
             ) {
                 self[..].add_error_choice(error)
             }
+
+            fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+                self[..].reflect_choice(visitor)
+            }
         }
         )+
     };
@@ -349,6 +384,53 @@ array_choice_parser!(
     30 31 32
     );
 
+macro_rules! owned_slice_choice_parser {
+    ($($ty: ty),+) => {
+        $(
+        #[cfg(feature = "std")]
+        impl<Input, P> ChoiceParser<Input> for $ty
+        where
+            Input: Stream,
+            P: Parser<Input>,
+        {
+
+            type Output = P::Output;
+            type PartialState = <[P] as ChoiceParser<Input>>::PartialState;
+
+            parse_mode_choice!(Input);
+            #[inline]
+            fn parse_mode_choice<M>(
+                &mut self,
+                mode: M,
+                input: &mut Input,
+                state: &mut Self::PartialState,
+            ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+            where
+                M: ParseMode,
+            {
+                if mode.is_first() {
+                    self[..].parse_first(input, state)
+                } else {
+                    self[..].parse_partial(input, state)
+                }
+            }
+            fn add_error_choice(
+                &mut self,
+                error: &mut Tracked<<Input as StreamOnce>::Error>
+            ) {
+                self[..].add_error_choice(error)
+            }
+
+            fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+                self[..].reflect_choice(visitor)
+            }
+        }
+        )+
+    };
+}
+
+owned_slice_choice_parser!(Vec<P>, Box<[P]>);
+
 #[derive(Copy, Clone)]
 pub struct Choice<P>(P);
 
@@ -379,6 +461,11 @@ where
         self.0.add_error_choice(error);
         error.offset.0 = before.saturating_sub(1);
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let choices = &mut self.0;
+        visit(visitor, Node::Choice, |v| choices.reflect_choice(v))
+    }
 }
 
 fn slice_parse_mode<Input, P, M>(
@@ -432,10 +519,7 @@ where
                             self_[i - 1].add_error(&mut prev_err);
                             last_parser_having_non_1_offset = i;
                         }
-                        Some(Tracked {
-                            error: prev_err.error.merge(err.error),
-                            offset: err.offset,
-                        })
+                        Some(prev_err.merge(err))
                     }
                 };
             }
@@ -515,11 +599,22 @@ where
             }
         }
     }
+
+    fn reflect_choice(&mut self, visitor: &mut dyn Visitor<Input>) {
+        for p in self {
+            p.reflect(visitor);
+        }
+    }
 }
 
-/// Takes a tuple, a slice or an array of parsers and tries to apply them each in order.
+/// Takes a tuple, a slice, an array or a `Vec` of parsers and tries to apply them each in order.
 /// Fails if all the parsers fails or if an applied parser consumes input before failing.
 ///
+/// Tuples and arrays of up to 32 parsers are supported. The [`choice!`][] macro can be used for
+/// any number of parsers.
+///
+/// [`choice!`]: ../../macro.choice.html
+///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
@@ -556,6 +651,46 @@ where
     Choice(ps)
 }
 
+/// Collects the parsers of `parsers` and tries to apply them each in order, like [`choice`].
+///
+/// Useful when the alternatives are only known at runtime. Since every parser must have the same
+/// type, parsers of different types can be turned into trait objects with [`Parser::boxed`]
+/// first.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, letter, string};
+/// # use combine::parser::choice::choice_iter;
+/// # use combine::parser::combinator::no_partial;
+/// # fn main() {
+/// let keywords = vec!["let", "in", "if"];
+/// let mut parser = choice_iter(keywords.iter().map(|&keyword| attempt(string(keyword))));
+/// assert_eq!(parser.parse("if"), Ok(("if", "")));
+/// assert!(parser.parse("else").is_err());
+///
+/// let rules: Vec<Box<dyn Parser<&str, Output = char, PartialState = ()>>> = vec![
+///     no_partial(digit()).boxed(),
+///     no_partial(letter()).boxed(),
+/// ];
+/// let mut parser = choice_iter(rules);
+/// assert_eq!(parser.parse("a1"), Ok(('a', "1")));
+/// # }
+/// ```
+///
+/// [`choice`]: fn.choice.html
+/// [`Parser::boxed`]: ../trait.Parser.html#method.boxed
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn choice_iter<Input, I>(parsers: I) -> Choice<Vec<I::Item>>
+where
+    Input: Stream,
+    I: IntoIterator,
+    I::Item: Parser<Input>,
+{
+    Choice(parsers.into_iter().collect())
+}
+
 #[derive(Copy, Clone)]
 pub struct Or<P1, P2>(Choice<(P1, P2)>);
 impl<Input, O, P1, P2> Parser<Input> for Or<P1, P2>
@@ -587,6 +722,10 @@ where
             self.0.add_error(errors);
         }
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        self.0.reflect(visitor)
+    }
 }
 
 /// Equivalent to [`p1.or(p2)`].
@@ -663,6 +802,11 @@ where
     }
 
     forward_parser!(Input, add_error parser_count, 0);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let parser = &mut self.0;
+        visit(visitor, Node::Optional, |v| parser.reflect(v))
+    }
 }
 
 /// Parses `parser` and outputs `Some(value)` if it succeeds, `None` if it fails without
@@ -687,6 +831,293 @@ where
     Optional(parser)
 }
 
+#[derive(Copy, Clone)]
+pub struct OptionalOr<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for OptionalOr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut() -> P::Output,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let before = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(x) => PeekOk(x),
+            CommitOk(x) => CommitOk(x),
+            CommitErr(err) => CommitErr(err),
+            PeekErr(_) => {
+                ctry!(input.reset(before).committed());
+                PeekOk((self.1)())
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error parser_count, 0);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let parser = &mut self.0;
+        visit(visitor, Node::Optional, |v| parser.reflect(v))
+    }
+}
+
+/// Parses `parser` and outputs its value if it succeeds or the value returned by `default` if it
+/// fails without consuming any input. Fails if `parser` fails after having committed some input.
+///
+/// Since `default` is called each time a value is needed it does not need to be `Clone`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, string};
+/// # fn main() {
+/// let mut parser = optional_or(string("hello"), || "default");
+/// assert_eq!(parser.parse("hello"), Ok(("hello", "")));
+/// assert_eq!(parser.parse("world"), Ok(("default", "world")));
+/// assert!(parser.parse("heya").is_err());
+///
+/// let mut parser = optional_or(many1(digit()), Vec::new);
+/// assert_eq!(parser.parse("abc"), Ok((vec![], "abc")));
+/// # }
+/// ```
+pub fn optional_or<Input, P, F>(parser: P, default: F) -> OptionalOr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut() -> P::Output,
+{
+    OptionalOr(parser, default)
+}
+
+#[derive(Copy, Clone)]
+pub struct Permutation<P>(P);
+
+#[doc(hidden)]
+pub struct PermutationSlot<O, S> {
+    value: Option<O>,
+    resume: bool,
+    state: S,
+}
+
+impl<O, S> Default for PermutationSlot<O, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        PermutationSlot {
+            value: None,
+            resume: false,
+            state: S::default(),
+        }
+    }
+}
+
+macro_rules! permutation_parser {
+    (($head: ident $head_slot: ident)) => {
+        permutation_parser_inner!(($head $head_slot));
+    };
+    (($head: ident $head_slot: ident) $(($id: ident $slot: ident))+) => {
+        permutation_parser_inner!(($head $head_slot) $(($id $slot))+);
+        permutation_parser!($(($id $slot))+);
+    };
+}
+
+macro_rules! permutation_parser_inner {
+    ($(($id: ident $slot: ident))+) => {
+        #[allow(non_snake_case)]
+        impl<Input $(, $id)+> Parser<Input> for Permutation<($($id,)+)>
+        where
+            Input: Stream,
+            $($id: Parser<Input>),+
+        {
+            type Output = ($($id::Output,)+);
+            type PartialState = ($(PermutationSlot<$id::Output, $id::PartialState>,)+);
+
+            parse_mode!(Input);
+            #[inline]
+            fn parse_mode_impl<Mode>(
+                &mut self,
+                mode: Mode,
+                input: &mut Input,
+                state: &mut Self::PartialState,
+            ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+            where
+                Mode: ParseMode,
+            {
+                if mode.is_first() {
+                    *state = Default::default();
+                }
+                let ($(ref mut $id,)+) = self.0;
+                let ($(ref mut $slot,)+) = *state;
+
+                let mut committed = false;
+                $(
+                    committed |= $slot.value.is_some() || $slot.resume;
+                )+
+
+                // Finish the parser which were interrupted on the previous call
+                $(
+                    if $slot.resume {
+                        match $id.parse_mode(mode, input, &mut $slot.state) {
+                            CommitOk(x) | PeekOk(x) => {
+                                $slot.value = Some(x);
+                                $slot.resume = false;
+                            }
+                            CommitErr(err) => return CommitErr(err),
+                            PeekErr(err) => return CommitErr(err.into_error()),
+                        }
+                    }
+                )+
+
+                loop {
+                    let before_position = input.position();
+                    let before = input.checkpoint();
+                    let mut progressed = false;
+                    let mut error: Option<<Input as StreamOnce>::Error> = None;
+                    $(
+                        if !progressed && $slot.value.is_none() {
+                            $slot.state = Default::default();
+                            match $id.parse_mode(FirstMode, input, &mut $slot.state) {
+                                CommitOk(x) => {
+                                    $slot.value = Some(x);
+                                    committed = true;
+                                    progressed = true;
+                                }
+                                PeekOk(x) => {
+                                    $slot.value = Some(x);
+                                    progressed = true;
+                                }
+                                CommitErr(err) => {
+                                    // If the input is the same this is a partial parse which
+                                    // we cannot commit to, so try all the missing parsers again
+                                    // on the next call
+                                    $slot.resume = input.position() != before_position;
+                                    return CommitErr(err);
+                                }
+                                PeekErr(mut err) => {
+                                    ctry!(input.reset(before.clone()).committed());
+                                    // As in `choice`, errors of parsers which returned `PeekOk`
+                                    // before failing must be added before the errors are merged
+                                    if err.offset != ErrorOffset(1) {
+                                        $id.add_error(&mut err);
+                                    }
+                                    error = Some(match error {
+                                        Some(prev) => prev.merge(err.into_error()),
+                                        None => err.into_error(),
+                                    });
+                                }
+                            }
+                        }
+                    )+
+                    if progressed {
+                        continue;
+                    }
+                    let error = match error {
+                        Some(error) => error,
+                        // Every parser has produced a value
+                        None => break,
+                    };
+                    let all_missing = true $(&& $slot.value.is_none())+;
+                    if !committed && all_missing {
+                        // Every parser is still missing so `add_error` can add the errors lazily
+                        return PeekErr(error.into());
+                    }
+                    let mut error = if committed {
+                        let mut unexpected = Input::Error::empty(input.position());
+                        if let Ok(t) = input.uncons() {
+                            unexpected.add(StreamError::unexpected_token(t));
+                        }
+                        Tracked::from(unexpected.merge(error))
+                    } else {
+                        Tracked::from(error)
+                    };
+                    $(
+                        if $slot.value.is_none() {
+                            error.offset = ErrorOffset(1);
+                            $id.add_error(&mut error);
+                        }
+                    )+
+                    return if committed {
+                        CommitErr(error.into_error())
+                    } else {
+                        error.offset = ErrorOffset(0);
+                        PeekErr(error)
+                    };
+                }
+
+                let value = ($($slot.value.take().unwrap(),)+);
+                if committed {
+                    CommitOk(value)
+                } else {
+                    PeekOk(value)
+                }
+            }
+
+            fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+                if error.offset != ErrorOffset(0) {
+                    let ($(ref mut $id,)+) = self.0;
+                    // Every parser may appear first so add the errors of all of them
+                    $(
+                        error.offset = ErrorOffset(1);
+                        $id.add_error(error);
+                    )+
+                    error.offset = ErrorOffset(0);
+                }
+            }
+        }
+    };
+}
+
+permutation_parser!(
+    (A a) (B b) (C c) (D d) (E e) (F f) (G g) (H h) (I i) (J j) (K k) (L l)
+);
+
+/// Takes a tuple of parsers and parses each of them exactly once, in any order. The values are
+/// returned in the order the parsers were declared in, regardless of the order they were parsed
+/// in.
+///
+/// Each time, the parsers which have not yet produced a value are tried in the order they are
+/// declared in. If none of them can parse the input the error reports what each of the missing
+/// parsers expected. As with `choice`, a parser which fails after committing input fails the
+/// entire `permutation`.
+///
+/// NOTE: A parser which succeeds without consuming any input (such as `optional(..)`) will always
+/// succeed the first time it is tried, so it will not be able to match at a later position.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit, letter};
+/// # use combine::parser::choice::permutation;
+/// # fn main() {
+/// let mut parser = permutation((char('a'), digit(), char('b')));
+/// assert_eq!(parser.parse("a1b"), Ok((('a', '1', 'b'), "")));
+/// assert_eq!(parser.parse("b1a"), Ok((('a', '1', 'b'), "")));
+/// assert_eq!(parser.parse("1ab!"), Ok((('a', '1', 'b'), "!")));
+/// assert!(parser.parse("a1").is_err());
+/// assert!(parser.parse("ab").is_err());
+/// # }
+/// ```
+pub fn permutation<Input, P>(ps: P) -> Permutation<P>
+where
+    Input: Stream,
+    Permutation<P>: Parser<Input>,
+{
+    Permutation(ps)
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! parse_mode_dispatch {
@@ -834,6 +1265,194 @@ macro_rules! dispatch {
     }
 }
 
+/// The keys of a `DispatchTable` which are added as expected errors if its table does not select
+/// a parser.
+pub trait DispatchKeys<Input: Stream> {
+    fn add_expected(&self, errors: &mut Tracked<<Input as StreamOnce>::Error>);
+}
+
+impl<Input> DispatchKeys<Input> for ()
+where
+    Input: Stream,
+{
+    fn add_expected(&self, _errors: &mut Tracked<<Input as StreamOnce>::Error>) {}
+}
+
+impl<Input, T> DispatchKeys<Input> for &'_ [T]
+where
+    Input: Stream,
+    T: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    fn add_expected(&self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        for key in self.iter() {
+            errors.error.add_expected(key);
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct DispatchTable<S, F, K = ()> {
+    selector: S,
+    table: F,
+    keys: K,
+    // Set if the last parse failed because `table` did not select a parser
+    unknown_key: bool,
+}
+
+impl<S, F> DispatchTable<S, F> {
+    /// Sets the keys which the table selects a parser for. If the table returns `None` they are
+    /// reported as the expected input.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::easy::Error;
+    /// # use combine::parser::char::string;
+    /// # use combine::parser::choice::dispatch;
+    /// # fn main() {
+    /// let mut parser = dispatch(any(), |c| match c {
+    ///     'l' => Some(string("let")),
+    ///     'i' => Some(string("in")),
+    ///     _ => None,
+    /// })
+    /// .keys(&['l', 'i'][..]);
+    /// assert_eq!(
+    ///     parser.easy_parse("x").map_err(|err| err.errors),
+    ///     Err(vec![
+    ///         Error::Unexpected('x'.into()),
+    ///         Error::Expected('l'.into()),
+    ///         Error::Expected('i'.into()),
+    ///     ])
+    /// );
+    /// # }
+    /// ```
+    pub fn keys<K>(self, keys: K) -> DispatchTable<S, F, K> {
+        DispatchTable {
+            selector: self.selector,
+            table: self.table,
+            keys,
+            unknown_key: false,
+        }
+    }
+}
+
+impl<Input, S, F, P, K> Parser<Input> for DispatchTable<S, F, K>
+where
+    Input: Stream,
+    S: Parser<Input>,
+    F: FnMut(S::Output) -> Option<P>,
+    P: Parser<Input>,
+    K: DispatchKeys<Input>,
+{
+    type Output = P::Output;
+    type PartialState = (Option<P>, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut branch, ref mut branch_state) = *state;
+
+        if mode.is_first() || branch.is_none() {
+            let before = input.checkpoint();
+            let result = self.selector.parse_lazy(input);
+            ctry!(input.reset(before).committed());
+            self.unknown_key = false;
+            let (key, _) = ctry!(result);
+            match (self.table)(key) {
+                Some(parser) => *branch = Some(parser),
+                None => {
+                    self.unknown_key = true;
+                    return PeekErr(Input::Error::empty(input.position()).into());
+                }
+            }
+            *branch_state = Default::default();
+            mode.set_first();
+        }
+
+        let result = branch
+            .as_mut()
+            .unwrap()
+            .parse_committed_mode(mode, input, branch_state);
+        match result {
+            // Keep the branch so that it can be resumed on the next call
+            CommitErr(err) => CommitErr(err),
+            PeekErr(mut err) => {
+                branch.take().unwrap().add_error(&mut err);
+                err.offset = ErrorOffset(0);
+                PeekErr(err)
+            }
+            result => {
+                *branch = None;
+                result
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if errors.offset != ErrorOffset(0) {
+            if self.unknown_key {
+                self.keys.add_expected(errors);
+            } else {
+                self.selector.add_error(errors);
+            }
+        }
+    }
+}
+
+/// Peeks at the input with `selector` and then passes its output to `table` which selects the
+/// one parser that should parse the input.
+///
+/// `selector` does not consume any input, so the selected parser sees the same input as the
+/// selector did. If `table` returns `None` the parser fails without consuming input, reporting the
+/// keys given to [`DispatchTable::keys`] as expected.
+///
+/// Unlike `choice((attempt(a), attempt(b), ...))` at most one branch is ever tried, which avoids
+/// the cost of backtracking in grammars where a single token decides the branch. If the branches
+/// have different types, [`dispatch!`] can be used inside `table`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string;
+/// # use combine::parser::choice::dispatch;
+/// # fn main() {
+/// let mut parser = dispatch(any(), |c| match c {
+///     'l' => Some(string("let")),
+///     'i' => Some(string("in")),
+///     _ => None,
+/// });
+/// assert_eq!(parser.parse("let x"), Ok(("let", " x")));
+/// assert_eq!(parser.parse("in"), Ok(("in", "")));
+/// assert!(parser.parse("if").is_err());
+/// assert!(parser.parse("x").is_err());
+/// # }
+/// ```
+///
+/// [`dispatch!`]: ../../macro.dispatch.html
+/// [`DispatchTable::keys`]: struct.DispatchTable.html#method.keys
+pub fn dispatch<Input, S, F, P>(selector: S, table: F) -> DispatchTable<S, F>
+where
+    Input: Stream,
+    S: Parser<Input>,
+    F: FnMut(S::Output) -> Option<P>,
+    P: Parser<Input>,
+{
+    DispatchTable {
+        selector,
+        table,
+        keys: (),
+        unknown_key: false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -845,4 +1464,92 @@ mod tests {
     fn choice_single_parser() {
         assert!(choice((any(),),).easy_parse("a").is_ok());
     }
+
+    #[test]
+    fn choice_vec() {
+        use crate::{parser::token::token, stream::easy};
+
+        let mut parser = choice(vec![token('a'), token('b')]);
+        assert_eq!(parser.easy_parse("b"), Ok(('b', "")));
+        assert_eq!(
+            parser.easy_parse("c").map_err(|err| err.errors),
+            Err(vec![
+                easy::Error::Unexpected('c'.into()),
+                easy::Error::Expected('a'.into()),
+                easy::Error::Expected('b'.into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn dispatch_errors() {
+        use crate::{
+            parser::{char::string, token::one_of},
+            stream::easy,
+        };
+
+        let mut parser = dispatch(one_of("li".chars()), |c| match c {
+            'l' => Some(string("let")),
+            _ => None,
+        });
+        assert_eq!(
+            parser.easy_parse("x").map_err(|err| err.errors),
+            Err(vec![
+                easy::Error::Unexpected('x'.into()),
+                easy::Error::Expected('l'.into()),
+                easy::Error::Expected('i'.into()),
+            ])
+        );
+        assert_eq!(
+            parser.easy_parse("i").map_err(|err| err.errors),
+            Err(vec![easy::Error::Unexpected('i'.into())])
+        );
+        assert_eq!(
+            parser.easy_parse("lex").map_err(|err| err.errors),
+            Err(vec![easy::Error::Unexpected('x'.into())])
+        );
+
+        let mut parser = parser.keys(&['l'][..]);
+        assert_eq!(
+            parser.easy_parse("i").map_err(|err| err.errors),
+            Err(vec![
+                easy::Error::Unexpected('i'.into()),
+                easy::Error::Expected('l'.into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn permutation_reports_missing_parsers() {
+        use crate::{
+            parser::char::{char, digit},
+            stream::{easy, position::SourcePosition},
+        };
+
+        let mut parser = permutation((char('a'), digit(), char('b')));
+        assert_eq!(
+            parser
+                .easy_parse(crate::stream::position::Stream::new("a!"))
+                .map_err(|err| err.errors),
+            Err(vec![
+                easy::Error::Unexpected('!'.into()),
+                easy::Error::Expected("digit".into()),
+                easy::Error::Expected('b'.into()),
+            ])
+        );
+        assert_eq!(
+            parser
+                .easy_parse(crate::stream::position::Stream::new("!"))
+                .map_err(|err| (err.position, err.errors)),
+            Err((
+                SourcePosition { line: 1, column: 1 },
+                vec![
+                    easy::Error::Unexpected('!'.into()),
+                    easy::Error::Expected('a'.into()),
+                    easy::Error::Expected("digit".into()),
+                    easy::Error::Expected('b'.into()),
+                ]
+            ))
+        );
+    }
 }
@@ -2,16 +2,23 @@
 
 use crate::{
     error::{
-        Info, ParseError,
+        ParseError,
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
-    lib::{fmt, marker::PhantomData, mem, str},
-    parser::ParseMode,
-    stream::{input_at_eof, ResetStream, Stream, StreamErrorFor, StreamOnce},
+    lib::{fmt, marker::PhantomData, mem, ops, str},
+    parser::{reflect::Visitor, ParseMode},
+    stream::{
+        input_at_eof,
+        state::{self, Interner},
+        Positioned, ResetStream, Stream, StreamErrorFor, StreamOnce,
+    },
     Parser,
 };
 
+#[cfg(feature = "std")]
+use crate::stream::packrat;
+
 #[derive(Copy, Clone)]
 pub struct NotFollowedBy<P>(P);
 impl<Input, O, P> Parser<Input> for NotFollowedBy<P>
@@ -54,6 +61,9 @@ where
 /// Succeeds only if `parser` fails.
 /// Never consumes any input.
 ///
+/// The output of `parser` is discarded so `parser` may produce any value, such as a node of a
+/// syntax tree.
+///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
@@ -71,7 +81,6 @@ pub fn not_followed_by<Input, P>(parser: P) -> NotFollowedBy<P>
 where
     Input: Stream,
     P: Parser<Input>,
-    P::Output: Into<Info<<Input as StreamOnce>::Token, <Input as StreamOnce>::Range, &'static str>>,
 {
     NotFollowedBy(parser)
 }
@@ -131,7 +140,7 @@ where
         }
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// `attempt(p)` behaves as `p` except it always acts as `p` peeked instead of committed on its
@@ -178,7 +187,7 @@ where
         PeekOk(o)
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// `look_ahead(p)` acts as `p` but doesn't consume input on success.
@@ -235,7 +244,7 @@ where
         }
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Equivalent to [`p.map(f)`].
@@ -280,7 +289,7 @@ where
         }
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Equivalent to [`p.map_input(f)`].
@@ -295,6 +304,50 @@ where
     MapInput(p, f)
 }
 
+#[derive(Copy, Clone)]
+pub struct Interned<P>(pub(crate) P);
+impl<S, U, P> Parser<state::Stream<S, U>> for Interned<P>
+where
+    state::Stream<S, U>: Stream,
+    U: Interner,
+    P: Parser<state::Stream<S, U>>,
+    P::Output: AsRef<str>,
+{
+    type Output = U::Symbol;
+    type PartialState = P::PartialState;
+
+    parse_mode!(state::Stream<S, U>);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut state::Stream<S, U>,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <state::Stream<S, U> as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0
+            .parse_mode(mode, input, state)
+            .map(|x| input.state.intern(x.as_ref()))
+    }
+
+    forward_parser!(state::Stream<S, U>, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Equivalent to [`p.interned()`].
+///
+/// [`p.interned()`]: ../trait.Parser.html#method.interned
+pub fn interned<S, U, P>(p: P) -> Interned<P>
+where
+    state::Stream<S, U>: Stream,
+    U: Interner,
+    P: Parser<state::Stream<S, U>>,
+    P::Output: AsRef<str>,
+{
+    Interned(p)
+}
+
 #[derive(Copy, Clone)]
 pub struct FlatMap<P, F>(P, F);
 impl<Input, A, B, P, F> Parser<Input> for FlatMap<P, F>
@@ -331,7 +384,7 @@ where
         }
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Equivalent to [`p.flat_map(f)`].
@@ -399,7 +452,7 @@ where
         }
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Equivalent to [`p.and_then(f)`].
@@ -415,6 +468,141 @@ where
     AndThen(p, f)
 }
 
+#[derive(Copy, Clone)]
+pub struct AndThenFull<P, F>(P, F);
+impl<Input, P, F, O> Parser<Input> for AndThenFull<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(P::Output, Input::Position) -> Result<O, Input::Error>,
+{
+    type Output = O;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let position = input.position();
+        let checkpoint = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => match (self.1)(o, position) {
+                Ok(o) => PeekOk(o),
+                Err(err) => {
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                        CommitErr(err)
+                    } else {
+                        PeekErr(err.into())
+                    }
+                }
+            },
+            CommitOk(o) => match (self.1)(o, position) {
+                Ok(o) => CommitOk(o),
+                Err(err) => {
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                    }
+                    CommitErr(err)
+                }
+            },
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Equivalent to [`p.and_then_full(f)`].
+///
+/// [`p.and_then_full(f)`]: ../trait.Parser.html#method.and_then_full
+pub fn and_then_full<Input, P, F, O>(p: P, f: F) -> AndThenFull<P, F>
+where
+    P: Parser<Input>,
+    F: FnMut(P::Output, Input::Position) -> Result<O, Input::Error>,
+    Input: Stream,
+{
+    AndThenFull(p, f)
+}
+
+#[derive(Copy, Clone)]
+pub struct Ensure<P, F>(P, F, &'static str);
+impl<Input, P, F> Parser<Input> for Ensure<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(&P::Output) -> bool,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let position = input.position();
+        let checkpoint = input.checkpoint();
+        let message = self.2;
+        let error = |position| {
+            <Input as StreamOnce>::Error::from_error(
+                position,
+                StreamError::message_static_message(message),
+            )
+        };
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => {
+                if (self.1)(&o) {
+                    PeekOk(o)
+                } else if input.is_partial() && input_at_eof(input) {
+                    ctry!(input.reset(checkpoint).committed());
+                    CommitErr(error(position))
+                } else {
+                    PeekErr(error(position).into())
+                }
+            }
+            CommitOk(o) => {
+                if (self.1)(&o) {
+                    CommitOk(o)
+                } else {
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                    }
+                    CommitErr(error(position))
+                }
+            }
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Equivalent to [`p.ensure(predicate, message)`].
+///
+/// [`p.ensure(predicate, message)`]: ../trait.Parser.html#method.ensure
+pub fn ensure<Input, P, F>(p: P, predicate: F, message: &'static str) -> Ensure<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(&P::Output) -> bool,
+{
+    Ensure(p, predicate, message)
+}
+
 #[derive(Copy, Clone)]
 pub struct Recognize<F, P>(P, PhantomData<fn() -> F>);
 
@@ -425,14 +613,14 @@ impl<F, P> Recognize<F, P> {
         before: <Input as ResetStream>::Checkpoint,
         input: &mut Input,
         result: ParseResult<P::Output, <Input as StreamOnce>::Error>,
-    ) -> ParseResult<F, <Input as StreamOnce>::Error>
+    ) -> ParseResult<(F, P::Output), <Input as StreamOnce>::Error>
     where
         P: Parser<Input>,
         Input: Stream,
         F: Default + Extend<Input::Token>,
     {
         match result {
-            PeekOk(_) => {
+            PeekOk(value) => {
                 let last_position = input.position();
                 ctry!(input.reset(before).committed());
 
@@ -447,9 +635,9 @@ impl<F, P> Recognize<F, P> {
                         }
                     }
                 }
-                PeekOk(mem::replace(elements, F::default()))
+                PeekOk((mem::take(elements), value))
             }
-            CommitOk(_) => {
+            CommitOk(value) => {
                 let last_position = input.position();
                 ctry!(input.reset(before).committed());
 
@@ -464,7 +652,7 @@ impl<F, P> Recognize<F, P> {
                         }
                     }
                 }
-                CommitOk(mem::replace(elements, F::default()))
+                CommitOk((mem::take(elements), value))
             }
             CommitErr(err) => {
                 let last_position = input.position();
@@ -483,61 +671,530 @@ impl<F, P> Recognize<F, P> {
                 }
                 CommitErr(err)
             }
-            PeekErr(err) => PeekErr(err),
+            PeekErr(err) => PeekErr(err),
+        }
+    }
+}
+
+impl<Input, P, F> Parser<Input> for Recognize<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    type Output = F;
+    type PartialState = (F, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut elements, ref mut child_state) = *state;
+
+        let before = input.checkpoint();
+        let result = self.0.parse_mode(mode, input, child_state);
+        match Self::recognize_result(elements, before, input, result) {
+            PeekOk((elements, _)) => PeekOk(elements),
+            CommitOk((elements, _)) => CommitOk(elements),
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    #[inline]
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        self.0.reflect(visitor)
+    }
+}
+
+/// Constructs a parser which returns the tokens parsed by `parser` accumulated in
+/// `F: Extend<Input::Token>` instead of `P::Output`.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::{repeat::skip_many1, token::token, combinator::recognize, char::digit};
+///
+/// let mut parser = recognize((skip_many1(digit()), token('.'), skip_many1(digit())));
+/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
+/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
+/// ```
+pub fn recognize<F, Input, P>(parser: P) -> Recognize<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    Recognize(parser, PhantomData)
+}
+
+pub struct RecognizeWithValue<F, P>(Recognize<F, P>);
+
+impl<Input, P, F> Parser<Input> for RecognizeWithValue<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    type Output = (F, P::Output);
+    type PartialState = (F, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut elements, ref mut child_state) = *state;
+
+        let before = input.checkpoint();
+        let result = (self.0).0.parse_mode(mode, input, child_state);
+        Recognize::<F, P>::recognize_result(elements, before, input, result)
+    }
+
+    #[inline]
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        (self.0).0.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        (self.0).0.reflect(visitor)
+    }
+}
+
+/// Constructs a parser which returns the tokens parsed by `parser` accumulated in
+/// `F: Extend<Input::Token>` together with the output of `parser`.
+///
+/// [`range::recognize_with_value`][] is a zero-copy alternative for `RangeStream`s.
+///
+/// [`range::recognize_with_value`]: ../range/fn.recognize_with_value.html
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::{char::digit, combinator::recognize_with_value, repeat::many1};
+///
+/// let mut parser = recognize_with_value(many1(digit()).map(|s: String| s.len()));
+/// assert_eq!(parser.parse("123a"), Ok((("123".to_string(), 3), "a")));
+/// ```
+pub fn recognize_with_value<F, Input, P>(parser: P) -> RecognizeWithValue<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    RecognizeWithValue(Recognize(parser, PhantomData))
+}
+
+#[derive(Copy, Clone)]
+pub struct Spanned<P>(P);
+
+impl<Input, P> Parser<Input> for Spanned<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = (ops::Range<Input::Position>, P::Output);
+    type PartialState = (Option<Input::Position>, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut start, ref mut child_state) = *state;
+
+        if mode.is_first() || start.is_none() {
+            *start = Some(input.position());
+        }
+        match self.0.parse_mode(mode, input, child_state) {
+            PeekOk(value) => PeekOk((start.take().unwrap()..input.position(), value)),
+            CommitOk(value) => CommitOk((start.take().unwrap()..input.position(), value)),
+            PeekErr(err) => {
+                *start = None;
+                PeekErr(err)
+            }
+            // Keep the start position so a partial parse can be resumed
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    #[inline]
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_committed_expected_error(errors)
+    }
+
+    forward_parser!(Input, parser_count reflect, 0);
+}
+
+/// Constructs a parser which returns the range of positions `parser` was parsed at (from the
+/// position before `parser` to the position after it) together with the output of `parser`.
+///
+/// ```
+/// use combine::{Parser, EasyParser};
+/// use combine::parser::{char::{digit, spaces}, combinator::spanned, repeat::many1};
+/// use combine::stream::position::{self, SourcePosition};
+///
+/// let mut parser = spaces().with(spanned(many1::<String, _, _>(digit())));
+/// let result = parser.easy_parse(position::Stream::new("  123"));
+/// assert_eq!(
+///     result.map(|(output, _)| output),
+///     Ok((
+///         SourcePosition { line: 1, column: 3 }..SourcePosition { line: 1, column: 6 },
+///         "123".to_string(),
+///     ))
+/// );
+/// ```
+pub fn spanned<Input, P>(parser: P) -> Spanned<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    Spanned(parser)
+}
+
+#[derive(Copy, Clone)]
+pub struct WithPosition<P>(P);
+
+impl<Input, P> Parser<Input> for WithPosition<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = (Input::Position, P::Output);
+    type PartialState = (Option<Input::Position>, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut start, ref mut child_state) = *state;
+
+        if mode.is_first() || start.is_none() {
+            *start = Some(input.position());
+        }
+        match self.0.parse_mode(mode, input, child_state) {
+            PeekOk(value) => PeekOk((start.take().unwrap(), value)),
+            CommitOk(value) => CommitOk((start.take().unwrap(), value)),
+            PeekErr(err) => {
+                *start = None;
+                PeekErr(err)
+            }
+            // Keep the start position so a partial parse can be resumed
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    #[inline]
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_committed_expected_error(errors)
+    }
+
+    forward_parser!(Input, parser_count reflect, 0);
+}
+
+/// Constructs a parser which returns the position before `parser` consumed any input together
+/// with the output of `parser`.
+///
+/// Unlike `(position(), parser)` the position is kept in the partial state, so it still refers to
+/// the start of `parser` after a partial parse has been resumed.
+///
+/// ```
+/// use combine::{Parser, EasyParser};
+/// use combine::parser::{char::{digit, spaces}, combinator::with_position, repeat::many1};
+/// use combine::stream::position::{self, SourcePosition};
+///
+/// let mut parser = spaces().with(with_position(many1::<String, _, _>(digit())));
+/// let result = parser.easy_parse(position::Stream::new("  123"));
+/// assert_eq!(
+///     result.map(|(output, _)| output),
+///     Ok((SourcePosition { line: 1, column: 3 }, "123".to_string()))
+/// );
+/// ```
+pub fn with_position<Input, P>(parser: P) -> WithPosition<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    WithPosition(parser)
+}
+
+#[derive(Copy, Clone)]
+pub struct AllConsuming<P>(P);
+impl<Input, P> Parser<Input> for AllConsuming<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (value, committed) = ctry!(self.0.parse_mode(mode, input, state));
+        let before = input.checkpoint();
+        let position = input.position();
+        match input.uncons() {
+            Err(ref err) if err.is_unexpected_end_of_input() => {
+                committed.combine_commit(|_| PeekOk(value))
+            }
+            result => {
+                ctry!(input.reset(before).committed());
+                let mut error = <Input as StreamOnce>::Error::empty(position);
+                if let Ok(token) = result {
+                    error.add(StreamError::unexpected_token(token));
+                }
+                error.add_expected("end of input");
+                error.add_message("unexpected trailing input");
+                committed.combine_commit(|_| PeekErr(error.into()))
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Parses `parser` and then requires that the whole input has been consumed, failing with an
+/// "unexpected trailing input" error at the start of any input which is left.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::easy;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::combinator::all_consuming;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut parser = all_consuming(many1::<String, _, _>(digit()));
+/// assert_eq!(parser.parse("123"), Ok(("123".to_string(), "")));
+///
+/// let mut parser = all_consuming(many1::<String, _, _>(digit()));
+/// let err = parser.easy_parse(position::Stream::new("12x3")).unwrap_err();
+/// assert_eq!(err.position, SourcePosition { line: 1, column: 3 });
+/// assert!(err.errors.contains(&easy::Error::Unexpected('x'.into())));
+/// assert!(err.errors.contains(&easy::Error::Message("unexpected trailing input".into())));
+/// # }
+/// ```
+pub fn all_consuming<Input, P>(parser: P) -> AllConsuming<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    AllConsuming(parser)
+}
+
+#[derive(Copy, Clone)]
+pub struct Trace<P>(P, &'static str);
+impl<Input, P> Parser<Input> for Trace<P>
+where
+    Input: Stream,
+    Input::Token: fmt::Debug,
+    Input::Position: fmt::Debug,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        #[cfg(feature = "trace")]
+        log::trace!(
+            "{}: {} at {:?} before {}",
+            self.1,
+            if mode.is_first() { "enter" } else { "resume" },
+            input.position(),
+            trace_preview(input),
+        );
+
+        let span = RuleSpan::enter(self.1, mode.is_first());
+        #[cfg(feature = "tracing")]
+        span.record_position(&input.position());
+
+        let result = self.0.parse_mode(mode, input, state);
+
+        span.exit(&result);
+
+        #[cfg(feature = "trace")]
+        log::trace!(
+            "{}: {} at {:?}",
+            self.1,
+            trace_result(&result),
+            input.position(),
+        );
+
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+#[cfg(any(feature = "trace", feature = "tracing"))]
+pub(crate) fn trace_result<T, E>(result: &ParseResult<T, E>) -> &'static str {
+    match *result {
+        PeekOk(_) => "PeekOk",
+        CommitOk(_) => "CommitOk",
+        PeekErr(_) => "PeekErr",
+        CommitErr(_) => "CommitErr",
+    }
+}
+
+/// Guard for the `tracing` span of a named parser which does nothing unless the `tracing` feature
+/// is enabled.
+///
+/// Used by `parser!` so that the feature is checked in this crate instead of the crate the macro
+/// is expanded in.
+#[doc(hidden)]
+pub struct RuleSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+}
+
+impl RuleSpan {
+    #[inline]
+    pub fn enter(name: &'static str, first: bool) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = (name, first);
+        RuleSpan {
+            #[cfg(feature = "tracing")]
+            span: tracing::trace_span!(
+                "parse",
+                parser = name,
+                mode = if first { "enter" } else { "resume" },
+                position = tracing::field::Empty,
+                result = tracing::field::Empty,
+            )
+            .entered(),
+        }
+    }
+
+    /// Enters the span of the rule `name` defined with `parser!` in `module_path`.
+    ///
+    /// The rules which implement combine's own parsers do not get a span, so only the rules of the
+    /// user's grammar show up in the trace.
+    #[inline]
+    pub fn enter_rule(module_path: &'static str, name: &'static str, first: bool) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            if module_path.split("::").next() == Some("combine") {
+                return RuleSpan {
+                    span: tracing::Span::none().entered(),
+                };
+            }
         }
+        #[cfg(not(feature = "tracing"))]
+        let _ = module_path;
+        RuleSpan::enter(name, first)
+    }
+
+    #[cfg(feature = "tracing")]
+    #[inline]
+    pub(crate) fn record_position<P>(&self, position: &P)
+    where
+        P: fmt::Debug,
+    {
+        self.span
+            .record("position", tracing::field::debug(position));
+    }
+
+    #[inline]
+    pub fn exit<T, E>(self, result: &ParseResult<T, E>) {
+        #[cfg(feature = "tracing")]
+        self.span.record("result", trace_result(result));
+        #[cfg(not(feature = "tracing"))]
+        let _ = result;
     }
 }
 
-impl<Input, P, F> Parser<Input> for Recognize<F, P>
+/// Formats the next few tokens of `input` without consuming them.
+#[cfg(feature = "trace")]
+fn trace_preview<Input>(input: &mut Input) -> String
 where
     Input: Stream,
-    P: Parser<Input>,
-    F: Default + Extend<<Input as StreamOnce>::Token>,
+    Input::Token: fmt::Debug,
 {
-    type Output = F;
-    type PartialState = (F, P::PartialState);
+    use std::fmt::Write;
 
-    parse_mode!(Input);
-    fn parse_mode_impl<M>(
-        &mut self,
-        mode: M,
-        input: &mut Input,
-        state: &mut Self::PartialState,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
-    where
-        M: ParseMode,
-    {
-        let (ref mut elements, ref mut child_state) = *state;
+    const PREVIEW_TOKENS: usize = 16;
 
-        let before = input.checkpoint();
-        let result = self.0.parse_mode(mode, input, child_state);
-        Self::recognize_result(elements, before, input, result)
+    let checkpoint = input.checkpoint();
+    let mut preview = String::new();
+    for _ in 0..PREVIEW_TOKENS {
+        match input.uncons() {
+            Ok(token) => {
+                let _ = write!(preview, "{:?} ", token);
+            }
+            Err(_) => {
+                preview.push_str("<end>");
+                break;
+            }
+        }
     }
-
-    #[inline]
-    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        self.0.add_error(errors)
+    if input.reset(checkpoint).is_err() {
+        preview.push_str("<could not reset the input>");
     }
+    preview
 }
 
-/// Constructs a parser which returns the tokens parsed by `parser` accumulated in
-/// `F: Extend<Input::Token>` instead of `P::Output`.
-///
-/// ```
-/// use combine::Parser;
-/// use combine::parser::{repeat::skip_many1, token::token, combinator::recognize, char::digit};
+/// Equivalent to [`p.trace(name)`].
 ///
-/// let mut parser = recognize((skip_many1(digit()), token('.'), skip_many1(digit())));
-/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
-/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
-/// ```
-pub fn recognize<F, Input, P>(parser: P) -> Recognize<F, P>
+/// [`p.trace(name)`]: ../trait.Parser.html#method.trace
+pub fn trace<Input, P>(parser: P, name: &'static str) -> Trace<P>
 where
     Input: Stream,
+    Input::Token: fmt::Debug,
+    Input::Position: fmt::Debug,
     P: Parser<Input>,
-    F: Default + Extend<<Input as StreamOnce>::Token>,
 {
-    Recognize(parser, PhantomData)
+    Trace(parser, name)
 }
 
 pub enum Either<L, R> {
@@ -619,6 +1276,13 @@ where
             Either::Right(ref mut x) => x.add_error(error),
         }
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        match *self {
+            Either::Left(ref mut x) => x.reflect(visitor),
+            Either::Right(ref mut x) => x.reflect(visitor),
+        }
+    }
 }
 
 pub struct NoPartial<P>(P);
@@ -653,7 +1317,7 @@ where
         self.0.parse_lazy(input)
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 pub fn no_partial<Input, P>(p: P) -> NoPartial<P>
@@ -696,7 +1360,15 @@ where
         self.0.parse_mode(mode, input, state).map(|_| ())
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    fn parse_many_tokens(
+        &mut self,
+        input: &mut Input,
+        f: &mut dyn FnMut(()),
+    ) -> Option<ParseResult<(), <Input as StreamOnce>::Error>> {
+        self.0.parse_many_tokens(input, &mut |_| f(()))
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 #[doc(hidden)]
@@ -712,6 +1384,42 @@ where
 #[derive(Default)]
 pub struct AnyPartialState(Option<Box<dyn std::any::Any>>);
 
+#[cfg(feature = "std")]
+impl AnyPartialState {
+    /// Discards any in-progress parse so that the next parse starts from the beginning.
+    ///
+    /// Useful after a decoding error to resynchronize on the next frame.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::parser::combinator::{any_partial_state, AnyPartialState};
+    /// # use combine::parser::char::{char, digit};
+    /// # use combine::stream::{decode, PartialStream};
+    /// # use combine::*;
+    /// # fn main() {
+    /// let mut parser = any_partial_state(many1::<String, _, _>(digit()).skip(char(';')));
+    /// let mut state = AnyPartialState::default();
+    ///
+    /// assert_eq!(decode(&mut parser, &mut PartialStream("12"), &mut state), Ok((None, 2)));
+    /// assert!(!state.is_empty());
+    ///
+    /// // Drop the rest of the frame and start over
+    /// state.reset();
+    /// assert!(state.is_empty());
+    /// let result = decode(&mut parser, &mut PartialStream("3;"), &mut state);
+    /// assert_eq!(result, Ok((Some("3".to_string()), 2)));
+    /// # }
+    /// ```
+    pub fn reset(&mut self) {
+        self.0 = None;
+    }
+
+    /// Returns `true` if no partial parse is in progress.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
 #[cfg(feature = "std")]
 pub struct AnyPartialStateParser<P>(P);
 
@@ -767,7 +1475,7 @@ where
         result
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
@@ -808,10 +1516,34 @@ where
     AnyPartialStateParser(p)
 }
 
+/// A boxed parser whose partial state is type erased with [`AnyPartialState`][], as returned by
+/// [`Parser::boxed_any`][].
+///
+/// [`AnyPartialState`]: struct.AnyPartialState.html
+/// [`Parser::boxed_any`]: ../trait.Parser.html#method.boxed_any
+#[cfg(feature = "std")]
+pub type BoxedParser<'a, Input, O> =
+    Box<dyn Parser<Input, Output = O, PartialState = AnyPartialState> + 'a>;
+
 #[cfg(feature = "std")]
 #[derive(Default)]
 pub struct AnySendPartialState(Option<Box<dyn std::any::Any + Send>>);
 
+#[cfg(feature = "std")]
+impl AnySendPartialState {
+    /// Discards any in-progress parse so that the next parse starts from the beginning.
+    ///
+    /// Useful after a decoding error to resynchronize on the next frame.
+    pub fn reset(&mut self) {
+        self.0 = None;
+    }
+
+    /// Returns `true` if no partial parse is in progress.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
 #[cfg(feature = "std")]
 pub struct AnySendPartialStateParser<P>(P);
 
@@ -867,7 +1599,7 @@ where
         result
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
@@ -967,7 +1699,7 @@ where
         result
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
@@ -1066,9 +1798,10 @@ where
 ///
 /// NOTE: Expects that the parser returned is always the same one, if that is not the case the
 /// reported error may be wrong. If different parsers may be returned, use the [`factory`][] parser
-/// instead.
+/// instead. To only construct the parser once, use [`lazy_cached`][].
 ///
 /// [`factory`]: fn.factory.html
+/// [`lazy_cached`]: fn.lazy_cached.html
 pub fn lazy<Input, P, R>(p: P) -> Lazy<P>
 where
     Input: Stream,
@@ -1078,6 +1811,106 @@ where
     Lazy(p)
 }
 
+pub struct LazyCached<P, R>(P, Option<R>);
+
+impl<P, R> LazyCached<P, R> {
+    fn parser(&mut self) -> &mut R
+    where
+        P: FnMut() -> R,
+    {
+        if let Some(ref mut r) = self.1 {
+            return r;
+        }
+        self.1 = Some((self.0)());
+        self.1.as_mut().unwrap()
+    }
+}
+
+impl<Input, O, P, R> Parser<Input> for LazyCached<P, R>
+where
+    Input: Stream,
+    P: FnMut() -> R,
+    R: Parser<Input, Output = O>,
+{
+    type Output = O;
+    type PartialState = R::PartialState;
+
+    parse_mode!(Input);
+
+    fn parse_committed_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.parser().parse_mode(mode, input, state)
+    }
+
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.parser().parse_mode_impl(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser().add_error(errors);
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser().add_committed_expected_error(errors);
+    }
+
+    fn parser_count(&self) -> crate::ErrorOffset {
+        self.1
+            .as_ref()
+            .map_or(crate::ErrorOffset(1), |parser| parser.parser_count())
+    }
+}
+
+/// Constructs the parser the first time it is used and then keeps using that same parser. Unlike
+/// [`lazy`][], which calls `p` on each `parse_*` call, this is useful for parsers which are
+/// expensive to construct (such as large `choice` tables) but which may never be needed.
+///
+/// Since the constructed parser is stored, a parser may not contain itself through `lazy_cached`.
+/// Use [`lazy`][] or [`recursive`][] for recursive definitions instead.
+///
+/// [`lazy`]: fn.lazy.html
+/// [`recursive`]: fn.recursive.html
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string;
+/// # use combine::parser::combinator::lazy_cached;
+/// # fn main() {
+/// let mut built = 0;
+/// let mut parser = many::<Vec<_>, _, _>(lazy_cached(|| {
+///     built += 1;
+///     choice((string("let"), string("in"), string("do")))
+/// }));
+/// assert_eq!(parser.parse("letindo"), Ok((vec!["let", "in", "do"], "")));
+/// drop(parser);
+/// assert_eq!(built, 1);
+/// # }
+/// ```
+pub fn lazy_cached<Input, P, R>(p: P) -> LazyCached<P, R>
+where
+    Input: Stream,
+    P: FnMut() -> R,
+    R: Parser<Input>,
+{
+    LazyCached(p, None)
+}
+
 #[derive(Copy, Clone)]
 pub struct Factory<P, R>(P, Option<R>);
 
@@ -1160,6 +1993,232 @@ where
     Factory(p, None)
 }
 
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct Memo<P> {
+    parser: P,
+    id: usize,
+}
+
+#[cfg(feature = "std")]
+impl<S, P> Parser<packrat::Stream<S>> for Memo<P>
+where
+    S: Stream,
+    P: Parser<packrat::Stream<S>>,
+    P::Output: Clone + 'static,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    fn parse_lazy(
+        &mut self,
+        input: &mut packrat::Stream<S>,
+    ) -> ParseResult<Self::Output, <packrat::Stream<S> as StreamOnce>::Error> {
+        let position = input.position();
+        match input.cached::<P::Output>(self.id, position.clone()) {
+            Some(Some(memo)) => {
+                let value = memo
+                    .value
+                    .downcast_ref::<P::Output>()
+                    .expect("cache is keyed by the output type")
+                    .clone();
+                let committed = memo.committed;
+                let end = memo.end.clone();
+                ctry!(input.reset(end).committed());
+                return if committed {
+                    CommitOk(value)
+                } else {
+                    PeekOk(value)
+                };
+            }
+            // The expected errors are added by `add_error`
+            Some(None) => return PeekErr(S::Error::empty(position).into()),
+            None => (),
+        }
+
+        let result = self.parser.parse_lazy(input);
+        match result {
+            PeekOk(ref value) | CommitOk(ref value) => {
+                let memo = packrat::Memo {
+                    value: std::rc::Rc::new(value.clone()),
+                    end: input.checkpoint(),
+                    committed: matches!(result, CommitOk(_)),
+                };
+                input.insert_cached::<P::Output>(self.id, position, Some(memo));
+            }
+            PeekErr(_) => input.insert_cached::<P::Output>(self.id, position, None),
+            CommitErr(_) => (),
+        }
+        result
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<packrat::Stream<S> as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn add_committed_expected_error(
+        &mut self,
+        errors: &mut Tracked<<packrat::Stream<S> as StreamOnce>::Error>,
+    ) {
+        self.parser.add_committed_expected_error(errors)
+    }
+
+    forward_parser!(packrat::Stream<S>, parser_count reflect, parser);
+}
+
+/// Caches the results of `parser` in the [`packrat::Stream`][] by the position they started at, so
+/// that parsing the same input position again (for instance in several `attempt` alternatives)
+/// only resets the input instead of running `parser` again. Clones of the returned parser share
+/// their cached results.
+///
+/// Successes and failures which did not consume input are cached. As the cache is stored in the
+/// stream it only lives as long as the parse of that input.
+///
+/// NOTE: The resulting parser does not support partial parsing.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::combinator::memo;
+/// # use combine::stream::packrat;
+/// # use std::cell::Cell;
+/// # fn main() {
+/// let calls = Cell::new(0);
+/// let number = memo(many1(digit()).map(|s: String| {
+///     calls.set(calls.get() + 1);
+///     s.parse::<i32>().unwrap()
+/// }));
+/// let mut parser = attempt((number.clone(), char('%'))).or((number, char('!')));
+/// let result = parser.parse(packrat::Stream::new("123!"));
+/// assert_eq!(result.map(|(value, rest)| (value, rest.stream)), Ok(((123, '!'), "")));
+/// assert_eq!(calls.get(), 1);
+/// # }
+/// ```
+///
+/// [`packrat::Stream`]: ../../stream/packrat/struct.Stream.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn memo<S, P>(parser: P) -> Memo<P>
+where
+    S: Stream,
+    P: Parser<packrat::Stream<S>>,
+    P::Output: Clone + 'static,
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    Memo {
+        parser,
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+    }
+}
+
+#[cfg(feature = "std")]
+type RecursiveBuilder<'a, Input, O> =
+    std::rc::Rc<dyn Fn(Recursive<'a, Input, O>) -> BoxedParser<'a, Input, O> + 'a>;
+
+#[cfg(feature = "std")]
+pub struct Recursive<'a, Input, O> {
+    builder: RecursiveBuilder<'a, Input, O>,
+    parser: Option<BoxedParser<'a, Input, O>>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, Input, O> Clone for Recursive<'a, Input, O> {
+    /// Returns a new handle to the same parser. Each handle builds its own instance of the parser
+    /// when it is first used so a handle may be used inside the parser it refers to.
+    fn clone(&self) -> Self {
+        Recursive {
+            builder: self.builder.clone(),
+            parser: None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, Input, O> Recursive<'a, Input, O> {
+    fn parser(&mut self) -> &mut BoxedParser<'a, Input, O> {
+        if self.parser.is_none() {
+            self.parser = Some((self.builder)(self.clone()));
+        }
+        self.parser.as_mut().unwrap()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, Input, O> Parser<Input> for Recursive<'a, Input, O>
+where
+    Input: Stream,
+{
+    type Output = O;
+    type PartialState = AnyPartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.parser().parse_mode(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser().add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser().add_committed_expected_error(errors)
+    }
+
+    fn parser_count(&self) -> crate::ErrorOffset {
+        self.parser
+            .as_ref()
+            .map_or(crate::ErrorOffset(1), |parser| parser.parser_count())
+    }
+}
+
+/// Defines a self-referential parser inline. `f` is given a handle to the parser being defined,
+/// which can be used (and cloned) anywhere inside the parser that `f` returns.
+///
+/// Unlike the `parser!` macro or a function returning `impl Parser`, this works in expression
+/// position. Each use of a handle constructs the parser returned by `f` the first time it is
+/// parsed, so `f` should be cheap and always return the same parser.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::char;
+/// # use combine::parser::combinator::recursive;
+/// # fn main() {
+/// // Counts how deeply a set of parentheses are nested
+/// let mut parser = recursive(|nested| {
+///     between(char('('), char(')'), optional(nested)).map(|depth| depth.map_or(1, |d: u32| d + 1))
+/// });
+/// assert_eq!(parser.parse("((()))"), Ok((3, "")));
+/// assert!(parser.parse("(()").is_err());
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn recursive<'a, Input, O, F, P>(f: F) -> Recursive<'a, Input, O>
+where
+    Input: Stream,
+    F: Fn(Recursive<'a, Input, O>) -> P + 'a,
+    P: Parser<Input, Output = O> + 'a,
+    P::PartialState: 'static,
+{
+    Recursive {
+        builder: std::rc::Rc::new(move |handle| Box::new(any_partial_state(f(handle)))),
+        parser: None,
+    }
+}
+
 mod internal {
     pub trait Sealed {}
 }
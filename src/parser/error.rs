@@ -2,12 +2,16 @@
 
 use crate::{
     error::{
-        ErrorInfo, ParseError,
+        ErrorInfo, Info, ParseError,
         ParseResult::{self, *},
         StreamError, Tracked,
     },
     lib::marker::PhantomData,
-    parser::ParseMode,
+    parser::{
+        combinator::RuleSpan,
+        reflect::{visit, Node, Visitor},
+        ParseMode,
+    },
     Parser, Stream, StreamOnce,
 };
 
@@ -134,7 +138,7 @@ where
         errors.error.add_message(&self.1);
     }
 
-    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+    forward_parser!(Input, parser_count add_committed_expected_error reflect, 0);
 }
 
 /// Equivalent to [`p1.message(msg)`].
@@ -180,7 +184,16 @@ where
         })
     }
 
-    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let parser = &mut self.0;
+        match self.1.into_info() {
+            Info::Static(label) => visit(visitor, Node::Label(&label), |v| parser.reflect(v)),
+            Info::Format(label) => visit(visitor, Node::Label(&label), |v| parser.reflect(v)),
+            Info::Token(_) | Info::Range(_) => parser.reflect(visitor),
+        }
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error parse_many_tokens, 0);
 }
 
 /// Equivalent to [`p.expected(info)`].
@@ -195,6 +208,73 @@ where
     Expected(p, info)
 }
 
+#[derive(Clone)]
+pub struct Label<P>(P, &'static str);
+impl<Input, P> Parser<Input> for Label<P>
+where
+    P: Parser<Input>,
+    Input: Stream,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        #[cfg(feature = "trace")]
+        log::trace!("{}: enter", self.1);
+
+        let span = RuleSpan::enter(self.1, mode.is_first());
+        let result = self.0.parse_mode(mode, input, state);
+        span.exit(&result);
+
+        #[cfg(feature = "trace")]
+        log::trace!(
+            "{}: {}",
+            self.1,
+            crate::parser::combinator::trace_result(&result)
+        );
+
+        result
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        ParseError::set_expected(
+            errors,
+            StreamError::expected_static_message(self.1),
+            |errors| {
+                self.0.add_error(errors);
+            },
+        )
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let parser = &mut self.0;
+        visit(visitor, Node::Label(&self.1), |v| parser.reflect(v))
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error parse_many_tokens, 0);
+}
+
+/// Equivalent to [`p.label(name)`].
+///
+/// [`p.label(name)`]: ../trait.Parser.html#method.label
+pub fn label<Input, P>(p: P, name: &'static str) -> Label<P>
+where
+    P: Parser<Input>,
+    Input: Stream,
+{
+    Label(p, name)
+}
+
 #[derive(Clone)]
 pub struct Silent<P>(P);
 impl<Input, P> Parser<Input> for Silent<P>
@@ -216,10 +296,18 @@ where
     where
         M: ParseMode,
     {
-        self.0.parse_mode(mode, input, state).map_err(|mut err| {
-            err.clear_expected();
-            err
-        })
+        let position = input.position();
+        match self.0.parse_mode(mode, input, state) {
+            // Nothing was consumed so only report that `p` failed
+            PeekErr(err) => PeekErr(Tracked::new(
+                <Input as StreamOnce>::Error::empty(position),
+                err.offset,
+            )),
+            result => result.map_err(|mut err| {
+                err.clear_expected();
+                err
+            }),
+        }
     }
 
     fn add_error(&mut self, _errors: &mut Tracked<<Input as StreamOnce>::Error>) {}
@@ -230,7 +318,7 @@ where
     ) {
     }
 
-    forward_parser!(Input, parser_count, 0);
+    forward_parser!(Input, parser_count reflect, 0);
 }
 
 /// Equivalent to [`p.silent()`].
@@ -243,3 +331,60 @@ where
 {
     Silent(p)
 }
+
+#[derive(Clone)]
+pub struct MapErr<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for MapErr<P, F>
+where
+    P: Parser<Input>,
+    Input: Stream,
+    F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        match self.0.parse_mode(mode, input, state) {
+            PeekErr(mut err) => {
+                // Collect the expected errors of `p` eagerly so `f` sees the complete error
+                self.0.add_error(&mut err);
+                let offset = err.offset;
+                PeekErr(Tracked::new((self.1)(err.into_error()), offset))
+            }
+            CommitErr(err) => CommitErr((self.1)(err)),
+            result => result,
+        }
+    }
+
+    fn add_error(&mut self, _errors: &mut Tracked<<Input as StreamOnce>::Error>) {}
+
+    fn add_committed_expected_error(
+        &mut self,
+        _errors: &mut Tracked<<Input as StreamOnce>::Error>,
+    ) {
+    }
+
+    forward_parser!(Input, parser_count reflect, 0);
+}
+
+/// Equivalent to [`p.map_err(f)`].
+///
+/// [`p.map_err(f)`]: ../trait.Parser.html#method.map_err
+pub fn map_err<Input, P, F>(p: P, f: F) -> MapErr<P, F>
+where
+    P: Parser<Input>,
+    Input: Stream,
+    F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+{
+    MapErr(p, f)
+}
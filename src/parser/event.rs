@@ -0,0 +1,306 @@
+//! Parsing into a flat list of events from which a lossless concrete syntax tree can be built.
+//!
+//! [`parse`][] runs a parser over an [`event::Stream`][] which records every piece of input the
+//! parser consumes as an [`Event::Token`][], including whitespace and comments which the parser
+//! throws away. Wrapping a parser in [`node`][] surrounds the events it emits with
+//! [`Event::StartNode`][] and [`Event::FinishNode`][], which is the same shape of events that
+//! tree builders such as `rowan::GreenNodeBuilder` expect.
+//!
+//! Adjacent input consumed inside the same node is merged into a single token, so trivia which
+//! should be a separate token (rather than a part of the token before it) must be parsed in a
+//! `node` of its own or outside of the node it follows. Events of alternatives which were
+//! backtracked out of are removed again.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::char::{digit, spaces};
+//! # use combine::parser::event::{self, node, Event};
+//! # fn main() {
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Kind {
+//!     List,
+//!     Number,
+//! }
+//!
+//! let list = || {
+//!     node(
+//!         Kind::List,
+//!         many::<Vec<_>, _, _>(node(Kind::Number, many1::<String, _, _>(digit())).skip(spaces())),
+//!     )
+//! };
+//!
+//! let mut events = Vec::new();
+//! let result = event::parse(list(), "1 22", &mut events);
+//! assert_eq!(result.map(|t| t.0), Ok(vec!["1".to_string(), "22".to_string()]));
+//! assert_eq!(
+//!     events,
+//!     [
+//!         Event::StartNode(Kind::List),
+//!         Event::StartNode(Kind::Number),
+//!         Event::Token(0..1),
+//!         Event::FinishNode,
+//!         Event::Token(1..2),
+//!         Event::StartNode(Kind::Number),
+//!         Event::Token(2..4),
+//!         Event::FinishNode,
+//!         Event::FinishNode,
+//!     ]
+//! );
+//! # }
+//! ```
+//!
+//! [`parse`]: fn.parse.html
+//! [`node`]: fn.node.html
+//! [`event::Stream`]: struct.Stream.html
+//! [`Event::Token`]: enum.Event.html#variant.Token
+//! [`Event::StartNode`]: enum.Event.html#variant.StartNode
+//! [`Event::FinishNode`]: enum.Event.html#variant.FinishNode
+
+use crate::{
+    error::{
+        ParseResult::{self, *},
+        Tracked,
+    },
+    lib::ops::Range,
+    parser::reflect::Visitor,
+    stream::{Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
+    Parser, Stream as StreamTrait,
+};
+
+/// An event emitted while parsing with [`parse`][].
+///
+/// [`parse`]: fn.parse.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event<K> {
+    /// Starts a node of kind `K`, the events up to the matching `FinishNode` are its children.
+    StartNode(K),
+    /// A piece of consumed input, as distances from the start of the input (in the units of
+    /// `RangeStream::distance`, bytes for `&str` and `&[u8]`).
+    Token(Range<usize>),
+    /// Finishes the innermost node which has not been finished yet.
+    FinishNode,
+}
+
+/// Streams which record the events of [`node`][].
+///
+/// [`node`]: fn.node.html
+pub trait EventStream: StreamTrait {
+    type Kind;
+
+    fn events(&mut self) -> &mut Vec<Event<Self::Kind>>;
+}
+
+/// A stream which records all input consumed from `stream` as [`Event::Token`][]s.
+///
+/// Usually created through [`parse`][].
+///
+/// [`Event::Token`]: enum.Event.html#variant.Token
+/// [`parse`]: fn.parse.html
+#[derive(Debug)]
+pub struct Stream<'a, S, K> {
+    pub stream: S,
+    offset: usize,
+    events: &'a mut Vec<Event<K>>,
+}
+
+impl<'a, S, K> Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    /// Wraps `stream`, appending the events to `events`. The offsets of the tokens are relative to
+    /// the current position of `stream`.
+    pub fn new(stream: S, events: &'a mut Vec<Event<K>>) -> Self {
+        Stream {
+            stream,
+            offset: 0,
+            events,
+        }
+    }
+
+    fn consume<T>(&mut self, f: impl FnOnce(&mut S) -> T) -> T {
+        let before = self.stream.checkpoint();
+        let result = f(&mut self.stream);
+        let len = self.stream.distance(&before);
+        if len != 0 {
+            let start = self.offset;
+            self.offset += len;
+            match self.events.last_mut() {
+                Some(Event::Token(range)) if range.end == start => range.end = self.offset,
+                _ => self.events.push(Event::Token(start..self.offset)),
+            }
+        }
+        result
+    }
+}
+
+impl<'a, S, K> EventStream for Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    type Kind = K;
+
+    fn events(&mut self) -> &mut Vec<Event<K>> {
+        self.events
+    }
+}
+
+impl<'a, S, K> Positioned for Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.stream.position()
+    }
+}
+
+impl<'a, S, K> ResetStream for Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    type Checkpoint = (S::Checkpoint, usize, usize);
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.stream.checkpoint(), self.offset, self.events.len())
+    }
+
+    fn reset(&mut self, (checkpoint, offset, len): Self::Checkpoint) -> Result<(), Self::Error> {
+        self.stream.reset(checkpoint)?;
+        self.offset = offset;
+        self.events.truncate(len);
+        // The last token may have been extended past the checkpoint
+        if let Some(Event::Token(range)) = self.events.last_mut() {
+            if range.end > offset {
+                range.end = offset;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, S, K> StreamOnce for Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<S::Token, StreamErrorFor<Self>> {
+        self.consume(|stream| stream.uncons())
+    }
+
+    fn is_partial(&self) -> bool {
+        self.stream.is_partial()
+    }
+}
+
+impl<'a, S, K> RangeStreamOnce for Stream<'a, S, K>
+where
+    S: RangeStream,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        self.consume(|stream| stream.uncons_range(size))
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.consume(|stream| stream.uncons_while(f))
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.consume(|stream| stream.uncons_while1(f))
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.stream.distance(&end.0)
+    }
+
+    #[inline]
+    fn range(&self) -> Self::Range {
+        self.stream.range()
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Node<K, P> {
+    kind: K,
+    parser: P,
+}
+
+impl<Input, K, P> Parser<Input> for Node<K, P>
+where
+    Input: EventStream<Kind = K>,
+    K: Clone,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
+        let len = input.events().len();
+        input.events().push(Event::StartNode(self.kind.clone()));
+        let result = self.parser.parse_lazy(input);
+        match result {
+            // Nothing was consumed so the node is dropped entirely
+            PeekErr(_) => input.events().truncate(len),
+            // Keep the consumed input so the events still cover all of it
+            CommitOk(_) | PeekOk(_) | CommitErr(_) => input.events().push(Event::FinishNode),
+        }
+        result
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<Input::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        self.parser.reflect(visitor)
+    }
+}
+
+/// Parses `parser` as a node of kind `kind`, surrounding the events it emits with
+/// [`Event::StartNode`][] and [`Event::FinishNode`][].
+///
+/// If `parser` fails without consuming input the node is not emitted at all.
+///
+/// [`Event::StartNode`]: enum.Event.html#variant.StartNode
+/// [`Event::FinishNode`]: enum.Event.html#variant.FinishNode
+pub fn node<Input, K, P>(kind: K, parser: P) -> Node<K, P>
+where
+    Input: EventStream<Kind = K>,
+    K: Clone,
+    P: Parser<Input>,
+{
+    Node { kind, parser }
+}
+
+/// Parses `input` with `parser`, appending the events of the parse to `events`.
+///
+/// Returns the output of `parser` and the remaining input. The events are emitted even if the
+/// parse fails, covering the input consumed before the error.
+pub fn parse<'a, Input, K, P>(
+    mut parser: P,
+    input: Input,
+    events: &'a mut Vec<Event<K>>,
+) -> Result<(P::Output, Input), Input::Error>
+where
+    Input: RangeStream,
+    P: Parser<Stream<'a, Input, K>>,
+{
+    let mut stream = Stream::new(input, events);
+    let output = parser.parse_with_state(&mut stream, &mut Default::default())?;
+    Ok((output, stream.stream))
+}
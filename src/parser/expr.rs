@@ -0,0 +1,339 @@
+//! Parser for expressions built from an atom parser and a set of prefix, infix and postfix
+//! operators (using precedence climbing, also known as a Pratt parser).
+//!
+//! Instead of writing one `chainl1` (or `chainr1`) parser for each precedence level, the
+//! operators are registered on an [`Expr`][] together with their precedence and associativity.
+//!
+//! ```
+//! # extern crate combine;
+//! use combine::{between, many1, parser, Parser, Stream};
+//! use combine::error::ParseError;
+//! use combine::parser::char::{char, digit, spaces};
+//! use combine::parser::expr::{expr, Assoc};
+//!
+//! fn expression<'a, Input>() -> impl Parser<Input, Output = i64> + 'a
+//! where
+//!     Input: Stream<Token = char> + 'a,
+//!     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+//! {
+//!     let lex_char = |c| char(c).skip(spaces());
+//!     let integer = many1(digit())
+//!         .map(|s: String| s.parse::<i64>().unwrap())
+//!         .skip(spaces());
+//!     let parens = between(
+//!         lex_char('('),
+//!         lex_char(')'),
+//!         parser(|input| expression().parse_stream(input).into_result()),
+//!     );
+//!
+//!     expr(integer.or(parens))
+//!         .infix(lex_char('+'), 1, Assoc::Left, |l, r| l + r)
+//!         .infix(lex_char('-'), 1, Assoc::Left, |l, r| l - r)
+//!         .infix(lex_char('*'), 2, Assoc::Left, |l, r| l * r)
+//!         .infix(lex_char('^'), 3, Assoc::Right, |l, r| l.pow(r as u32))
+//!         .prefix(lex_char('-'), 4, |x| -x)
+//! }
+//!
+//! fn main() {
+//!     assert_eq!(expression().parse("1 + 2 * 3"), Ok((7, "")));
+//!     assert_eq!(expression().parse("(1 + 2) * 3"), Ok((9, "")));
+//!     assert_eq!(expression().parse("10 - 2 - 3"), Ok((5, "")));
+//!     assert_eq!(expression().parse("2 ^ 3 ^ 2"), Ok((512, "")));
+//!     assert_eq!(expression().parse("-2 * 3"), Ok((-6, "")));
+//!     assert!(expression().parse("1 +").is_err());
+//! }
+//! ```
+//!
+//! [`Expr`]: struct.Expr.html
+
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{self, *},
+        ResultExt, Token, Tracked,
+    },
+    parser::combinator::no_partial,
+    stream::{Stream, StreamOnce},
+    ErrorOffset, Parser,
+};
+
+/// Associativity of an infix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is parsed as `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is parsed as `a ^ (b ^ c)`
+    Right,
+}
+
+type OperatorParser<'a, Input> = Box<dyn Parser<Input, Output = (), PartialState = ()> + 'a>;
+
+struct Operator<'a, Input, F> {
+    parser: OperatorParser<'a, Input>,
+    left_power: u32,
+    right_power: u32,
+    f: F,
+}
+
+type Unary<'a, Input, O> = Operator<'a, Input, Box<dyn FnMut(O) -> O + 'a>>;
+type Binary<'a, Input, O> = Operator<'a, Input, Box<dyn FnMut(O, O) -> O + 'a>>;
+
+/// Expression parser created by [`expr`].
+///
+/// [`expr`]: fn.expr.html
+pub struct Expr<'a, Input, A>
+where
+    A: Parser<Input>,
+    Input: Stream,
+{
+    atom: A,
+    prefix: Vec<Unary<'a, Input, A::Output>>,
+    infix: Vec<Binary<'a, Input, A::Output>>,
+    postfix: Vec<Unary<'a, Input, A::Output>>,
+}
+
+fn operator_parser<'a, Input, P>(parser: P) -> OperatorParser<'a, Input>
+where
+    Input: Stream,
+    P: Parser<Input> + 'a,
+{
+    no_partial(parser).map(|_| ()).boxed()
+}
+
+// Tries each operator in `operators` which binds at least as tightly as `min_power`, returning the
+// index of the first operator that succeeds and whether it committed input.
+fn parse_operator<Input, F>(
+    operators: &mut [Operator<'_, Input, F>],
+    input: &mut Input,
+    min_power: u32,
+) -> Result<Option<(usize, bool)>, <Input as StreamOnce>::Error>
+where
+    Input: Stream,
+{
+    for (i, operator) in operators.iter_mut().enumerate() {
+        if operator.left_power < min_power {
+            continue;
+        }
+        let before = input.checkpoint();
+        match operator.parser.parse_lazy(input) {
+            PeekOk(()) => return Ok(Some((i, false))),
+            CommitOk(()) => return Ok(Some((i, true))),
+            PeekErr(_) => input.reset(before)?,
+            CommitErr(err) => return Err(err),
+        }
+    }
+    Ok(None)
+}
+
+impl<'a, Input, A> Expr<'a, Input, A>
+where
+    A: Parser<Input>,
+    Input: Stream,
+{
+    /// Registers a prefix operator, such as `-x`. The operand of the operator is parsed using
+    /// `precedence`, so infix operators with a lower precedence apply to the result of `f`.
+    pub fn prefix<P, F>(mut self, op: P, precedence: u16, f: F) -> Self
+    where
+        P: Parser<Input> + 'a,
+        F: FnMut(A::Output) -> A::Output + 'a,
+    {
+        let power = 2 * u32::from(precedence) + 1;
+        self.prefix.push(Operator {
+            parser: operator_parser(op),
+            left_power: 0,
+            right_power: power,
+            f: Box::new(f),
+        });
+        self
+    }
+
+    /// Registers an infix operator, such as `x + y`. Operators with a higher `precedence` bind
+    /// more tightly.
+    pub fn infix<P, F>(mut self, op: P, precedence: u16, assoc: Assoc, f: F) -> Self
+    where
+        P: Parser<Input> + 'a,
+        F: FnMut(A::Output, A::Output) -> A::Output + 'a,
+    {
+        let power = 2 * u32::from(precedence) + 1;
+        let (left_power, right_power) = match assoc {
+            Assoc::Left => (power, power + 1),
+            Assoc::Right => (power, power),
+        };
+        self.infix.push(Operator {
+            parser: operator_parser(op),
+            left_power,
+            right_power,
+            f: Box::new(f),
+        });
+        self
+    }
+
+    /// Registers a postfix operator, such as `x!`.
+    pub fn postfix<P, F>(mut self, op: P, precedence: u16, f: F) -> Self
+    where
+        P: Parser<Input> + 'a,
+        F: FnMut(A::Output) -> A::Output + 'a,
+    {
+        let power = 2 * u32::from(precedence) + 1;
+        self.postfix.push(Operator {
+            parser: operator_parser(op),
+            left_power: power,
+            right_power: 0,
+            f: Box::new(f),
+        });
+        self
+    }
+
+    // `committed` is `true` if an operator has already committed input before this call, in which
+    // case the errors of a failing operand are added directly.
+    fn parse_expr(
+        &mut self,
+        input: &mut Input,
+        min_power: u32,
+        committed: bool,
+    ) -> ParseResult<A::Output, <Input as StreamOnce>::Error> {
+        macro_rules! operand {
+            ($result: expr, $committed: ident) => {
+                match $result {
+                    PeekOk(x) => x,
+                    CommitOk(x) => {
+                        $committed = true;
+                        x
+                    }
+                    PeekErr(err) => {
+                        return if $committed {
+                            CommitErr(err.into_error())
+                        } else {
+                            PeekErr(err)
+                        };
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                }
+            };
+        }
+
+        let mut consumed = false;
+        let mut lhs = match parse_operator(&mut self.prefix, input, 0) {
+            Err(err) => return CommitErr(err),
+            Ok(Some((i, op_committed))) => {
+                consumed = op_committed;
+                let power = self.prefix[i].right_power;
+                let operand = operand!(self.parse_expr(input, power, consumed), consumed);
+                (self.prefix[i].f)(operand)
+            }
+            Ok(None) => {
+                let before = input.checkpoint();
+                match self.atom.parse_lazy(input) {
+                    PeekErr(mut err) if committed => {
+                        ctry!(input.reset(before.clone()).committed());
+                        if let Ok(t) = input.uncons() {
+                            ctry!(input.reset(before).committed());
+                            err.error.add_unexpected(Token(t));
+                        }
+                        self.add_error(&mut err);
+                        return PeekErr(err);
+                    }
+                    result => operand!(result, consumed),
+                }
+            }
+        };
+
+        loop {
+            match parse_operator(&mut self.postfix, input, min_power) {
+                Err(err) => return CommitErr(err),
+                Ok(Some((i, op_committed))) => {
+                    consumed |= op_committed;
+                    lhs = (self.postfix[i].f)(lhs);
+                    continue;
+                }
+                Ok(None) => (),
+            }
+
+            match parse_operator(&mut self.infix, input, min_power) {
+                Err(err) => return CommitErr(err),
+                Ok(Some((i, op_committed))) => {
+                    consumed |= op_committed;
+                    let power = self.infix[i].right_power;
+                    let rhs = operand!(self.parse_expr(input, power, consumed), consumed);
+                    lhs = (self.infix[i].f)(lhs, rhs);
+                }
+                Ok(None) => break,
+            }
+        }
+
+        if consumed {
+            CommitOk(lhs)
+        } else {
+            PeekOk(lhs)
+        }
+    }
+}
+
+impl<'a, Input, A> Parser<Input> for Expr<'a, Input, A>
+where
+    A: Parser<Input>,
+    Input: Stream,
+{
+    type Output = A::Output;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.parse_expr(input, 0, false)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        // An expression starts with either a prefix operator or an atom
+        if errors.offset != ErrorOffset(0) {
+            let offset = errors.offset;
+            for operator in &mut self.prefix {
+                errors.offset = ErrorOffset(1);
+                operator.parser.add_error(errors);
+            }
+            errors.offset = offset;
+            self.atom.add_error(errors);
+        }
+    }
+}
+
+/// Creates an expression parser with `atom` as the parser for the operands. Operators are then
+/// added with [`Expr::prefix`], [`Expr::infix`] and [`Expr::postfix`].
+///
+/// When several operators of the same kind match the input the one registered first is used.
+///
+/// NOTE: The resulting parser does not support partial parsing.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::expr::{expr, Assoc};
+/// # fn main() {
+/// let number = digit().map(|c| c.to_digit(10).unwrap() as i32);
+/// let mut parser = expr(number)
+///     .infix(char('+'), 1, Assoc::Left, |l, r| l + r)
+///     .infix(char('*'), 2, Assoc::Left, |l, r| l * r)
+///     .postfix(char('!'), 3, |x| (1..=x).product());
+/// assert_eq!(parser.parse("1+2*3!"), Ok((13, "")));
+/// assert_eq!(parser.parse("2*3+4"), Ok((10, "")));
+/// # }
+/// ```
+///
+/// [`Expr::prefix`]: struct.Expr.html#method.prefix
+/// [`Expr::infix`]: struct.Expr.html#method.infix
+/// [`Expr::postfix`]: struct.Expr.html#method.postfix
+pub fn expr<'a, Input, A>(atom: A) -> Expr<'a, Input, A>
+where
+    A: Parser<Input>,
+    Input: Stream,
+{
+    Expr {
+        atom,
+        prefix: Vec::new(),
+        infix: Vec::new(),
+        postfix: Vec::new(),
+    }
+}
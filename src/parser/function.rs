@@ -1,12 +1,23 @@
 //! Parsers constructor from regular functions
 
 use crate::{
-    error::{ParseResult, StdParseResult},
+    error::{ParseResult, StdParseResult, Tracked},
     lib::marker::PhantomData,
-    stream::Stream,
+    parser::ParseMode,
+    stream::{Stream, StreamOnce},
     Parser,
 };
 
+#[cfg(feature = "nom")]
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{CommitErr, PeekErr},
+        StreamError,
+    },
+    stream::{uncons_range, wrap_stream_error, Range as StreamRange, RangeStream, StreamErrorFor},
+};
+
 impl<'a, Input: Stream, O> Parser<Input>
     for dyn FnMut(&mut Input) -> StdParseResult<O, Input> + 'a
 {
@@ -177,3 +188,233 @@ where
 {
     EnvParser { env, parser }
 }
+
+/// A simplified version of [`Parser`] for hand written parsers which want to support partial
+/// parsing without implementing `parse_mode` and its related methods directly.
+///
+/// Use [`simple`] to turn a `SimpleParser` into a [`Parser`].
+///
+/// [`Parser`]: ../trait.Parser.html
+/// [`simple`]: fn.simple.html
+pub trait SimpleParser<Input: Stream> {
+    /// The type which is returned if the parser is successful.
+    type Output;
+
+    /// The state stored between calls when parsing a partial stream.
+    ///
+    /// The state is reset to its default value whenever a new parse starts so it only carries
+    /// data from an earlier call which ended with an end of input error on a partial stream.
+    type PartialState: Default;
+
+    /// Parses `input`, picking up where an earlier call left off if `state` is not its default
+    /// value.
+    ///
+    /// Any input committed to before returning an end of input error on a partial stream must be
+    /// recorded in `state` as it is not passed to the parser again when parsing is resumed.
+    fn parse_partial(
+        &mut self,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>;
+
+    /// Adds the expected errors of this parser to `errors`. Called when this parser fails
+    /// without committing.
+    fn add_error(&mut self, _errors: &mut Tracked<<Input as StreamOnce>::Error>) {}
+}
+
+#[derive(Copy, Clone)]
+pub struct Simple<P>(P);
+
+impl<Input, P> Parser<Input> for Simple<P>
+where
+    Input: Stream,
+    P: SimpleParser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        if mode.is_first() {
+            *state = Default::default();
+        }
+        self.0.parse_partial(input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+}
+
+/// Turns a [`SimpleParser`] into a [`Parser`], taking care of resetting its state when a new parse
+/// starts so that it behaves correctly when parsing both complete and partial input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::error::{ParseError, ParseResult, StreamError};
+/// # use combine::parser::function::{simple, SimpleParser};
+/// # use combine::stream::{PartialStream, ResetStream};
+/// # fn main() {
+/// // Counts the `a` characters at the start of the input
+/// struct CountA;
+///
+/// impl<Input> SimpleParser<Input> for CountA
+/// where
+///     Input: Stream<Token = char>,
+/// {
+///     type Output = usize;
+///     type PartialState = usize;
+///
+///     fn parse_partial(
+///         &mut self,
+///         input: &mut Input,
+///         count: &mut usize,
+///     ) -> ParseResult<usize, Input::Error> {
+///         loop {
+///             let checkpoint = input.checkpoint();
+///             match input.uncons() {
+///                 Ok('a') => *count += 1,
+///                 Ok(_) => {
+///                     if let Err(err) = input.reset(checkpoint) {
+///                         return ParseResult::CommitErr(err);
+///                     }
+///                     break;
+///                 }
+///                 Err(err) => {
+///                     if input.is_partial() && err.is_unexpected_end_of_input() {
+///                         // Keep the count so parsing can resume once more input is available
+///                         return ParseResult::CommitErr(Input::Error::from_error(
+///                             input.position(),
+///                             err,
+///                         ));
+///                     }
+///                     break;
+///                 }
+///             }
+///         }
+///         match std::mem::replace(count, 0) {
+///             0 => ParseResult::PeekOk(0),
+///             n => ParseResult::CommitOk(n),
+///         }
+///     }
+/// }
+///
+/// let mut parser = simple(CountA);
+/// assert_eq!(parser.parse("aab"), Ok((2, "b")));
+///
+/// let mut state = Default::default();
+/// assert!(parser.parse_with_state(&mut PartialStream("aa"), &mut state).is_err());
+/// assert_eq!(parser.parse_with_state(&mut PartialStream("ab"), &mut state), Ok(3));
+/// # }
+/// ```
+pub fn simple<P>(parser: P) -> Simple<P> {
+    Simple(parser)
+}
+
+#[cfg(feature = "nom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nom")))]
+#[derive(Copy, Clone)]
+pub struct FromNom<F, Input>(F, PhantomData<fn(Input) -> Input>);
+
+#[cfg(feature = "nom")]
+impl<Input, O, F> Parser<Input> for FromNom<F, Input>
+where
+    Input: RangeStream,
+    Input::Range: StreamRange,
+    F: FnMut(Input::Range) -> nom_7::IResult<Input::Range, O>,
+{
+    type Output = O;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, Input::Error> {
+        let len = input.range().len();
+        match (self.0)(input.range()) {
+            Ok((rest, output)) => uncons_range(input, len - rest.len()).map(|_| output),
+            // A recoverable error lets alternatives be tried, just as `nom::branch::alt` does
+            Err(nom_7::Err::Error(err)) => PeekErr(
+                Input::Error::from_error(
+                    input.position(),
+                    StreamError::message_format(err.code.description()),
+                )
+                .into(),
+            ),
+            // `cut` was used so the error is reported where `nom` failed
+            Err(nom_7::Err::Failure(err)) => {
+                ctry!(uncons_range(input, len - err.input.len()));
+                CommitErr(Input::Error::from_error(
+                    input.position(),
+                    StreamError::message_format(err.code.description()),
+                ))
+            }
+            Err(nom_7::Err::Incomplete(_)) => {
+                wrap_stream_error(input, StreamErrorFor::<Input>::end_of_input())
+            }
+        }
+    }
+}
+
+/// Wraps a [`nom`][] parser, turning it into a parser of ranges such as `&[u8]` and `&str`.
+///
+/// `nom::Err::Incomplete` is reported as the end of input, letting partial parsing request more
+/// input before `f` is called again from the start. `nom::Err::Error` fails without committing so
+/// other alternatives are tried while `nom::Err::Failure` commits to the error.
+///
+/// ```
+/// # extern crate combine;
+/// # extern crate nom_7 as nom;
+/// # use combine::*;
+/// # use combine::parser::function::from_nom;
+/// # use combine::stream::PartialStream;
+/// # fn main() {
+/// fn request<'a, Input>() -> impl Parser<Input, Output = (&'a [u8], u8, &'a [u8])>
+/// where
+///     Input: RangeStream<Token = u8, Range = &'a [u8]>,
+/// {
+///     (
+///         from_nom(nom::bytes::streaming::tag("GET")),
+///         token(b' '),
+///         from_nom(nom::character::streaming::digit1),
+///     )
+/// }
+///
+/// assert_eq!(
+///     request().parse(&b"GET 123\n"[..]),
+///     Ok(((&b"GET"[..], b' ', &b"123"[..]), &b"\n"[..]))
+/// );
+/// assert!(request().parse(&b"PUT 1\n"[..]).is_err());
+///
+/// // `digit1` is called again with all of the digits once the rest of them are available
+/// let mut parser = request();
+/// let mut state = Default::default();
+/// assert!(parser.parse_with_state(&mut PartialStream(&b"GET 1"[..]), &mut state).is_err());
+/// assert_eq!(
+///     parser.parse_with_state(&mut PartialStream(&b"123\n"[..]), &mut state),
+///     Ok((&b"GET"[..], b' ', &b"123"[..]))
+/// );
+///
+/// let mut word = from_nom(nom::character::complete::alpha1);
+/// assert_eq!(word.parse("abc1"), Ok(("abc", "1")));
+/// # }
+/// ```
+///
+/// [`nom`]: https://docs.rs/nom
+#[cfg(feature = "nom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nom")))]
+pub fn from_nom<Input, O, F>(f: F) -> FromNom<F, Input>
+where
+    Input: RangeStream,
+    Input::Range: StreamRange,
+    F: FnMut(Input::Range) -> nom_7::IResult<Input::Range, O>,
+{
+    FromNom(f, PhantomData)
+}
@@ -0,0 +1,356 @@
+//! Parsers for indentation sensitive grammars such as Python or YAML.
+//!
+//! The columns of the enclosing blocks are kept in an [`IndentStack`][] which is stored as the
+//! user state of a [`state::Stream`][] (directly or through [`IndentState`][]). [`block`][] opens a
+//! new block at the column of its first token, [`aligned`][] parses a line of the innermost block,
+//! [`indented`][] parses a continuation to the right of it and [`dedent`][] checks that the block
+//! has ended.
+//!
+//! These parsers only look at the column of the next token so whitespace, including newlines, must
+//! be skipped before them.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::char::{letter, spaces, string};
+//! # use combine::parser::indent::{self, aligned, block, IndentStack};
+//! # use combine::stream::{position, state};
+//! # fn main() {
+//! #[derive(Debug, PartialEq)]
+//! enum Stmt {
+//!     Word(String),
+//!     Block(Vec<Stmt>),
+//! }
+//!
+//! parser! {
+//!     fn stmt[Input]()(Input) -> Stmt
+//!     where [Input: Stream<Token = char>, Input: indent::IndentStream]
+//!     {
+//!         choice((
+//!             attempt(string("do:")).skip(spaces()).with(statements()).map(Stmt::Block),
+//!             many1(letter()).skip(spaces()).map(Stmt::Word),
+//!         ))
+//!     }
+//! }
+//!
+//! parser! {
+//!     fn statements[Input]()(Input) -> Vec<Stmt>
+//!     where [Input: Stream<Token = char>, Input: indent::IndentStream]
+//!     {
+//!         block(many1(aligned(stmt())))
+//!     }
+//! }
+//!
+//! let text = "\
+//! a
+//! do:
+//!   b
+//!   do:
+//!     c
+//!   d
+//! e
+//! ";
+//! let mut input = state::Stream {
+//!     stream: position::Stream::new(text),
+//!     state: IndentStack::new(),
+//! };
+//! let result = statements().skip(eof()).parse_stream(&mut input).into_result();
+//! let word = |s: &str| Stmt::Word(s.to_string());
+//! assert_eq!(
+//!     result.map(|t| t.0),
+//!     Ok(vec![
+//!         word("a"),
+//!         Stmt::Block(vec![word("b"), Stmt::Block(vec![word("c")]), word("d")]),
+//!         word("e"),
+//!     ])
+//! );
+//! # }
+//! ```
+//!
+//! [`IndentStack`]: struct.IndentStack.html
+//! [`IndentState`]: trait.IndentState.html
+//! [`state::Stream`]: ../../stream/state/struct.Stream.html
+//! [`block`]: fn.block.html
+//! [`aligned`]: fn.aligned.html
+//! [`indented`]: fn.indented.html
+//! [`dedent`]: fn.dedent.html
+
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{self, *},
+        StreamError, Tracked,
+    },
+    lib::{fmt, marker::PhantomData},
+    parser::{reflect::Visitor, ParseMode},
+    stream::{input_at_eof, position::Column, state, Positioned},
+    Parser, Stream,
+};
+
+/// The columns of the blocks enclosing the current position, innermost last.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndentStack(Vec<i32>);
+
+impl IndentStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the column of the innermost block or `0` if no block has been opened.
+    pub fn level(&self) -> i32 {
+        self.0.last().cloned().unwrap_or(0)
+    }
+
+    /// Returns the number of open blocks.
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn push(&mut self, column: i32) {
+        self.0.push(column);
+    }
+
+    pub fn pop(&mut self) -> Option<i32> {
+        self.0.pop()
+    }
+}
+
+/// User states which contain an `IndentStack`.
+pub trait IndentState {
+    fn indent_stack(&mut self) -> &mut IndentStack;
+}
+
+impl IndentState for IndentStack {
+    fn indent_stack(&mut self) -> &mut IndentStack {
+        self
+    }
+}
+
+impl<T> IndentState for &mut T
+where
+    T: IndentState + ?Sized,
+{
+    fn indent_stack(&mut self) -> &mut IndentStack {
+        (**self).indent_stack()
+    }
+}
+
+/// Streams which the parsers in this module can be used with.
+///
+/// Implemented for [`state::Stream`][] when the user state implements `IndentState` and the
+/// position knows its column.
+///
+/// [`state::Stream`]: ../../stream/state/struct.Stream.html
+pub trait IndentStream: Stream {
+    fn indent_stack(&mut self) -> &mut IndentStack;
+
+    fn column(&self) -> i32;
+}
+
+impl<S, U> IndentStream for state::Stream<S, U>
+where
+    S: Stream,
+    S::Position: Column,
+    U: IndentState,
+{
+    fn indent_stack(&mut self) -> &mut IndentStack {
+        self.state.indent_stack()
+    }
+
+    fn column(&self) -> i32 {
+        self.position().column()
+    }
+}
+
+fn column_error<Input>(input: &Input, args: fmt::Arguments<'_>) -> Input::Error
+where
+    Input: IndentStream,
+{
+    Input::Error::from_error(input.position(), StreamError::message_format(args))
+}
+
+#[derive(Copy, Clone)]
+pub struct Block<P>(P);
+
+impl<Input, P> Parser<Input> for Block<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
+        let level = input.indent_stack().level();
+        let column = input.column();
+        if column <= level {
+            return PeekErr(
+                column_error(
+                    input,
+                    format_args!("expected a block indented past column {}", level),
+                )
+                .into(),
+            );
+        }
+        input.indent_stack().push(column);
+        let result = self.0.parse_lazy(input);
+        input.indent_stack().pop();
+        result
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<Input::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        self.0.reflect(visitor)
+    }
+}
+
+/// Parses `p` as a block whose lines are aligned to the column `p` starts at, which must be
+/// further to the right than the enclosing block.
+///
+/// The lines of the block are usually parsed with [`aligned`].
+///
+/// [`aligned`]: fn.aligned.html
+pub fn block<Input, P>(p: P) -> Block<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    Block(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Aligned<P>(P);
+
+impl<Input, P> Parser<Input> for Aligned<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        if mode.is_first() {
+            let level = input.indent_stack().level();
+            if input.column() != level {
+                return PeekErr(
+                    column_error(input, format_args!("expected a line at column {}", level)).into(),
+                );
+            }
+        }
+        self.0.parse_mode(mode, input, state)
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Parses `p` if it starts exactly at the column of the innermost [`block`].
+///
+/// [`block`]: fn.block.html
+pub fn aligned<Input, P>(p: P) -> Aligned<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    Aligned(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Indented<P>(P);
+
+impl<Input, P> Parser<Input> for Indented<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        if mode.is_first() {
+            let level = input.indent_stack().level();
+            if input.column() <= level {
+                return PeekErr(
+                    column_error(
+                        input,
+                        format_args!("expected indentation past column {}", level),
+                    )
+                    .into(),
+                );
+            }
+        }
+        self.0.parse_mode(mode, input, state)
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Parses `p` if it starts to the right of the column of the innermost [`block`], such as the
+/// continuation of a line which has been split.
+///
+/// [`block`]: fn.block.html
+pub fn indented<Input, P>(p: P) -> Indented<P>
+where
+    Input: IndentStream,
+    P: Parser<Input>,
+{
+    Indented(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Dedent<Input>(PhantomData<Input>);
+
+impl<Input> Parser<Input> for Dedent<Input>
+where
+    Input: IndentStream,
+{
+    type Output = ();
+    type PartialState = ();
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<(), Input::Error> {
+        let level = input.indent_stack().level();
+        if input.column() < level || input_at_eof(input) {
+            PeekOk(())
+        } else {
+            PeekErr(
+                column_error(
+                    input,
+                    format_args!("expected a dedent to before column {}", level),
+                )
+                .into(),
+            )
+        }
+    }
+}
+
+/// Succeeds without consuming input if the next token is to the left of the column of the
+/// innermost [`block`] or if the input is empty, that is, if the block has ended.
+///
+/// [`block`]: fn.block.html
+pub fn dedent<Input>() -> Dedent<Input>
+where
+    Input: IndentStream,
+{
+    Dedent(PhantomData)
+}
@@ -9,21 +9,58 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, Token, Tracked,
     },
+    lib::fmt,
     parser::{
-        combinator::{and_then, flat_map, map, map_input, AndThen, Either, FlatMap, Map, MapInput},
-        error::{expected, message, silent, Expected, Message, Silent},
-        repeat::Iter,
-        sequence::{then, then_partial, then_ref, Then, ThenPartial, ThenRef},
+        combinator::{
+            and_then, and_then_full, ensure, flat_map, map, map_input, spanned, trace,
+            with_position, AndThen, AndThenFull, Either, Ensure, FlatMap, Interned, Map, MapInput,
+            Spanned, Trace, WithPosition,
+        },
+        error::{
+            expected, label, map_err, message, silent, Expected, Label, MapErr, Message, Silent,
+        },
+        reflect::Visitor,
+        repeat::{fold_many, repeat, CountMinMax, FoldMany, Iter, ParseIter},
+        sequence::{
+            then, then_input, then_partial, then_partial_ref, then_ref, Then, ThenInput,
+            ThenPartial, ThenPartialRef, ThenRef,
+        },
     },
     stream::{Stream, StreamOnce},
     ErrorOffset,
 };
 
 use self::{
-    choice::{or, Or},
+    choice::{optional_or, or, OptionalOr, Or},
     sequence::{skip, with, Skip, With},
 };
 
+/// The `PartialState` of the parser `P` when parsing `Input`.
+///
+/// Lets the state of a parser be stored (for instance between calls to [`decode`]) without
+/// spelling out the, often very large, state type.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::{repeat::{many1, Many1}, token::{token, Token}, PartialStateOf};
+/// # use combine::stream::{decode, PartialStream};
+/// # fn main() {
+/// type Input<'a> = PartialStream<&'a str>;
+/// type Letters<'a> = (Many1<String, Token<Input<'a>>>, Token<Input<'a>>);
+///
+/// let mut state: PartialStateOf<Input, Letters> = Default::default();
+/// let mut parser: Letters = (many1(token('a')), token(';'));
+///
+/// let result = decode(&mut parser, &mut PartialStream("aa"), &mut state);
+/// assert_eq!(result, Ok((None, 2)));
+/// let result = decode(&mut parser, &mut PartialStream("a;"), &mut state);
+/// assert_eq!(result, Ok((Some(("aaa".to_string(), ';')), 2)));
+/// # }
+/// ```
+///
+/// [`decode`]: ../stream/fn.decode.html
+pub type PartialStateOf<Input, P> = <P as Parser<Input>>::PartialState;
+
 /// Internal API. May break without a semver bump
 #[macro_export]
 #[doc(hidden)]
@@ -50,12 +87,26 @@ macro_rules! parse_mode {
 }
 
 pub mod byte;
+pub mod byte_set;
 pub mod char;
 pub mod choice;
 pub mod combinator;
 pub mod error;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod event;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod expr;
 pub mod function;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod indent;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod packrat;
 pub mod range;
+pub mod reflect;
 #[cfg(feature = "regex")]
 #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
 pub mod regex;
@@ -102,7 +153,7 @@ pub trait Parser<Input: Stream> {
     ) -> Result<(Self::Output, Input), <Input as StreamOnce>::Error> {
         match self.parse_stream(&mut input).into() {
             Ok((v, _)) => Ok((v, input)),
-            Err(error) => Err(error.into_inner().error),
+            Err(error) => Err(error.into_inner().into_error()),
         }
     }
 
@@ -118,7 +169,7 @@ pub trait Parser<Input: Stream> {
     ) -> Result<Self::Output, <Input as StreamOnce>::Error> {
         match self.parse_stream_partial(input, state).into() {
             Ok((v, _)) => Ok(v),
-            Err(error) => Err(error.into_inner().error),
+            Err(error) => Err(error.into_inner().into_error()),
         }
     }
 
@@ -197,6 +248,16 @@ pub trait Parser<Input: Stream> {
     /// [`parse_lazy`]: trait.Parser.html#method.parse_lazy
     fn add_error(&mut self, _error: &mut Tracked<<Input as StreamOnce>::Error>) {}
 
+    /// Describes the structure of this parser to `visitor`.
+    ///
+    /// Combinators report themselves and then describe the parsers inside them, other parsers
+    /// report themselves as opaque. See the [`reflect`] module for details.
+    ///
+    /// [`reflect`]: reflect/index.html
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visitor.opaque()
+    }
+
     /// Like `parse_stream` but supports partial parsing.
     #[inline]
     fn parse_stream_partial(
@@ -320,6 +381,21 @@ pub trait Parser<Input: Stream> {
     fn add_committed_expected_error(&mut self, _error: &mut Tracked<<Input as StreamOnce>::Error>) {
     }
 
+    /// Parses `self` for as long as it succeeds, passing each output to `f`.
+    ///
+    /// Returns `None`, without consuming any input, unless `self` always parses exactly one token
+    /// and `input` implements `StreamOnce::uncons_tokens_while`.
+    ///
+    /// Internal API: This should not be implemented explicitly outside of combine.
+    #[doc(hidden)]
+    fn parse_many_tokens(
+        &mut self,
+        _input: &mut Input,
+        _f: &mut dyn FnMut(Self::Output),
+    ) -> Option<ParseResult<(), <Input as StreamOnce>::Error>> {
+        None
+    }
+
     /// Borrows a parser instead of consuming it.
     ///
     /// Used to apply parser combinators on `self` without losing ownership.
@@ -458,13 +534,67 @@ pub trait Parser<Input: Stream> {
         or(self, p)
     }
 
+    /// Parses using `self` and outputs its value if it succeeds or the value returned by `default`
+    /// if it fails without consuming any input.
+    ///
+    /// See [`optional_or`](choice/fn.optional_or.html).
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut parser = digit().or_value(|| '0');
+    /// assert_eq!(parser.parse("1"), Ok(('1', "")));
+    /// assert_eq!(parser.parse("a"), Ok(('0', "a")));
+    /// # }
+    /// ```
+    fn or_value<F>(self, default: F) -> OptionalOr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut() -> Self::Output,
+    {
+        optional_or(self, default)
+    }
+
+    /// Parses using `self` as many times as allowed by `range` and collects the values into `F`.
+    ///
+    /// See [`repeat`](repeat/fn.repeat.html).
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let min = 2;
+    /// let mut parser = digit().repeat(min..);
+    /// assert_eq!(parser.parse("123a"), Ok((String::from("123"), "a")));
+    /// assert!(parser.parse("1a").is_err());
+    /// # }
+    /// ```
+    fn repeat<F, R>(self, range: R) -> CountMinMax<F, Self>
+    where
+        Self: Sized,
+        F: Extend<Self::Output> + Default,
+        R: crate::lib::ops::RangeBounds<usize>,
+    {
+        repeat(range, self)
+    }
+
     /// Parses using `self` and then passes the value to `f` which returns a parser used to parse
     /// the rest of the input.
     ///
     /// Since the parser returned from `f` must have a single type it can be useful to use the
     /// `left` and `right` methods to merge parsers of differing types into one.
     ///
-    /// If you are using partial parsing you may want to use `partial_then` instead.
+    /// When partial parsing, the parser returned by `f` is stored in the partial state so parsing
+    /// can resume with it. If the partial state needs to be `'static` (as with
+    /// [`any_partial_state`]) that is not possible for parsers over borrowed input, use
+    /// [`then_partial`] or [`then_partial_ref`] instead.
+    ///
+    /// [`any_partial_state`]: combinator/fn.any_partial_state.html
+    /// [`then_partial`]: trait.Parser.html#method.then_partial
+    /// [`then_partial_ref`]: trait.Parser.html#method.then_partial_ref
     ///
     /// ```
     /// # #![cfg(feature = "std")]
@@ -500,7 +630,11 @@ pub trait Parser<Input: Stream> {
     ///
     /// Useful when doing partial parsing since it does not need to store the parser returned by
     /// `f` in the partial state. Instead it will call `f` each to request a new parser each time
-    /// parsing resumes and that parser is needed.
+    /// parsing resumes and that parser is needed. As `f` may be called several times it only
+    /// receives a reference to the value, [`then_partial_ref`] returns the value along with the
+    /// output of the parser so it does not need to be cloned.
+    ///
+    /// [`then_partial_ref`]: trait.Parser.html#method.then_partial_ref
     ///
     /// Since the parser returned from `f` must have a single type it can be useful to use the
     /// `left` and `right` methods to merge parsers of differing types into one.
@@ -535,6 +669,45 @@ pub trait Parser<Input: Stream> {
         then_partial(self, f)
     }
 
+    /// Variant of `then_partial` which returns the value produced by `self` together with the
+    /// output of the parser returned by `f`.
+    ///
+    /// `f` only borrows the value while the value itself is kept in the partial state, so
+    /// intermediate values such as a parsed header which decides how to parse the body can be
+    /// returned by value without being cloned.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter};
+    /// # fn main() {
+    /// #[derive(Debug, PartialEq)]
+    /// struct Header {
+    ///     name: String,
+    ///     len: usize,
+    /// }
+    /// let header = (many1(letter()), digit()).map(|(name, len): (String, char)| Header {
+    ///     name,
+    ///     len: len.to_digit(10).unwrap() as usize,
+    /// });
+    /// let mut parser = header.then_partial_ref(|header| {
+    ///     count_min_max::<String, _, _>(header.len, header.len, letter())
+    /// });
+    /// assert_eq!(
+    ///     parser.parse("abc2xy"),
+    ///     Ok(((Header { name: "abc".into(), len: 2 }, "xy".to_string()), ""))
+    /// );
+    /// # }
+    /// ```
+    fn then_partial_ref<N, F>(self, f: F) -> ThenPartialRef<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self::Output) -> N,
+        N: Parser<Input>,
+    {
+        then_partial_ref(self, f)
+    }
+
     /// Parses using `self` and then passes a reference to the value to `f` which returns a parser
     /// used to parse the rest of the input. The value is then combined with the output of `f`.
     ///
@@ -571,6 +744,38 @@ pub trait Parser<Input: Stream> {
         then_ref(self, f)
     }
 
+    /// Variant of `then` which also passes the input to `f`, so the parser returned by `f` can
+    /// depend on the current position or the remaining input.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{char, letter};
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// // `#` only starts a comment at the start of a line
+    /// type Input<'a> = position::Stream<&'a str, SourcePosition>;
+    /// let mut parser = char('#').then_input(|_, input: &mut Input| {
+    ///     if input.position().column == 2 {
+    ///         many::<String, _, _>(letter()).left()
+    ///     } else {
+    ///         unexpected_any("#").message("comments must start a line").right()
+    ///     }
+    /// });
+    /// let result = parser.parse(position::Stream::new("#abc")).map(|x| x.0);
+    /// assert_eq!(result, Ok("abc".to_string()));
+    /// assert!((letter(), parser).parse(position::Stream::new("a#bc")).is_err());
+    /// # }
+    /// ```
+    fn then_input<N, F>(self, f: F) -> ThenInput<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Output, &mut Input) -> N,
+        N: Parser<Input>,
+    {
+        then_input(self, f)
+    }
+
     /// Uses `f` to map over the parsed value.
     ///
     /// ```
@@ -593,6 +798,26 @@ pub trait Parser<Input: Stream> {
         map(self, f)
     }
 
+    /// Uses `f` to map over the parsed value, also passing the input that remains after `self`
+    /// to `f`. This gives access to the position after `self` (and the remaining range of a
+    /// `RangeStream`).
+    ///
+    /// NOTE: `f` should not consume any input.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::letter;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let mut parser = many1::<String, _, _>(letter())
+    ///     .map_input(|word, input: &mut position::Stream<&str, SourcePosition>| {
+    ///         (word, input.position().column)
+    ///     });
+    /// let result = parser.parse(position::Stream::new("abc1")).map(|x| x.0);
+    /// assert_eq!(result, Ok(("abc".to_string(), 4)));
+    /// # }
+    /// ```
     fn map_input<F, B>(self, f: F) -> MapInput<Self, F>
     where
         Self: Sized,
@@ -601,6 +826,119 @@ pub trait Parser<Input: Stream> {
         map_input(self, f)
     }
 
+    /// Interns the string produced by `self` in the [`Interner`] stored as the user state of a
+    /// [`state::Stream`], returning its symbol instead of the string.
+    ///
+    /// Other shared, mutable state can be reached the same way through `input.state` in
+    /// [`map_input`].
+    ///
+    /// [`Interner`]: ../stream/state/trait.Interner.html
+    /// [`state::Stream`]: ../stream/state/struct.Stream.html
+    /// [`map_input`]: #method.map_input
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::spaces;
+    /// # use combine::parser::range::take_while1;
+    /// # use combine::stream::state::{self, StringInterner};
+    /// # fn main() {
+    /// let ident = take_while1(|c: char| c.is_alphabetic()).interned();
+    /// let mut parser = many::<Vec<_>, _, _>(ident.skip(spaces()));
+    ///
+    /// let mut input = state::Stream {
+    ///     stream: "let x let y x",
+    ///     state: StringInterner::new(),
+    /// };
+    /// let symbols = parser.parse_stream(&mut input).into_result().map(|t| t.0);
+    /// assert_eq!(symbols, Ok(vec![0, 1, 0, 2, 1]));
+    /// assert_eq!(input.state.len(), 3);
+    /// assert_eq!(input.state.resolve(2), Some("y"));
+    /// # }
+    /// ```
+    fn interned(self) -> Interned<Self>
+    where
+        Self: Sized,
+    {
+        Interned(self)
+    }
+
+    /// Returns the position before `self` was parsed together with its output.
+    ///
+    /// See [`combinator::with_position`](combinator/fn.with_position.html).
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::letter;
+    /// # use combine::stream::position::{self, IndexPositioner};
+    /// # fn main() {
+    /// let result = token('(')
+    ///     .with(many1::<String, _, _>(letter()).with_position())
+    ///     .parse(position::Stream::with_positioner("(abc)", IndexPositioner::new()))
+    ///     .map(|x| x.0);
+    /// assert_eq!(result, Ok((1, "abc".to_string())));
+    /// # }
+    /// ```
+    fn with_position(self) -> WithPosition<Self>
+    where
+        Self: Sized,
+    {
+        with_position(self)
+    }
+
+    /// Returns the range of positions `self` was parsed at together with its output.
+    ///
+    /// See [`combinator::spanned`](combinator/fn.spanned.html).
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::letter;
+    /// # use combine::stream::position::{self, IndexPositioner};
+    /// # fn main() {
+    /// let result = token('(')
+    ///     .with(many1::<String, _, _>(letter()).spanned())
+    ///     .parse(position::Stream::with_positioner("(abc)", IndexPositioner::new()))
+    ///     .map(|x| x.0);
+    /// assert_eq!(result, Ok((1..4, "abc".to_string())));
+    /// # }
+    /// ```
+    fn spanned(self) -> Spanned<Self>
+    where
+        Self: Sized,
+    {
+        spanned(self)
+    }
+
+    /// Logs when `self` is entered and with which result it exits, using the `log` crate at the
+    /// `trace` level, together with the position and the upcoming input. Every message starts
+    /// with `name`.
+    ///
+    /// Nothing is logged (and the parser behaves exactly like `self`) unless the `trace` feature
+    /// is enabled. With the `tracing` feature a `tracing` span named `parse` is also emitted, with
+    /// the `parser`, `mode`, `position` and `result` fields.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{char, digit};
+    /// # fn main() {
+    /// // With the `trace` feature this logs messages such as
+    /// // `number: enter at 0x.. before '1' '2' ')' <end>` and `number: CommitOk at 0x..`
+    /// let mut parser = between(char('('), char(')'), many1::<String, _, _>(digit()).trace("number"));
+    /// assert_eq!(parser.parse("(12)"), Ok(("12".to_string(), "")));
+    /// # }
+    /// ```
+    fn trace(self, name: &'static str) -> Trace<Self>
+    where
+        Self: Sized,
+        Input::Token: fmt::Debug,
+        Input::Position: fmt::Debug,
+    {
+        trace(self, name)
+    }
+
     /// Uses `f` to map over the output of `self`. If `f` returns an error the parser fails.
     ///
     /// ```
@@ -625,6 +963,11 @@ pub trait Parser<Input: Stream> {
 
     /// Parses with `self` and if it fails, adds the message `msg` to the error.
     ///
+    /// Besides `&'static str`, `msg` may be an owned `String` or `Cow<'static, str>`, or a
+    /// [`LazyFormat`] which only formats the message when the error is created.
+    ///
+    /// [`LazyFormat`]: ../error/struct.LazyFormat.html
+    ///
     /// ```
     /// # #![cfg(feature = "std")]
     /// # extern crate combine;
@@ -685,6 +1028,18 @@ pub trait Parser<Input: Stream> {
     ///         easy::Error::Expected("That is not a nine!".to_string().into())
     ///     ]
     /// }));
+    ///
+    /// let digit = 9;
+    /// let result = token('9')
+    ///     .expected(format!("the digit {}", digit))
+    ///     .easy_parse(position::Stream::new("8"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition::default(),
+    ///     errors: vec![
+    ///         easy::Error::Unexpected('8'.into()),
+    ///         easy::Error::Expected("the digit 9".to_string().into())
+    ///     ]
+    /// }));
     /// # }
     /// ```
     fn expected<S>(self, msg: S) -> Expected<Self, S>
@@ -695,8 +1050,82 @@ pub trait Parser<Input: Stream> {
         expected(self, msg)
     }
 
-    /// Parses with `self`, if it fails without consuming any input any expected errors that would
-    /// otherwise be emitted by `self` are suppressed.
+    /// Names the grammar rule parsed by `self`. Like [`expected`][] the expected errors are
+    /// replaced by `name` if `self` fails without consuming any input, and with the `trace`
+    /// feature enabled entering and exiting the rule is also logged under `name` (see
+    /// [`trace`][]). The `tracing` feature emits a span for the rule like `trace` does, but
+    /// without the `position` field. Rules defined with `parser!` emit the same span under the
+    /// name of their function.
+    ///
+    /// [`expected`]: #method.expected
+    /// [`trace`]: #method.trace
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::letter;
+    /// # fn main() {
+    /// let mut parser = many1::<String, _, _>(letter()).label("identifier");
+    /// let err = parser.easy_parse("1").unwrap_err();
+    /// assert!(err.to_string().contains("Expected `identifier`"), "{}", err);
+    /// # }
+    /// ```
+    fn label(self, name: &'static str) -> Label<Self>
+    where
+        Self: Sized,
+    {
+        label(self, name)
+    }
+
+    /// Parses with `self` and transforms the error with `f` if it fails.
+    ///
+    /// Unlike [`message`][] and [`expected`][] which can only add to the error, `f` receives the
+    /// whole error (including the expected errors of `self`) and may rewrite or remove any part
+    /// of it, or change its position.
+    ///
+    /// [`message`]: #method.message
+    /// [`expected`]: #method.expected
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let result = many1::<String, _, _>(digit())
+    ///     .map_err(|mut err: easy::Errors<char, &str, SourcePosition>| {
+    ///         err.errors.retain(|err| !matches!(err, easy::Error::Expected(_)));
+    ///         err.add_error(easy::Error::Message("invalid port".into()));
+    ///         err
+    ///     })
+    ///     .easy_parse(position::Stream::new("x"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition::default(),
+    ///     errors: vec![
+    ///         easy::Error::Message("invalid port".into()),
+    ///         easy::Error::Unexpected('x'.into()),
+    ///     ]
+    /// }));
+    /// # }
+    /// ```
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+    {
+        map_err(self, f)
+    }
+
+    /// Parses with `self`, if it fails without consuming any input any expected, unexpected or
+    /// message errors that would otherwise be emitted by `self` are suppressed, only the failure
+    /// itself is reported. If `self` fails after consuming input only the expected errors are
+    /// suppressed.
+    ///
+    /// Useful for parsers such as a whitespace skipper whose errors would only add noise to the
+    /// errors of the parsers around them.
     ///
     /// ```
     /// # #![cfg(feature = "std")]
@@ -754,6 +1183,74 @@ pub trait Parser<Input: Stream> {
         and_then(self, f)
     }
 
+    /// Parses with `self` and applies `f` on the result if `self` parses successfully. Unlike
+    /// [`and_then`][] `f` returns a complete `ParseError` and is given the position where `self`
+    /// started, so it can decide the position of the error and add any number of errors to it.
+    ///
+    /// [`and_then`]: #method.and_then
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::easy::{self, Error};
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut parser = (digit(), digit()).and_then_full(|(l, r), position: SourcePosition| {
+    ///     if l <= r {
+    ///         Ok((l, r))
+    ///     } else {
+    ///         let mut err = easy::Errors::new(position, Error::Unexpected(r.into()));
+    ///         err.add_error(Error::Expected("a digit not smaller than the first".into()));
+    ///         Err(err)
+    ///     }
+    /// });
+    /// let result = parser.easy_parse(position::Stream::new("12")).map(|x| x.0);
+    /// assert_eq!(result, Ok(('1', '2')));
+    /// let err = parser.easy_parse(position::Stream::new("21")).unwrap_err();
+    /// assert_eq!(err.position, SourcePosition { line: 1, column: 1 });
+    /// assert_eq!(err.errors.len(), 2);
+    /// # }
+    /// ```
+    fn and_then_full<F, O>(self, f: F) -> AndThenFull<Self, F>
+    where
+        Self: Parser<Input> + Sized,
+        F: FnMut(Self::Output, Input::Position) -> Result<O, Input::Error>,
+    {
+        and_then_full(self, f)
+    }
+
+    /// Parses with `self` and fails with `message` at the position where `self` started if
+    /// `predicate` returns `false` for the output.
+    ///
+    /// A shorthand for the common case of [`and_then`][] where the output is only validated.
+    ///
+    /// [`and_then`]: #method.and_then
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # use combine::parser::char::spaces;
+    /// # use combine::parser::range::number;
+    /// # fn main() {
+    /// let mut parser = spaces().with(number::<_, u32>().ensure(|&n| n <= 255, "byte out of range"));
+    /// let result = parser.easy_parse(position::Stream::new("127")).map(|x| x.0);
+    /// assert_eq!(result, Ok(127));
+    /// let err = parser.easy_parse(position::Stream::new(" 256")).unwrap_err();
+    /// assert_eq!(err.position, SourcePosition { line: 1, column: 2 });
+    /// assert!(err.to_string().contains("byte out of range"));
+    /// # }
+    /// ```
+    fn ensure<F>(self, predicate: F, message: &'static str) -> Ensure<Self, F>
+    where
+        Self: Parser<Input> + Sized,
+        F: FnMut(&Self::Output) -> bool,
+    {
+        ensure(self, predicate, message)
+    }
+
     /// Creates an iterator from a parser and a state. Can be used as an alternative to [`many`]
     /// when collecting directly into a `Extend` type is not desirable.
     ///
@@ -820,6 +1317,71 @@ pub trait Parser<Input: Stream> {
         Iter::new(self, mode, input, partial_state)
     }
 
+    /// Creates an iterator which applies `self` repeatedly to `input`, yielding each parsed value
+    /// until the end of the input is reached.
+    ///
+    /// If parsing fails the error is yielded and the iteration stops. The input which has not been
+    /// parsed can be retrieved with [`ParseIter::into_input`].
+    ///
+    /// If `self` succeeds without consuming any input the iteration stops after yielding that
+    /// value, as parsing the same location again would yield it forever.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, newline};
+    /// # fn main() {
+    /// let record = many1::<String, _, _>(digit()).skip(newline());
+    /// let mut iter = record.parse_iter("1\n23\n4x\n");
+    /// assert_eq!(iter.next(), Some(Ok("1".to_string())));
+    /// assert_eq!(iter.next(), Some(Ok("23".to_string())));
+    /// assert!(iter.next().unwrap().is_err());
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let mut iter = many::<String, _, _>(digit()).parse_iter("1x");
+    /// assert_eq!(iter.next(), Some(Ok("1".to_string())));
+    /// assert_eq!(iter.next(), Some(Ok("".to_string())));
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.into_input(), "x");
+    /// # }
+    /// ```
+    ///
+    /// [`ParseIter::into_input`]: repeat/struct.ParseIter.html#method.into_input
+    fn parse_iter(self, input: Input) -> ParseIter<Input, Self>
+    where
+        Self: Parser<Input> + Sized,
+    {
+        ParseIter::new(self, input)
+    }
+
+    /// Parses with `self` zero or more times, folding each value into an accumulator which starts
+    /// out as `init`.
+    ///
+    /// See [`fold_many`] for details.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{char, letter};
+    /// # fn main() {
+    /// let result = letter()
+    ///     .skip(char(','))
+    ///     .fold(0, |count, _| count + 1)
+    ///     .parse("a,b,c,!");
+    /// assert_eq!(result, Ok((3, "!")));
+    /// # }
+    /// ```
+    ///
+    /// [`fold_many`]: repeat/fn.fold_many.html
+    fn fold<A, F>(self, init: A, f: F) -> FoldMany<Self, A, F>
+    where
+        Self: Parser<Input> + Sized,
+        A: Clone,
+        F: FnMut(A, Self::Output) -> A,
+    {
+        fold_many(init, self, f)
+    }
+
     /// Turns the parser into a trait object by putting it in a `Box`. Can be used to easily
     /// return parsers from functions without naming the type.
     ///
@@ -849,6 +1411,38 @@ pub trait Parser<Input: Stream> {
         Box::new(self)
     }
 
+    /// Turns the parser into a trait object like [`boxed`][], but also erases the type of the
+    /// partial state with [`any_partial_state`][] so the type can be written without naming the
+    /// partial state of `self`.
+    ///
+    /// [`boxed`]: #method.boxed
+    /// [`any_partial_state`]: combinator/fn.any_partial_state.html
+    ///
+    /// ```
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter};
+    /// # use combine::parser::combinator::BoxedParser;
+    /// # fn main() {
+    /// fn number_or_word<'input>(number: bool) -> BoxedParser<'input, &'input str, String> {
+    ///     if number {
+    ///         many1(digit()).boxed_any()
+    ///     } else {
+    ///         many1(letter()).boxed_any()
+    ///     }
+    /// }
+    /// assert_eq!(number_or_word(true).parse("12a"), Ok(("12".to_string(), "a")));
+    /// assert_eq!(number_or_word(false).parse("ab1"), Ok(("ab".to_string(), "1")));
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    fn boxed_any<'a>(self) -> combinator::BoxedParser<'a, Input, Self::Output>
+    where
+        Self: Sized + 'a,
+        Self::PartialState: 'static,
+    {
+        Box::new(combinator::any_partial_state(self))
+    }
+
     /// Wraps the parser into the `Either` enum which allows combinators such as `then` to return
     /// multiple different parser types (merging them to one)
     ///
@@ -1038,6 +1632,10 @@ macro_rules! forward_deref {
         fn parser_count(&self) -> ErrorOffset {
             (**self).parser_count()
         }
+
+        fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+            (**self).reflect(visitor)
+        }
     }
 }
 
@@ -1058,6 +1656,59 @@ where
     forward_deref!(Input);
 }
 
+/// Allows a parser to be shared between several other parsers. The parser is borrowed mutably for
+/// each call so using it while it is already being used (through recursion) panics.
+#[cfg(feature = "std")]
+impl<P, Input> Parser<Input> for std::rc::Rc<std::cell::RefCell<P>>
+where
+    P: ?Sized + Parser<Input>,
+    Input: Stream,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    #[inline]
+    fn parse_first(
+        &mut self,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.borrow_mut().parse_first(input, state)
+    }
+
+    #[inline]
+    fn parse_partial(
+        &mut self,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.borrow_mut().parse_partial(input, state)
+    }
+
+    #[inline]
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.borrow_mut().add_error(error)
+    }
+
+    #[inline]
+    fn add_committed_expected_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.borrow_mut().add_committed_expected_error(error)
+    }
+
+    #[inline]
+    fn parser_count(&self) -> ErrorOffset {
+        self.borrow().parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        // A parser which is already borrowed is being reflected on through recursion
+        match self.try_borrow_mut() {
+            Ok(mut parser) => parser.reflect(visitor),
+            Err(_) => visitor.opaque(),
+        }
+    }
+}
+
 /// Internal API. May break without a semver bump
 #[doc(hidden)]
 /// Specifies whether the parser must check for partial state that must be resumed
@@ -0,0 +1,168 @@
+//! Parsers for left-recursive grammars, used together with [`stream::packrat::Stream`][].
+//!
+//! A rule such as `expr = expr '-' number | number` can not be written directly with combinators
+//! since parsing `expr` would immediately recurse into itself. [`left_rec`][] instead first parses
+//! the rule with the recursive call failing, then repeatedly re-parses it with the recursive call
+//! returning the previous result for as long as that consumes more input (seed growing).
+//!
+//! [`stream::packrat::Stream`]: ../../stream/packrat/struct.Stream.html
+//! [`left_rec`]: fn.left_rec.html
+
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{self, *},
+        ResultExt, Tracked,
+    },
+    stream::{packrat, Positioned, ResetStream, StreamOnce},
+    Parser, Stream,
+};
+
+use std::rc::Rc;
+
+pub struct LeftRec<P>(&'static str, P);
+
+impl<S, P> Parser<packrat::Stream<S>> for LeftRec<P>
+where
+    S: Stream,
+    P: Parser<packrat::Stream<S>>,
+    P::Output: Clone + 'static,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    fn parse_lazy(
+        &mut self,
+        input: &mut packrat::Stream<S>,
+    ) -> ParseResult<Self::Output, <packrat::Stream<S> as StreamOnce>::Error> {
+        let rule = self.0;
+        let start = input.checkpoint();
+        let start_position = input.position();
+
+        match input.memo::<P::Output>(rule, start_position.clone()) {
+            Some(Some(memo)) => {
+                let value = memo
+                    .value
+                    .downcast_ref::<P::Output>()
+                    .expect("memo table is keyed by the output type")
+                    .clone();
+                let committed = memo.committed;
+                let end = memo.end.clone();
+                ctry!(input.reset(end).committed());
+                return if committed {
+                    CommitOk(value)
+                } else {
+                    PeekOk(value)
+                };
+            }
+            Some(None) => return PeekErr(S::Error::empty(start_position).into()),
+            None => (),
+        }
+
+        // Seed the recursion with a failure so the left-recursive alternative fails
+        input.insert_memo::<P::Output>(rule, start_position.clone(), None);
+        let (mut value, mut committed) = match self.1.parse_lazy(input) {
+            PeekOk(value) => (value, false),
+            CommitOk(value) => (value, true),
+            PeekErr(err) => return PeekErr(err),
+            CommitErr(err) => return CommitErr(err),
+        };
+
+        let mut end = input.checkpoint();
+        let mut end_position = input.position();
+        loop {
+            input.insert_memo::<P::Output>(
+                rule,
+                start_position.clone(),
+                Some(packrat::Memo {
+                    value: Rc::new(value.clone()),
+                    end: end.clone(),
+                    committed,
+                }),
+            );
+
+            // Try to grow the seed, stopping once the rule no longer consumes more input
+            ctry!(input.reset(start.clone()).committed());
+            match self.1.parse_lazy(input) {
+                PeekOk(next) | CommitOk(next) if input.position() > end_position => {
+                    value = next;
+                    committed = true;
+                    end = input.checkpoint();
+                    end_position = input.position();
+                }
+                _ => break,
+            }
+        }
+
+        ctry!(input.reset(end).committed());
+        if committed {
+            CommitOk(value)
+        } else {
+            PeekOk(value)
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<packrat::Stream<S> as StreamOnce>::Error>) {
+        self.1.add_error(errors)
+    }
+}
+
+/// Parses a left-recursive `rule`. `rule` may refer to itself as its first element (through a
+/// function returning the `left_rec` parser) and the longest parse which can be built by
+/// repeatedly applying `rule` is returned.
+///
+/// Results are memoized in the [`packrat::Stream`][] by position and are identified by `name` (and
+/// the output type of `rule`), so each left-recursive rule must be given a distinct name. Only directly left-recursive rules are
+/// supported and attempts to grow the result always backtrack, even if they committed input before
+/// failing.
+///
+/// NOTE: The resulting parser does not support partial parsing.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::error::ParseError;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::packrat::left_rec;
+/// # use combine::stream::packrat;
+/// # fn main() {
+/// fn number<Input>() -> impl Parser<Input, Output = i32>
+/// where
+///     Input: Stream<Token = char>,
+///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+/// {
+///     digit().map(|c| c.to_digit(10).unwrap() as i32)
+/// }
+///
+/// // expr = expr '-' number | number
+/// fn subtraction<Input>() -> impl Parser<packrat::Stream<Input>, Output = i32>
+/// where
+///     Input: Stream<Token = char>,
+///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+/// {
+///     left_rec(
+///         "subtraction",
+///         (
+///             parser(|input| subtraction().parse_stream(input).into_result()),
+///             char('-'),
+///             number(),
+///         )
+///             .map(|(l, _, r)| l - r)
+///             .or(number()),
+///     )
+/// }
+///
+/// let result = subtraction().parse(packrat::Stream::new("9-2-3"));
+/// assert_eq!(result.map(|(value, rest)| (value, rest.stream)), Ok((4, "")));
+/// # }
+/// ```
+///
+/// [`packrat::Stream`]: ../../stream/packrat/struct.Stream.html
+pub fn left_rec<S, P>(name: &'static str, rule: P) -> LeftRec<P>
+where
+    S: Stream,
+    P: Parser<packrat::Stream<S>>,
+    P::Output: Clone + 'static,
+{
+    LeftRec(name, rule)
+}
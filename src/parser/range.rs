@@ -2,8 +2,30 @@
 //!
 //! These parsers require the [`RangeStream`][] bound instead of a plain [`Stream`][].
 //!
+//! The zero-copy parsers which search byte ranges for a delimiter using `memchr`
+//! ([`take_until_byte`][], [`take_until_byte2`][], [`take_until_byte3`][] and
+//! [`take_until_bytes`][]) are defined in the [`byte`][] module and re-exported here.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::range::{take_until_byte2, take_until_bytes};
+//! # fn main() {
+//! let mut line = take_until_byte2(b'\r', b'\n');
+//! assert_eq!(line.parse(&b"GET /\r\n"[..]), Ok((&b"GET /"[..], &b"\r\n"[..])));
+//!
+//! let mut header = take_until_bytes(b"\r\n\r\n");
+//! assert_eq!(header.parse("Host: a\r\n\r\n"), Ok(("Host: a", "\r\n\r\n")));
+//! # }
+//! ```
+//!
 //! [`RangeStream`]: ../../stream/trait.RangeStream.html
 //! [`Stream`]: ../../stream/trait.Stream.html
+//! [`byte`]: ../byte/index.html
+//! [`take_until_byte`]: fn.take_until_byte.html
+//! [`take_until_byte2`]: fn.take_until_byte2.html
+//! [`take_until_byte3`]: fn.take_until_byte3.html
+//! [`take_until_bytes`]: fn.take_until_bytes.html
 
 use crate::{
     error::{
@@ -11,17 +33,26 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
-    lib::marker::PhantomData,
-    parser::ParseMode,
+    lib::{convert::TryFrom, marker::PhantomData, str},
+    parser::{
+        byte_set::{ByteSet, ScanBytes},
+        reflect::Visitor,
+        ParseMode,
+    },
 };
 
 use crate::stream::{
-    uncons_range, uncons_while, uncons_while1, wrap_stream_error, Range as StreamRange,
-    RangeStream, StreamOnce,
+    input_at_eof, uncons_range, uncons_while, uncons_while1, wrap_stream_error,
+    Range as StreamRange, RangeStream, StreamOnce,
 };
 
 use crate::Parser;
 
+pub use crate::parser::byte::{
+    take_until_byte, take_until_byte2, take_until_byte3, take_until_bytes, TakeUntilByte,
+    TakeUntilByte2, TakeUntilByte3,
+};
+
 pub struct Range<Input>(Input::Range)
 where
     Input: RangeStream;
@@ -57,6 +88,10 @@ where
         // TODO Add unexpected message?
         errors.error.add_expected(error::Range(self.0.clone()));
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visitor.range(&self.0)
+    }
 }
 
 parser! {
@@ -297,6 +332,118 @@ where
     Take(n, PhantomData)
 }
 
+pub struct LengthPrefix<P>(P);
+impl<Input, P> Parser<Input> for LengthPrefix<P>
+where
+    Input: RangeStream,
+    P: Parser<Input>,
+    usize: TryFrom<P::Output>,
+{
+    type Output = Input::Range;
+    type PartialState = (Option<usize>, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut len, ref mut len_state) = *state;
+        if mode.is_first() {
+            *len = None;
+        }
+
+        let (n, committed) = match *len {
+            // The length was parsed by an earlier, partial, call
+            Some(n) => (n, true),
+            None => {
+                let (n, committed) = match self.0.parse_mode(mode, input, len_state) {
+                    PeekOk(n) => (n, false),
+                    CommitOk(n) => (n, true),
+                    PeekErr(err) => return PeekErr(err),
+                    CommitErr(err) => return CommitErr(err),
+                };
+                let n = match usize::try_from(n) {
+                    Ok(n) => n,
+                    Err(_) => {
+                        let err = <Input as StreamOnce>::Error::from_error(
+                            input.position(),
+                            StreamError::message_static_message("length does not fit in usize"),
+                        );
+                        return if committed {
+                            CommitErr(err)
+                        } else {
+                            PeekErr(err.into())
+                        };
+                    }
+                };
+                *len = Some(n);
+                (n, committed)
+            }
+        };
+
+        match uncons_range(input, n) {
+            PeekOk(range) => {
+                *len = None;
+                if committed {
+                    CommitOk(range)
+                } else {
+                    PeekOk(range)
+                }
+            }
+            CommitOk(range) => {
+                *len = None;
+                CommitOk(range)
+            }
+            PeekErr(err) => {
+                if committed {
+                    CommitErr(err.into_error())
+                } else {
+                    PeekErr(err)
+                }
+            }
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+}
+
+/// Zero-copy parser which parses a length using `len` and then reads a range of exactly that
+/// length.
+///
+/// When parsing partial input the parser waits until the whole range is available instead of
+/// parsing the length again.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::byte::num::be_u16;
+/// # use combine::parser::range::length_prefix;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = length_prefix(be_u16());
+/// let result = parser.parse(&b"\x00\x03abcd"[..]);
+/// assert_eq!(result, Ok((&b"abc"[..], &b"d"[..])));
+/// let result = parser.parse(&b"\x00\x05abcd"[..]);
+/// assert!(result.is_err());
+/// # }
+/// ```
+pub fn length_prefix<Input, P>(len: P) -> LengthPrefix<P>
+where
+    Input: RangeStream,
+    P: Parser<Input>,
+    usize: TryFrom<P::Output>,
+{
+    LengthPrefix(len)
+}
+
 pub struct TakeWhile<Input, F>(F, PhantomData<fn(Input) -> Input>);
 impl<Input, F> Parser<Input> for TakeWhile<Input, F>
 where
@@ -413,231 +560,1399 @@ where
     TakeWhile1(f, PhantomData)
 }
 
-pub struct TakeUntilRange<Input>(Input::Range)
+#[inline]
+fn uncons_set<Input>(
+    input: &mut Input,
+    set: &ByteSet,
+    at_least_one: bool,
+) -> ParseResult<Input::Range, Input::Error>
 where
-    Input: RangeStream;
-impl<Input> Parser<Input> for TakeUntilRange<Input>
+    Input: RangeStream,
+    Input::Range: ScanBytes + crate::stream::Range,
+{
+    let len = input.range().scan_bytes(set);
+    let range = match input.uncons_range(len) {
+        Ok(range) => range,
+        Err(err) => return wrap_stream_error(input, err),
+    };
+    if input.is_partial() && input_at_eof(input) {
+        // Partial inputs which encounter end of file must fail to let more input be retrieved
+        CommitErr(Input::Error::from_error(
+            input.position(),
+            StreamError::end_of_input(),
+        ))
+    } else if len != 0 {
+        CommitOk(range)
+    } else if at_least_one {
+        PeekErr(Input::Error::empty(input.position()).into())
+    } else {
+        PeekOk(range)
+    }
+}
+
+pub struct TakeWhileSet<Input>(ByteSet, PhantomData<fn(Input) -> Input>);
+impl<Input> Parser<Input> for TakeWhileSet<Input>
 where
     Input: RangeStream,
-    Input::Range: PartialEq + crate::stream::Range,
+    Input::Range: ScanBytes + crate::stream::Range,
 {
     type Output = Input::Range;
     type PartialState = usize;
 
+    parse_mode!(Input);
     #[inline]
-    fn parse_partial(
+    fn parse_mode_impl<M>(
         &mut self,
+        mode: M,
         input: &mut Input,
-        to_consume: &mut Self::PartialState,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        use crate::stream::Range;
-
-        let len = self.0.len();
-        let before = input.checkpoint();
-        let mut first_stream_error = None;
-
-        // Skip until the end of the last parse attempt
-        ctry!(uncons_range(input, *to_consume));
-
-        loop {
-            let look_ahead_input = input.checkpoint();
-
-            match input.uncons_range(len) {
-                Ok(xs) => {
-                    if xs == self.0 {
-                        let distance = input.distance(&before) - len;
-                        ctry!(input.reset(before).committed());
-
-                        if let Ok(committed) = input.uncons_range(distance) {
-                            if distance == 0 {
-                                return PeekOk(committed);
-                            } else {
-                                *to_consume = 0;
-                                return CommitOk(committed);
-                            }
-                        }
-
-                        // We are guaranteed able to uncons to_consume characters here
-                        // because we've already done it on look_ahead_input.
-                        unreachable!();
-                    } else {
-                        // Reset the stream back to where it was when we entered the top of the loop
-                        ctry!(input.reset(look_ahead_input).committed());
-
-                        // Advance the stream by one token
-                        if input.uncons().is_err() {
-                            unreachable!();
-                        }
-                    }
-                }
-                Err(first_error) => {
-                    // If we are unable to find a successful parse even after advancing with `uncons`
-                    // below we must reset the stream to its state before the first error.
-                    // If we don't we may try and match the range `::` against `:<EOF>` which would
-                    // fail as only one `:` is present at this parse attempt. But when we later resume
-                    // with more input we must start parsing again at the first time we errored so we
-                    // can see the entire `::`
-                    if first_stream_error.is_none() {
-                        first_stream_error = Some((first_error, input.distance(&before)));
-                    }
-
-                    // Reset the stream back to where it was when we entered the top of the loop
-                    ctry!(input.reset(look_ahead_input).committed());
-
-                    // See if we can advance anyway
-                    if input.uncons().is_err() {
-                        let (first_error, first_error_distance) = first_stream_error.unwrap();
-
-                        // Reset the stream
-                        ctry!(input.reset(before).committed());
-                        *to_consume = first_error_distance;
-
-                        // Return the original error if uncons failed
-                        return wrap_stream_error(input, first_error);
-                    }
-                }
-            };
-        }
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        parse_partial_range(
+            mode,
+            input,
+            state,
+            &self.0,
+            |input, set| uncons_set(input, set, false),
+            |input, set| uncons_set(input, set, false),
+        )
     }
 }
 
-/// Zero-copy parser which reads a range of 0 or more tokens until `r` is found.
-///
-/// The range `r` will not be committed. If `r` is not found, the parser will
-/// return an error.
+/// Zero-copy parser which reads a range of 0 or more bytes which are in `set`.
 ///
-/// [`repeat::take_until`][] is a non-`RangeStream` alternative.
+/// Behaves like [`take_while`][] but scans the range with [`ByteSet::scan`][], which is
+/// considerably faster for long ranges. When parsing `&str` only the ASCII bytes of `set` are
+/// matched.
 ///
-/// [`repeat::take_until`]: ../../parser/repeat/fn.take_until.html
+/// [`take_while`]: fn.take_while.html
+/// [`ByteSet::scan`]: ../byte_set/struct.ByteSet.html#method.scan
 /// ```
 /// # extern crate combine;
-/// # use combine::parser::range::{range, take_until_range};
+/// # use combine::parser::byte_set::ByteSet;
+/// # use combine::parser::range::take_while_set;
 /// # use combine::*;
 /// # fn main() {
-/// let mut parser = take_until_range("\r\n");
-/// let result = parser.parse("To: user@example.com\r\n");
-/// assert_eq!(result, Ok(("To: user@example.com", "\r\n")));
-/// let result = parser.parse("Hello, world\n");
-/// assert!(result.is_err());
+/// let mut parser = take_while_set(ByteSet::ASCII_DIGIT);
+/// let result = parser.parse(&b"123abc"[..]);
+/// assert_eq!(result, Ok((&b"123"[..], &b"abc"[..])));
+/// let result = parser.parse(&b"abc"[..]);
+/// assert_eq!(result, Ok((&b""[..], &b"abc"[..])));
 /// # }
 /// ```
-pub fn take_until_range<Input>(r: Input::Range) -> TakeUntilRange<Input>
+pub fn take_while_set<Input>(set: ByteSet) -> TakeWhileSet<Input>
 where
     Input: RangeStream,
+    Input::Range: ScanBytes + crate::stream::Range,
 {
-    TakeUntilRange(r)
+    TakeWhileSet(set, PhantomData)
 }
 
-#[derive(Debug, PartialEq)]
-pub enum TakeRange {
-    /// Found the pattern at this offset
-    Found(usize),
-    /// Did not find the pattern but the parser can skip ahead to this offset.
-    NotFound(usize),
-}
+pub struct TakeWhile1Set<Input>(ByteSet, PhantomData<fn(Input) -> Input>);
+impl<Input> Parser<Input> for TakeWhile1Set<Input>
+where
+    Input: RangeStream,
+    Input::Range: ScanBytes + crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = usize;
 
-impl From<Option<usize>> for TakeRange {
-    fn from(opt: Option<usize>) -> TakeRange {
-        match opt {
-            Some(i) => TakeRange::Found(i),
-            None => TakeRange::NotFound(0),
-        }
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        parse_partial_range(
+            mode,
+            input,
+            state,
+            &self.0,
+            |input, set| uncons_set(input, set, true),
+            |input, set| uncons_set(input, set, false),
+        )
     }
 }
 
-pub struct TakeFn<F, Input> {
-    searcher: F,
-    _marker: PhantomData<fn(Input)>,
+/// Zero-copy parser which reads a range of 1 or more bytes which are in `set`.
+///
+/// Behaves like [`take_while1`][] but scans the range with [`ByteSet::scan`][].
+///
+/// [`take_while1`]: fn.take_while1.html
+/// [`ByteSet::scan`]: ../byte_set/struct.ByteSet.html#method.scan
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::byte_set::ByteSet;
+/// # use combine::parser::range::take_while1_set;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = take_while1_set(ByteSet::ASCII_HEXDIGIT);
+/// let result = parser.parse("ff00 ");
+/// assert_eq!(result, Ok(("ff00", " ")));
+/// let result = parser.parse("xyz");
+/// assert!(result.is_err());
+/// # }
+/// ```
+pub fn take_while1_set<Input>(set: ByteSet) -> TakeWhile1Set<Input>
+where
+    Input: RangeStream,
+    Input::Range: ScanBytes + crate::stream::Range,
+{
+    TakeWhile1Set(set, PhantomData)
 }
 
-impl<Input, F, R> Parser<Input> for TakeFn<F, Input>
+pub struct TakeWhileMN<Input, F> {
+    min: usize,
+    max: usize,
+    predicate: F,
+    _marker: PhantomData<fn(Input) -> Input>,
+}
+impl<Input, F> Parser<Input> for TakeWhileMN<Input, F>
 where
-    F: FnMut(Input::Range) -> R,
-    R: Into<TakeRange>,
     Input: RangeStream,
     Input::Range: crate::stream::Range,
+    F: FnMut(Input::Token) -> bool,
 {
     type Output = Input::Range;
-    type PartialState = usize;
+    type PartialState = (usize, usize);
 
     parse_mode!(Input);
     #[inline]
-    fn parse_mode<M>(
+    fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
-        offset: &mut Self::PartialState,
+        state: &mut Self::PartialState,
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
     where
         M: ParseMode,
     {
-        let checkpoint = input.checkpoint();
-
-        if mode.is_first() {
-            *offset = 0;
-        } else {
-            let _ = input.uncons_range(*offset);
+        let (ref mut distance, ref mut count) = *state;
+        // The number of taken tokens is kept across resumptions since `distance` counts the length
+        // of the range, not the number of tokens
+        if mode.is_first() || *distance == 0 {
+            *count = 0;
         }
 
-        match (self.searcher)(input.range()).into() {
-            TakeRange::Found(i) => {
-                ctry!(input.reset(checkpoint).committed());
-                let result = uncons_range(input, *offset + i);
-                if result.is_ok() {
-                    *offset = 0;
-                }
-                result
+        let max = self.max;
+        let predicate = &mut self.predicate;
+        let mut take = |token| {
+            if *count < max && predicate(token) {
+                *count += 1;
+                true
+            } else {
+                false
             }
-            TakeRange::NotFound(next_offset) => {
-                *offset = next_offset;
-
-                let range = input.range();
-                let _ = input.uncons_range(range.len());
-                let position = input.position();
-                ctry!(input.reset(checkpoint).committed());
+        };
+        let result = parse_partial_range(
+            mode,
+            input,
+            distance,
+            &mut take,
+            |input, predicate| uncons_while(input, predicate),
+            |input, predicate| uncons_while(input, predicate),
+        );
+        let (range, committed) = ctry!(result);
 
-                let err = Input::Error::from_error(position, StreamError::end_of_input());
-                if !input.is_partial() && range.is_empty() {
-                    PeekErr(err.into())
-                } else {
-                    CommitErr(err)
-                }
-            }
+        let taken = crate::lib::mem::replace(count, 0);
+        if taken < self.min {
+            let err = Input::Error::from_error(
+                input.position(),
+                StreamError::message_format(format_args!(
+                    "expected {} more elements",
+                    self.min - taken
+                )),
+            );
+            return if taken == 0 {
+                PeekErr(err.into())
+            } else {
+                CommitErr(err)
+            };
         }
+        committed.combine_commit(|_| PeekOk(range))
     }
 }
 
-/// Searches the entire range using `searcher` and then consumes a range of `Some(n)`.
-/// If `f` can not find anything in the range it must return `None/NotFound` which indicates an end of input error.
+/// Zero-copy parser which reads a range of at least `min` and at most `max` tokens which satisfy
+/// `predicate`.
 ///
-/// If partial parsing is used the `TakeRange` enum can be returned instead of `Option`. By
-/// returning `TakeRange::NotFound(n)` it indicates that the input can skip ahead until `n`
-/// when parsing is next resumed.
+/// [`count_min_max`][] is a non-`RangeStream` alternative.
 ///
-/// See [`take_until_bytes`](../byte/fn.take_until_bytes.html) for a usecase.
-pub fn take_fn<F, R, Input>(searcher: F) -> TakeFn<F, Input>
+/// [`count_min_max`]: ../../parser/repeat/fn.count_min_max.html
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::take_while_m_n;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = take_while_m_n(2, 4, |c: char| c.is_digit(16));
+/// assert_eq!(parser.parse("1f"), Ok(("1f", "")));
+/// assert_eq!(parser.parse("00e9!"), Ok(("00e9", "!")));
+/// assert_eq!(parser.parse("1f600"), Ok(("1f60", "0")));
+/// assert!(parser.parse("1!").is_err());
+/// // `min` and `max` count tokens, not the length of the range
+/// assert_eq!(take_while_m_n(1, 2, |c: char| c != '!').parse("ééé"), Ok(("éé", "é")));
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// If `min` > `max`.
+pub fn take_while_m_n<Input, F>(min: usize, max: usize, predicate: F) -> TakeWhileMN<Input, F>
 where
-    F: FnMut(Input::Range) -> R,
-    R: Into<TakeRange>,
     Input: RangeStream,
     Input::Range: crate::stream::Range,
+    F: FnMut(Input::Token) -> bool,
 {
-    TakeFn {
-        searcher,
+    assert!(
+        min <= max,
+        "take_while_m_n: `min` must not be larger than `max`"
+    );
+    TakeWhileMN {
+        min,
+        max,
+        predicate,
         _marker: PhantomData,
     }
 }
 
-#[cfg(test)]
-mod tests {
-
-    use crate::Parser;
-
-    use super::*;
+pub struct Number<Input, T>(PhantomData<fn(Input) -> T>);
+impl<Input, T> Parser<Input> for Number<Input, T>
+where
+    Input: RangeStream,
+    Input::Token: Into<char>,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    T: crate::parser::char::Integral,
+{
+    type Output = T;
+    type PartialState = usize;
 
-    #[test]
-    fn take_while_test() {
-        let result = take_while(|c: char| c.is_digit(10)).parse("123abc");
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let before = input.position();
+        let result = parse_partial_range(
+            mode,
+            input,
+            state,
+            &mut |c: Input::Token| c.into().is_ascii_digit(),
+            |input, predicate| uncons_while1(input, predicate),
+            |input, predicate| uncons_while(input, predicate),
+        );
+        let (digits, committed) = ctry!(result);
+        let value = digits.as_ref().iter().try_fold(T::default(), |value, &b| {
+            value.push_digit(10, u32::from(b - b'0'), false)
+        });
+        committed.combine_commit(|_| match value {
+            Some(value) => PeekOk(value),
+            None => PeekErr(
+                Input::Error::from_error(
+                    before,
+                    StreamError::message_static_message("number too large"),
+                )
+                .into(),
+            ),
+        })
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected("digit");
+    }
+}
+
+/// Zero-copy parser which reads a run of 1 or more ascii digits and folds it directly into an
+/// integer of type `T`, failing with "number too large" if the value does not fit in `T`.
+///
+/// Unlike `many1(digit()).map(|s: String| s.parse())` no intermediate `String` is allocated. Signs,
+/// other radixes and digit separators are supported by [`char::integer`][] instead.
+///
+/// [`char::integer`]: ../char/fn.integer.html
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::number;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = number::<_, u8>();
+/// assert_eq!(parser.parse("123abc"), Ok((123, "abc")));
+/// assert!(parser.parse("256").is_err());
+/// assert!(parser.parse("abc").is_err());
+/// assert_eq!(number::<_, u64>().parse(&b"42"[..]), Ok((42, &b""[..])));
+/// # }
+/// ```
+pub fn number<Input, T>() -> Number<Input, T>
+where
+    Input: RangeStream,
+    Input::Token: Into<char>,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    T: crate::parser::char::Integral,
+{
+    Number(PhantomData)
+}
+
+// Shared implementation of `take_until_range` and `take_until_range_caseless` which uses `eq` to
+// compare each candidate range against `needle`
+fn parse_take_until_range<Input, F>(
+    needle: &Input::Range,
+    input: &mut Input,
+    to_consume: &mut usize,
+    mut eq: F,
+) -> ParseResult<Input::Range, <Input as StreamOnce>::Error>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+    F: FnMut(&Input::Range, &Input::Range) -> bool,
+{
+    use crate::stream::Range;
+
+    let len = needle.len();
+    let before = input.checkpoint();
+    let mut first_stream_error = None;
+
+    // Skip until the end of the last parse attempt
+    ctry!(uncons_range(input, *to_consume));
+
+    loop {
+        let look_ahead_input = input.checkpoint();
+
+        match input.uncons_range(len) {
+            Ok(xs) => {
+                if eq(&xs, needle) {
+                    let distance = input.distance(&before) - len;
+                    ctry!(input.reset(before).committed());
+
+                    if let Ok(committed) = input.uncons_range(distance) {
+                        if distance == 0 {
+                            return PeekOk(committed);
+                        } else {
+                            *to_consume = 0;
+                            return CommitOk(committed);
+                        }
+                    }
+
+                    // We are guaranteed able to uncons to_consume characters here
+                    // because we've already done it on look_ahead_input.
+                    unreachable!();
+                } else {
+                    // Reset the stream back to where it was when we entered the top of the loop
+                    ctry!(input.reset(look_ahead_input).committed());
+
+                    // Advance the stream by one token
+                    if input.uncons().is_err() {
+                        unreachable!();
+                    }
+                }
+            }
+            Err(first_error) => {
+                // If we are unable to find a successful parse even after advancing with `uncons`
+                // below we must reset the stream to its state before the first error.
+                // If we don't we may try and match the range `::` against `:<EOF>` which would
+                // fail as only one `:` is present at this parse attempt. But when we later resume
+                // with more input we must start parsing again at the first time we errored so we
+                // can see the entire `::`
+                if first_stream_error.is_none() {
+                    first_stream_error = Some((first_error, input.distance(&before)));
+                }
+
+                // Reset the stream back to where it was when we entered the top of the loop
+                ctry!(input.reset(look_ahead_input).committed());
+
+                // See if we can advance anyway
+                if input.uncons().is_err() {
+                    let (first_error, first_error_distance) = first_stream_error.unwrap();
+
+                    // Reset the stream
+                    ctry!(input.reset(before).committed());
+                    *to_consume = first_error_distance;
+
+                    // Return the original error if uncons failed
+                    return wrap_stream_error(input, first_error);
+                }
+            }
+        };
+    }
+}
+
+pub struct TakeUntilRange<Input>(Input::Range)
+where
+    Input: RangeStream;
+
+impl<Input> Parser<Input> for TakeUntilRange<Input>
+where
+    Input: RangeStream,
+    Input::Range: PartialEq + crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = usize;
+
+    #[inline]
+    fn parse_partial(
+        &mut self,
+        input: &mut Input,
+        to_consume: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        parse_take_until_range(&self.0, input, to_consume, |l, r| l == r)
+    }
+}
+
+/// Zero-copy parser which reads a range of 0 or more tokens until `r` is found.
+///
+/// The range `r` will not be committed. If `r` is not found, the parser will
+/// return an error.
+///
+/// [`repeat::take_until`][] is a non-`RangeStream` alternative.
+///
+/// [`repeat::take_until`]: ../../parser/repeat/fn.take_until.html
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{range, take_until_range};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = take_until_range("\r\n");
+/// let result = parser.parse("To: user@example.com\r\n");
+/// assert_eq!(result, Ok(("To: user@example.com", "\r\n")));
+/// let result = parser.parse("Hello, world\n");
+/// assert!(result.is_err());
+/// # }
+/// ```
+pub fn take_until_range<Input>(r: Input::Range) -> TakeUntilRange<Input>
+where
+    Input: RangeStream,
+{
+    TakeUntilRange(r)
+}
+
+pub struct TakeUntilRangeCaseless<Input>(Input::Range)
+where
+    Input: RangeStream;
+impl<Input> Parser<Input> for TakeUntilRangeCaseless<Input>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = usize;
+
+    #[inline]
+    fn parse_partial(
+        &mut self,
+        input: &mut Input,
+        to_consume: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        parse_take_until_range(&self.0, input, to_consume, |l, r| {
+            l.as_ref().eq_ignore_ascii_case(r.as_ref())
+        })
+    }
+}
+
+/// Zero-copy parser which reads a range of 0 or more tokens until `r` is found, ignoring ASCII
+/// case differences. Otherwise behaves like [`take_until_range`][].
+///
+/// [`take_until_range`]: fn.take_until_range.html
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::take_until_range_caseless;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = take_until_range_caseless("</script>");
+/// let result = parser.parse("alert(1)</SCRIPT>");
+/// assert_eq!(result, Ok(("alert(1)", "</SCRIPT>")));
+/// let result = parser.parse("alert(1)");
+/// assert!(result.is_err());
+/// # }
+/// ```
+pub fn take_until_range_caseless<Input>(r: Input::Range) -> TakeUntilRangeCaseless<Input>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]>,
+{
+    TakeUntilRangeCaseless(r)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TakeRange {
+    /// Found the pattern at this offset
+    Found(usize),
+    /// Did not find the pattern but the parser can skip ahead to this offset.
+    NotFound(usize),
+}
+
+impl From<Option<usize>> for TakeRange {
+    fn from(opt: Option<usize>) -> TakeRange {
+        match opt {
+            Some(i) => TakeRange::Found(i),
+            None => TakeRange::NotFound(0),
+        }
+    }
+}
+
+pub struct TakeFn<F, Input> {
+    searcher: F,
+    _marker: PhantomData<fn(Input)>,
+}
+
+impl<Input, F, R> Parser<Input> for TakeFn<F, Input>
+where
+    F: FnMut(Input::Range) -> R,
+    R: Into<TakeRange>,
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = usize;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        offset: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let checkpoint = input.checkpoint();
+
+        if mode.is_first() {
+            *offset = 0;
+        } else {
+            let _ = input.uncons_range(*offset);
+        }
+
+        match (self.searcher)(input.range()).into() {
+            TakeRange::Found(i) => {
+                ctry!(input.reset(checkpoint).committed());
+                let result = uncons_range(input, *offset + i);
+                if result.is_ok() {
+                    *offset = 0;
+                }
+                result
+            }
+            TakeRange::NotFound(next_offset) => {
+                *offset = next_offset;
+
+                let range = input.range();
+                let _ = input.uncons_range(range.len());
+                let position = input.position();
+                ctry!(input.reset(checkpoint).committed());
+
+                let err = Input::Error::from_error(position, StreamError::end_of_input());
+                if !input.is_partial() && range.is_empty() {
+                    PeekErr(err.into())
+                } else {
+                    CommitErr(err)
+                }
+            }
+        }
+    }
+}
+
+/// Searches the entire range using `searcher` and then consumes a range of `Some(n)`.
+/// If `f` can not find anything in the range it must return `None/NotFound` which indicates an end of input error.
+///
+/// If partial parsing is used the `TakeRange` enum can be returned instead of `Option`. By
+/// returning `TakeRange::NotFound(n)` it indicates that the input can skip ahead until `n`
+/// when parsing is next resumed.
+///
+/// See [`take_until_bytes`](../byte/fn.take_until_bytes.html) for a usecase.
+pub fn take_fn<F, R, Input>(searcher: F) -> TakeFn<F, Input>
+where
+    F: FnMut(Input::Range) -> R,
+    R: Into<TakeRange>,
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    TakeFn {
+        searcher,
+        _marker: PhantomData,
+    }
+}
+
+/// Ranges which can be searched for a single item.
+///
+/// `&[u8]` is searched with `memchr` and `&str` with `str::find`.
+pub trait FindItem<T> {
+    /// Returns the offset of the first occurrence of `item` in `self`.
+    fn find_item(&self, item: T) -> Option<usize>;
+}
+
+impl FindItem<u8> for &[u8] {
+    fn find_item(&self, item: u8) -> Option<usize> {
+        ::memchr::memchr(item, self)
+    }
+}
+
+impl FindItem<char> for &str {
+    fn find_item(&self, item: char) -> Option<usize> {
+        self.find(item)
+    }
+}
+
+parser! {
+    pub struct TakeUntilItem;
+    type PartialState = usize;
+    /// Zero-copy parser which reads a range of 0 or more tokens until `item` is found.
+    ///
+    /// If `item` is not found, the parser will return an error.
+    ///
+    /// [`byte::take_until_byte`][] can be used instead for any range which is `AsRef<[u8]>`.
+    ///
+    /// [`byte::take_until_byte`]: ../byte/fn.take_until_byte.html
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::parser::range::take_until_item;
+    /// # use combine::*;
+    /// # fn main() {
+    /// let mut parser = take_until_item(':').skip(token(':'));
+    /// assert_eq!(parser.parse("key:value"), Ok(("key", "value")));
+    /// assert!(parser.parse("key").is_err());
+    ///
+    /// let mut parser = take_until_item(b'\n');
+    /// assert_eq!(parser.parse(&b"line\nrest"[..]), Ok((&b"line"[..], &b"\nrest"[..])));
+    /// # }
+    /// ```
+    pub fn take_until_item[Input](item: Input::Token)(Input) -> Input::Range
+    where [
+        Input: RangeStream,
+        Input::Range: FindItem<Input::Token> + crate::stream::Range,
+    ]
+    {
+        let item = item.clone();
+        take_fn(move |haystack: Input::Range| match haystack.find_item(item.clone()) {
+            Some(i) => TakeRange::Found(i),
+            None => TakeRange::NotFound(haystack.len()),
+        })
+    }
+}
+
+/// Ranges which can be searched for a delimiter which is not preceded by an escape item.
+///
+/// `&[u8]` is searched with `memchr2` and `&str` character by character.
+pub trait FindUnescaped<T> {
+    /// Returns `TakeRange::Found` with the offset of the first `delimiter` in `self` which is not
+    /// escaped by `escape`, or `TakeRange::NotFound` with the offset that a later search (with
+    /// more input) should resume from.
+    fn find_unescaped(&self, delimiter: T, escape: T) -> TakeRange;
+}
+
+impl FindUnescaped<u8> for &[u8] {
+    fn find_unescaped(&self, delimiter: u8, escape: u8) -> TakeRange {
+        let mut offset = 0;
+        while let Some(i) = ::memchr::memchr2(delimiter, escape, &self[offset..]) {
+            let i = offset + i;
+            if self[i] == escape {
+                if i + 1 == self.len() {
+                    // The escaped byte has not been received yet so resume from the escape
+                    return TakeRange::NotFound(i);
+                }
+                offset = i + 2;
+            } else {
+                return TakeRange::Found(i);
+            }
+        }
+        TakeRange::NotFound(self.len())
+    }
+}
+
+impl FindUnescaped<char> for &str {
+    fn find_unescaped(&self, delimiter: char, escape: char) -> TakeRange {
+        let mut iter = self.char_indices();
+        while let Some((i, c)) = iter.next() {
+            if c == escape {
+                if iter.next().is_none() {
+                    // The escaped char has not been received yet so resume from the escape
+                    return TakeRange::NotFound(i);
+                }
+            } else if c == delimiter {
+                return TakeRange::Found(i);
+            }
+        }
+        TakeRange::NotFound(self.len())
+    }
+}
+
+parser! {
+    pub struct TakeUntilUnescaped;
+    type PartialState = usize;
+    /// Zero-copy parser which reads a range of 0 or more tokens until a `delimiter` is found which
+    /// is not escaped by a preceding `escape`.
+    ///
+    /// The token following an `escape` is always skipped, so an escaped escape (`\\`) does not
+    /// escape the token after it. The escapes are not removed from the returned range. `delimiter`
+    /// and `escape` must be different tokens.
+    ///
+    /// If no unescaped `delimiter` is found, the parser will return an error.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::parser::range::take_until_unescaped;
+    /// # use combine::*;
+    /// # fn main() {
+    /// let mut parser = token('"').with(take_until_unescaped('"', '\\')).skip(token('"'));
+    /// assert_eq!(parser.parse(r#""a \"b\" c" rest"#), Ok((r#"a \"b\" c"#, " rest")));
+    /// assert_eq!(parser.parse(r#""a\\" rest"#), Ok((r#"a\\"#, " rest")));
+    /// assert!(parser.parse(r#""a\""#).is_err());
+    ///
+    /// let mut parser = take_until_unescaped(b'/', b'\\');
+    /// assert_eq!(parser.parse(&b"a\\/b/g"[..]), Ok((&b"a\\/b"[..], &b"/g"[..])));
+    /// # }
+    /// ```
+    pub fn take_until_unescaped[Input](delimiter: Input::Token, escape: Input::Token)(Input) -> Input::Range
+    where [
+        Input: RangeStream,
+        Input::Range: FindUnescaped<Input::Token> + crate::stream::Range,
+    ]
+    {
+        let delimiter = delimiter.clone();
+        let escape = escape.clone();
+        take_fn(move |haystack: Input::Range| {
+            haystack.find_unescaped(delimiter.clone(), escape.clone())
+        })
+    }
+}
+
+pub struct Line<Input>(PhantomData<fn(Input)>);
+
+impl<Input> Parser<Input> for Line<Input>
+where
+    Input: RangeStream<Token = char>,
+    Input::Range: crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = usize;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        offset: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let checkpoint = input.checkpoint();
+
+        if mode.is_first() {
+            *offset = 0;
+        } else {
+            let _ = input.uncons_range(*offset);
+        }
+
+        let content = match input.uncons_while(|c| c != '\n' && c != '\r') {
+            Ok(content) => *offset + content.len(),
+            Err(err) => {
+                ctry!(input.reset(checkpoint).committed());
+                return wrap_stream_error(input, err);
+            }
+        };
+        let end_of_input = |input: &mut Input, offset: &mut usize| {
+            *offset = content;
+            let position = input.position();
+            ctry!(input.reset(checkpoint.clone()).committed());
+            CommitErr(Input::Error::from_error(
+                position,
+                StreamError::end_of_input(),
+            ))
+        };
+        let line_ending = match input.uncons() {
+            Ok('\n') => 1,
+            Ok(_) => match input.uncons() {
+                Ok('\n') => 2,
+                // A `\n` may still follow once more input is available
+                Err(ref err) if input.is_partial() && err.is_unexpected_end_of_input() => {
+                    return end_of_input(input, offset)
+                }
+                _ => 1,
+            },
+            Err(ref err) if err.is_unexpected_end_of_input() => {
+                if input.is_partial() {
+                    return end_of_input(input, offset);
+                } else if content == 0 {
+                    let position = input.position();
+                    ctry!(input.reset(checkpoint).committed());
+                    return PeekErr(
+                        Input::Error::from_error(position, StreamError::end_of_input()).into(),
+                    );
+                }
+                0
+            }
+            Err(err) => {
+                ctry!(input.reset(checkpoint).committed());
+                return wrap_stream_error(input, err);
+            }
+        };
+
+        ctry!(input.reset(checkpoint).committed());
+        let line = ctry!(uncons_range(input, content)).0;
+        ctry!(uncons_range(input, line_ending));
+        *offset = 0;
+        CommitOk(line)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected("line");
+    }
+}
+
+/// Zero-copy parser which reads the rest of the current line, returning it without the line
+/// ending. The line ending, `"\n"`, `"\r\n"` or a lone `"\r"` as in [`char::eol`], is consumed
+/// as well but may be left out on the last line.
+///
+/// Fails without consuming input if the input is empty.
+///
+/// [`char::eol`]: ../char/fn.eol.html
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::line;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = many::<Vec<_>, _, _>(line());
+/// assert_eq!(parser.parse("a,b\r\nc\n\nd\re"), Ok((vec!["a,b", "c", "", "d", "e"], "")));
+/// assert_eq!(parser.parse("a\n"), Ok((vec!["a"], "")));
+/// assert!(line().parse("").is_err());
+/// # }
+/// ```
+pub fn line<Input>() -> Line<Input>
+where
+    Input: RangeStream<Token = char>,
+    Input::Range: crate::stream::Range,
+{
+    Line(PhantomData)
+}
+
+pub struct Rest<Input>(PhantomData<fn(Input)>);
+
+impl<Input> Parser<Input> for Rest<Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Input::Range, Input::Error> {
+        let len = input.range().len();
+        if input.is_partial() {
+            // More input may still arrive so the end has not been found yet
+            let checkpoint = input.checkpoint();
+            let _ = input.uncons_range(len);
+            let position = input.position();
+            ctry!(input.reset(checkpoint).committed());
+            return CommitErr(Input::Error::from_error(
+                position,
+                StreamError::end_of_input(),
+            ));
+        }
+        uncons_range(input, len)
+    }
+}
+
+/// Zero-copy parser which consumes and returns all of the remaining input.
+///
+/// When parsing partial input this only succeeds once the input is complete.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{rest, take_until_item};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = (take_until_item(':').skip(token(':')), rest());
+/// assert_eq!(parser.parse("key:some value"), Ok((("key", "some value"), "")));
+/// assert_eq!(parser.parse("key:"), Ok((("key", ""), "")));
+/// # }
+/// ```
+pub fn rest<Input>() -> Rest<Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    Rest(PhantomData)
+}
+
+pub struct RestLen<Input>(PhantomData<fn(Input)>);
+
+impl<Input> Parser<Input> for RestLen<Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    type Output = usize;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<usize, Input::Error> {
+        PeekOk(input.range().len())
+    }
+}
+
+/// Returns the length of the remaining input without consuming it.
+///
+/// For partial input only the part of the input which is currently available is counted.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{rest_len, take};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = (take(2), rest_len());
+/// assert_eq!(parser.parse("abcde"), Ok((("ab", 3), "cde")));
+/// assert_eq!(parser.parse("ab"), Ok((("ab", 0), "")));
+/// # }
+/// ```
+pub fn rest_len<Input>() -> RestLen<Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    RestLen(PhantomData)
+}
+
+/// Returns the position `back` tokens before the current position of `input`.
+///
+/// `input` can only be rewound as far as `checkpoint` so if the parser was resumed (the range was
+/// partially consumed in an earlier call) the position of `checkpoint` may be returned instead.
+fn position_before<Input>(
+    input: &mut Input,
+    checkpoint: Input::Checkpoint,
+    back: usize,
+) -> Result<Input::Position, Input::Error>
+where
+    Input: RangeStream,
+{
+    let end = input.checkpoint();
+    let consumed = input.distance(&checkpoint);
+    input.reset(checkpoint)?;
+    let _ = input.uncons_range(consumed.saturating_sub(back));
+    let position = input.position();
+    input.reset(end)?;
+    Ok(position)
+}
+
+#[derive(Copy, Clone)]
+pub struct Utf8<P>(P);
+
+impl<'a, Input, P> Parser<Input> for Utf8<P>
+where
+    Input: RangeStream<Token = u8, Range = &'a [u8]>,
+    P: Parser<Input, Output = &'a [u8]>,
+{
+    type Output = &'a str;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let checkpoint = input.checkpoint();
+        let (bytes, committed) = ctry!(self.0.parse_mode(mode, input, state));
+        match str::from_utf8(bytes) {
+            Ok(s) => committed.combine_commit(|_| PeekOk(s)),
+            Err(err) => {
+                // Report the error at the first byte which is not valid UTF-8
+                let back = bytes.len() - err.valid_up_to();
+                let (position, _) = ctry!(position_before(input, checkpoint, back).committed());
+
+                let mut error = Input::Error::from_error(
+                    position,
+                    StreamError::unexpected_token(bytes[err.valid_up_to()]),
+                );
+                error.add_message("invalid UTF-8");
+                committed.combine_commit(|_| PeekErr(error.into()))
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Zero-copy parser which validates the bytes returned by `parser` as UTF-8, returning them as a
+/// `&str`.
+///
+/// If the bytes are not valid UTF-8 the error is reported at the position of the first invalid
+/// byte.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{take_while1, utf8};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = utf8(take_while1(|b: u8| b != b' '));
+/// assert_eq!(parser.parse(&b"caf\xc3\xa9 rest"[..]), Ok(("café", &b" rest"[..])));
+///
+/// let input = &b"ab\xffc"[..];
+/// let err = utf8(take_while1(|b: u8| b != b' ')).easy_parse(input).unwrap_err();
+/// assert_eq!(err.position.translate_position(input), 2);
+/// # }
+/// ```
+pub fn utf8<'a, Input, P>(parser: P) -> Utf8<P>
+where
+    Input: RangeStream<Token = u8, Range = &'a [u8]>,
+    P: Parser<Input, Output = &'a [u8]>,
+{
+    Utf8(parser)
+}
+
+#[cfg(any(feature = "hex", feature = "base64"))]
+#[derive(Copy, Clone)]
+struct Decode<P, F>(P, F);
+
+#[cfg(any(feature = "hex", feature = "base64"))]
+impl<Input, P, F> Parser<Input> for Decode<P, F>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    P: Parser<Input, Output = Input::Range>,
+    F: FnMut(&[u8]) -> Result<Vec<u8>, (usize, &'static str)>,
+{
+    type Output = Vec<u8>;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let checkpoint = input.checkpoint();
+        let (encoded, committed) = ctry!(self.0.parse_mode(mode, input, state));
+        match (self.1)(encoded.as_ref()) {
+            Ok(decoded) => committed.combine_commit(|_| PeekOk(decoded)),
+            Err((offset, message)) => {
+                // Report the error at the character which could not be decoded, counting back
+                // from the end as `encoded` need not start at `checkpoint`
+                let end = input.checkpoint();
+                let skip = input
+                    .distance(&checkpoint)
+                    .checked_sub(encoded.len() - offset);
+                ctry!(input.reset(checkpoint).committed());
+                let _ = input.uncons_range(skip.unwrap_or(0));
+                let position = input.position();
+                let token = input.uncons();
+                ctry!(input.reset(end).committed());
+
+                let mut error = Input::Error::empty(position);
+                if let (Some(_), Ok(token)) = (skip, token) {
+                    if offset < encoded.len() {
+                        error.add(StreamError::unexpected_token(token));
+                    }
+                }
+                error.add_message(message);
+                committed.combine_commit(|_| PeekErr(error.into()))
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Parses `len * 2` hexadecimal digits and returns the `len` bytes they encode in a newly
+/// allocated `Vec`. The digits are taken from the input as a single range.
+///
+/// Invalid characters are reported at their position in the input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::hex_bytes;
+/// # use combine::*;
+/// # fn main() {
+/// assert_eq!(hex_bytes(2).parse("0aFFrest"), Ok((vec![0x0a, 0xff], "rest")));
+///
+/// let err = hex_bytes(2).easy_parse("0aGF").unwrap_err();
+/// assert_eq!(err.position.translate_position("0aGF"), 2);
+/// assert!(hex_bytes(2).parse("0aF").is_err());
+/// # }
+/// ```
+#[cfg(feature = "hex")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hex")))]
+pub fn hex_bytes<Input>(len: usize) -> impl Parser<Input, Output = Vec<u8>>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+{
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>, (usize, &'static str)> {
+        hex_04::decode(encoded).map_err(|err| match err {
+            hex_04::FromHexError::InvalidHexCharacter { index, .. } => {
+                (index, "invalid hexadecimal digit")
+            }
+            _ => (encoded.len(), "invalid hexadecimal length"),
+        })
+    }
+    Decode(take(len * 2), decode)
+}
+
+#[cfg(feature = "base64")]
+#[cfg_attr(docsrs, doc(cfg(feature = "base64")))]
+pub use base64_013::{CharacterSet as Base64CharacterSet, Config as Base64Config};
+
+/// Parses base64 encoded text and returns the bytes it encodes in a newly allocated `Vec`. The
+/// encoded text is taken from the input as a single range.
+///
+/// All characters which may appear in any of the base64 alphabets (ASCII letters and digits,
+/// `+`, `/`, `-`, `_` and `=`) are consumed and then decoded according to `config`. Characters
+/// which are not valid for `config` are reported at their position in the input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{base64, Base64CharacterSet, Base64Config};
+/// # use combine::*;
+/// # fn main() {
+/// let config = Base64Config::new(Base64CharacterSet::Standard, true);
+/// assert_eq!(base64(config).parse("aGVsbG8= rest"), Ok((b"hello".to_vec(), " rest")));
+///
+/// let err = base64(config).easy_parse("aGV_bG8=").unwrap_err();
+/// assert_eq!(err.position.translate_position("aGV_bG8="), 3);
+///
+/// let config = Base64Config::new(Base64CharacterSet::UrlSafe, false);
+/// assert_eq!(base64(config).parse(&b"_-8"[..]), Ok((vec![0xff, 0xef], &b""[..])));
+/// # }
+/// ```
+#[cfg(feature = "base64")]
+#[cfg_attr(docsrs, doc(cfg(feature = "base64")))]
+pub fn base64<Input>(config: Base64Config) -> impl Parser<Input, Output = Vec<u8>>
+where
+    Input: RangeStream,
+    Input::Token: Into<char>,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+{
+    fn is_base64<T>(token: T) -> bool
+    where
+        T: Into<char>,
+    {
+        let c = token.into();
+        c.is_ascii_alphanumeric() || "+/-_=".contains(c)
+    }
+    Decode(
+        take_while(is_base64::<Input::Token>),
+        move |encoded: &[u8]| {
+            base64_013::decode_config(encoded, config).map_err(|err| match err {
+                base64_013::DecodeError::InvalidByte(offset, _)
+                | base64_013::DecodeError::InvalidLastSymbol(offset, _) => {
+                    (offset, "invalid base64 character")
+                }
+                base64_013::DecodeError::InvalidLength => (encoded.len(), "invalid base64 length"),
+            })
+        },
+    )
+}
+
+/// Algorithm used by [`checksummed`] to compute the digest of the bytes parsed by its body.
+///
+/// Implemented for closures of the form `FnMut(&[u8]) -> O` as well as for the [`Crc32`],
+/// [`Adler32`] and [`XxHash64`] algorithms (behind the `crc32`, `adler32` and `xxhash` features).
+///
+/// [`checksummed`]: fn.checksummed.html
+/// [`Crc32`]: struct.Crc32.html
+/// [`Adler32`]: struct.Adler32.html
+/// [`XxHash64`]: struct.XxHash64.html
+pub trait Checksum {
+    /// The digest which is compared against the parsed checksum.
+    type Output: PartialEq;
+
+    /// Computes the digest of `bytes`.
+    fn checksum(&mut self, bytes: &[u8]) -> Self::Output;
+}
+
+impl<F, O> Checksum for F
+where
+    F: FnMut(&[u8]) -> O,
+    O: PartialEq,
+{
+    type Output = O;
+
+    fn checksum(&mut self, bytes: &[u8]) -> O {
+        self(bytes)
+    }
+}
+
+/// The CRC-32 (IEEE) checksum.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::byte::num::le_u32;
+/// # use combine::parser::range::{checksummed, take, Crc32};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = checksummed(take(9), le_u32(), Crc32);
+/// let input = b"123456789\x26\x39\xf4\xcb";
+/// assert_eq!(parser.parse(&input[..]), Ok((&b"123456789"[..], &b""[..])));
+/// assert!(parser.parse(&b"123456780\x26\x39\xf4\xcb"[..]).is_err());
+/// # }
+/// ```
+#[cfg(feature = "crc32")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crc32")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Crc32;
+
+#[cfg(feature = "crc32")]
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn checksum(&mut self, bytes: &[u8]) -> u32 {
+        crc32fast_1::hash(bytes)
+    }
+}
+
+/// The Adler-32 checksum.
+#[cfg(feature = "adler32")]
+#[cfg_attr(docsrs, doc(cfg(feature = "adler32")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Adler32;
+
+#[cfg(feature = "adler32")]
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn checksum(&mut self, bytes: &[u8]) -> u32 {
+        adler_1::adler32_slice(bytes)
+    }
+}
+
+/// The XXH64 hash, computed with the contained seed.
+#[cfg(feature = "xxhash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xxhash")))]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct XxHash64(pub u64);
+
+#[cfg(feature = "xxhash")]
+impl Checksum for XxHash64 {
+    type Output = u64;
+
+    fn checksum(&mut self, bytes: &[u8]) -> u64 {
+        xxhash_rust_08::xxh64::xxh64(bytes, self.0)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Checksummed<P, C, A> {
+    body: P,
+    checksum: C,
+    algorithm: A,
+}
+
+impl<Input, P, C, A> Parser<Input> for Checksummed<P, C, A>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    P: Parser<Input>,
+    C: Parser<Input, Output = A::Output>,
+    A: Checksum,
+{
+    type Output = P::Output;
+    type PartialState =
+        <(RecognizeWithValue<P>, RecognizeWithValue<C>) as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let Checksummed {
+            ref mut body,
+            ref mut checksum,
+            ref mut algorithm,
+        } = *self;
+
+        let checkpoint = input.checkpoint();
+        let (((range, value), (checksum_range, expected)), committed) =
+            ctry!((recognize_with_value(body), recognize_with_value(checksum))
+                .parse_mode(mode, input, state));
+        if algorithm.checksum(range.as_ref()) == expected {
+            return committed.combine_commit(|_| PeekOk(value));
+        }
+
+        // Report the mismatch at the start of the checksum
+        let (position, _) =
+            ctry!(position_before(input, checkpoint, checksum_range.len()).committed());
+
+        let error = Input::Error::from_error(
+            position,
+            StreamError::message_static_message("checksum mismatch"),
+        );
+        committed.combine_commit(|_| PeekErr(error.into()))
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.body.add_error(errors)
+    }
+}
+
+/// Parses `body` followed by `checksum`, failing with a "checksum mismatch" error unless the
+/// checksum equals the digest `algorithm` computes over the input consumed by `body`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{checksummed, take};
+/// # use combine::*;
+/// # fn main() {
+/// let sum = |bytes: &[u8]| bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+/// let mut parser = checksummed(take(3), any(), sum);
+/// assert_eq!(parser.parse(&b"\x01\x02\x03\x06rest"[..]), Ok((&b"\x01\x02\x03"[..], &b"rest"[..])));
+///
+/// let err = checksummed(take(3), any(), sum).easy_parse(&b"\x01\x02\x03\x07"[..]).unwrap_err();
+/// assert_eq!(err.position.translate_position(&b"\x01\x02\x03\x07"[..]), 3);
+/// # }
+/// ```
+pub fn checksummed<Input, P, C, A>(body: P, checksum: C, algorithm: A) -> Checksummed<P, C, A>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+    P: Parser<Input>,
+    C: Parser<Input, Output = A::Output>,
+    A: Checksum,
+{
+    Checksummed {
+        body,
+        checksum,
+        algorithm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::Parser;
+
+    use super::*;
+
+    #[test]
+    fn take_while_test() {
+        let result = take_while(|c: char| c.is_digit(10)).parse("123abc");
         assert_eq!(result, Ok(("123", "abc")));
         let result = take_while(|c: char| c.is_digit(10)).parse("abc");
         assert_eq!(result, Ok(("", "abc")));
@@ -691,4 +2006,17 @@ mod tests {
         let result = take_until_range("⁘⁙/⁘").parse("⚙️🛠️🦀=🏎️⁘⁙⁘⁘⁙/⁘⁘⁙/⁘");
         assert_eq!(result, Ok(("⚙️🛠️🦀=🏎️⁘⁙⁘", "⁘⁙/⁘⁘⁙/⁘")));
     }
+
+    #[test]
+    fn rest_waits_for_partial_input() {
+        use crate::stream::PartialStream;
+
+        let mut input = PartialStream("abc");
+        let result = rest().parse_with_state(&mut input, &mut ());
+        assert!(result.is_err());
+        assert_eq!(input.0, "abc");
+
+        assert_eq!(rest().parse("abc"), Ok(("abc", "")));
+        assert_eq!(rest().parse(""), Ok(("", "")));
+    }
 }
@@ -0,0 +1,537 @@
+//! Reflection over the structure of a parser.
+//!
+//! [`Parser::reflect`][] walks a parser, reporting sequences, choices, repetitions, labels and the
+//! rules defined with [`parser!`][] to a [`Visitor`][]. Parsers whose structure is only known
+//! while parsing (such as `then` or plain functions) are reported as opaque.
+//!
+//! [`Grammar`][] is a visitor which collects the walk into a tree that can be written as EBNF, which
+//! railroad diagram generators such as <https://www.bottlecaps.de/rr/ui> accept, or as a GraphViz
+//! graph.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::char::{char, digit, letter};
+//! # use combine::parser::reflect::Grammar;
+//! # fn main() {
+//! parser! {
+//!     fn number[Input]()(Input) -> String
+//!     where [Input: Stream<Token = char>]
+//!     {
+//!         many1(digit())
+//!     }
+//! }
+//!
+//! let mut assignment = (
+//!     many1::<String, _, _>(letter()),
+//!     char('='),
+//!     sep_by::<Vec<_>, _, _, _>(number(), char(',')),
+//! );
+//! let grammar = Grammar::of::<&str, _>(&mut assignment);
+//! assert_eq!(
+//!     grammar.to_string(),
+//!     "\
+//! start ::= <letter>+ '=' ( number ( ',' number )* )?
+//! number ::= digit+
+//! digit ::= <digit>
+//! "
+//! );
+//! # }
+//! ```
+//!
+//! [`Parser::reflect`]: ../trait.Parser.html#method.reflect
+//! [`parser!`]: ../../macro.parser.html
+//! [`Visitor`]: trait.Visitor.html
+//! [`Grammar`]: struct.Grammar.html
+
+use crate::{lib::fmt, stream::StreamOnce};
+
+#[cfg(feature = "std")]
+use crate::{stream::Stream, Parser};
+
+/// A combinator reported to [`Visitor::start`][].
+///
+/// [`Visitor::start`]: trait.Visitor.html#tymethod.start
+#[derive(Clone, Copy)]
+pub enum Node<'a> {
+    /// The parsers inside are parsed one after another.
+    Sequence,
+    /// One of the parsers inside is parsed.
+    Choice,
+    /// The parser inside is parsed at least `min` and at most `max` times.
+    Repeat { min: usize, max: Option<usize> },
+    /// The parser inside is parsed zero or one time.
+    Optional,
+    /// The parser inside is described by `label` in errors.
+    Label(&'a dyn fmt::Display),
+    /// A rule defined with `parser!` in the module `module`. `parameterized` rules take arguments
+    /// so each use of them may parse something different.
+    Rule {
+        module: &'static str,
+        name: &'static str,
+        parameterized: bool,
+    },
+}
+
+/// Receives the structure of a parser from [`Parser::reflect`][].
+///
+/// [`Parser::reflect`]: ../trait.Parser.html#method.reflect
+pub trait Visitor<Input>
+where
+    Input: StreamOnce,
+{
+    /// Visits a parser which parses `token`.
+    fn token(&mut self, token: &Input::Token);
+
+    /// Visits a parser which parses `range`.
+    fn range(&mut self, range: &Input::Range);
+
+    /// Visits a parser whose structure is not known.
+    fn opaque(&mut self);
+
+    /// Starts visiting a combinator, the parsers inside it are visited next unless this returns
+    /// `false`. Every call is matched by a call to `finish`.
+    fn start(&mut self, node: Node<'_>) -> bool;
+
+    /// Finishes visiting the combinator of the last `start` which has not been finished.
+    fn finish(&mut self);
+}
+
+/// Reports `node` to `visitor`, calling `children` to visit the parsers inside it unless the
+/// visitor skips them.
+pub fn visit<Input>(
+    visitor: &mut dyn Visitor<Input>,
+    node: Node<'_>,
+    children: impl FnOnce(&mut dyn Visitor<Input>),
+) where
+    Input: StreamOnce,
+{
+    if visitor.start(node) {
+        children(visitor);
+    }
+    visitor.finish();
+}
+
+/// The structure of a parser, as collected by [`Grammar`][].
+///
+/// [`Grammar`]: struct.Grammar.html
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expr {
+    /// A token or range, formatted with `Debug`.
+    Terminal(String),
+    /// A reference to the rule `name` of the module `module`.
+    Rule {
+        module: &'static str,
+        name: &'static str,
+    },
+    Sequence(Vec<Expr>),
+    Choice(Vec<Expr>),
+    Repeat {
+        min: usize,
+        max: Option<usize>,
+        expr: Box<Expr>,
+    },
+    Optional(Box<Expr>),
+    Label(String, Box<Expr>),
+    Opaque,
+}
+
+#[cfg(feature = "std")]
+impl Expr {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Choice(exprs) if exprs.len() > 1 => 0,
+            Expr::Sequence(exprs) if exprs.len() > 1 => 1,
+            Expr::Repeat { .. } | Expr::Optional(_) => 2,
+            Expr::Label(_, expr) if **expr != Expr::Opaque => expr.precedence(),
+            _ => 3,
+        }
+    }
+
+    fn fmt_prec(&self, f: &mut fmt::Formatter<'_>, precedence: u8, rules: &[Rule]) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "( ")?;
+            self.fmt_prec(f, 0, rules)?;
+            return write!(f, " )");
+        }
+        match self {
+            Expr::Terminal(s) => write!(f, "{}", s),
+            Expr::Rule { module, name } => write!(f, "{}", rule_name(rules, module, name)),
+            Expr::Sequence(exprs) | Expr::Choice(exprs) if exprs.is_empty() => write!(f, "()"),
+            Expr::Sequence(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " ")?;
+                    }
+                    expr.fmt_prec(f, 2, rules)?;
+                }
+                Ok(())
+            }
+            Expr::Choice(exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " | ")?;
+                    }
+                    expr.fmt_prec(f, 1, rules)?;
+                }
+                Ok(())
+            }
+            Expr::Repeat { min, max, expr } => match (min, max) {
+                (0, None) => write!(f, "{}*", Postfix(expr, rules)),
+                (1, None) => write!(f, "{}+", Postfix(expr, rules)),
+                (0, Some(1)) => write!(f, "{}?", Postfix(expr, rules)),
+                _ => {
+                    // EBNF has no bounded repetition so it is written out
+                    let mut exprs = vec![(**expr).clone(); *min];
+                    match max {
+                        Some(max) => {
+                            exprs.extend((*min..*max).map(|_| Expr::Optional(expr.clone())))
+                        }
+                        None => exprs.push(Expr::Repeat {
+                            min: 0,
+                            max: None,
+                            expr: expr.clone(),
+                        }),
+                    }
+                    Expr::Sequence(exprs).fmt_prec(f, precedence, rules)
+                }
+            },
+            Expr::Optional(expr) => write!(f, "{}?", Postfix(expr, rules)),
+            Expr::Label(label, expr) => match **expr {
+                Expr::Opaque => write!(f, "<{}>", label),
+                _ => expr.fmt_prec(f, precedence, rules),
+            },
+            Expr::Opaque => write!(f, "<?>"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+struct Postfix<'a>(&'a Expr, &'a [Rule]);
+
+#[cfg(feature = "std")]
+impl fmt::Display for Postfix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_prec(f, 3, self.1)
+    }
+}
+
+/// Writes the expression as EBNF.
+#[cfg(feature = "std")]
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_prec(f, 0, &[])
+    }
+}
+
+/// Returns the name which the rule `name` of `module` is written as. Rules which share their name
+/// with a rule of another module in `rules` are qualified with their module path (using `.` as the
+/// separator, since EBNF names can not contain `::`).
+#[cfg(feature = "std")]
+fn rule_name(rules: &[Rule], module: &str, name: &str) -> String {
+    if rules
+        .iter()
+        .any(|rule| rule.name == name && rule.module != module)
+    {
+        format!("{}.{}", module.replace("::", "."), name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// A rule defined with `parser!`, as collected by [`Grammar`][].
+///
+/// [`Grammar`]: struct.Grammar.html
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub expr: Expr,
+}
+
+#[cfg(feature = "std")]
+enum Frame {
+    Node(Expr),
+    Label(String),
+    Rule {
+        key: (&'static str, &'static str),
+        index: Option<usize>,
+        visited: bool,
+    },
+}
+
+/// The grammar of a parser along with the grammar of every rule it uses.
+///
+/// Rules without parameters are collected once and referred to by name. Rules with parameters are
+/// written out in place since each use of them may differ, except when they are used recursively.
+///
+/// `Display` writes the grammar as EBNF with one line for each rule, starting with a `start` rule
+/// for the parser itself unless that is a rule already. Tokens and ranges are written with their
+/// `Debug` implementations, parsers with an unknown structure are written as their label (`<digit>`)
+/// or as `<?>`.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grammar {
+    pub start: Expr,
+    pub rules: Vec<Rule>,
+}
+
+#[cfg(feature = "std")]
+struct Builder {
+    stack: Vec<(Frame, Vec<Expr>)>,
+    visiting: Vec<(&'static str, &'static str)>,
+    rules: Vec<Rule>,
+}
+
+#[cfg(feature = "std")]
+impl Builder {
+    fn push(&mut self, expr: Expr) {
+        self.stack.last_mut().expect("frame").1.push(expr);
+    }
+}
+
+#[cfg(feature = "std")]
+fn single(mut exprs: Vec<Expr>) -> Expr {
+    match exprs.len() {
+        0 => Expr::Opaque,
+        1 => exprs.pop().unwrap(),
+        _ => Expr::Sequence(exprs),
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Input> Visitor<Input> for Builder
+where
+    Input: StreamOnce,
+    Input::Token: fmt::Debug,
+    Input::Range: fmt::Debug,
+{
+    fn token(&mut self, token: &Input::Token) {
+        self.push(Expr::Terminal(format!("{:?}", token)));
+    }
+
+    fn range(&mut self, range: &Input::Range) {
+        self.push(Expr::Terminal(format!("{:?}", range)));
+    }
+
+    fn opaque(&mut self) {
+        self.push(Expr::Opaque);
+    }
+
+    fn start(&mut self, node: Node<'_>) -> bool {
+        let (frame, visit) = match node {
+            Node::Sequence => (Frame::Node(Expr::Sequence(Vec::new())), true),
+            Node::Choice => (Frame::Node(Expr::Choice(Vec::new())), true),
+            Node::Repeat { min, max } => (
+                Frame::Node(Expr::Repeat {
+                    min,
+                    max,
+                    expr: Box::new(Expr::Opaque),
+                }),
+                true,
+            ),
+            Node::Optional => (Frame::Node(Expr::Optional(Box::new(Expr::Opaque))), true),
+            Node::Label(label) => (Frame::Label(label.to_string()), true),
+            Node::Rule {
+                module,
+                name,
+                parameterized,
+            } => {
+                // Rules are identified by their module as well as their name, as rules in different
+                // modules may share a name
+                let key = (module, name);
+                if self.visiting.contains(&key)
+                    || self.rules.iter().any(|r| (r.module, r.name) == key)
+                {
+                    let frame = Frame::Rule {
+                        key,
+                        index: None,
+                        visited: false,
+                    };
+                    (frame, false)
+                } else {
+                    self.visiting.push(key);
+                    let index = if parameterized {
+                        None
+                    } else {
+                        self.rules.push(Rule {
+                            module,
+                            name,
+                            expr: Expr::Opaque,
+                        });
+                        Some(self.rules.len() - 1)
+                    };
+                    let frame = Frame::Rule {
+                        key,
+                        index,
+                        visited: true,
+                    };
+                    (frame, true)
+                }
+            }
+        };
+        self.stack.push((frame, Vec::new()));
+        visit
+    }
+
+    fn finish(&mut self) {
+        let (frame, children) = self.stack.pop().expect("frame");
+        let expr = match frame {
+            Frame::Node(Expr::Sequence(_)) => Expr::Sequence(
+                children
+                    .into_iter()
+                    .flat_map(|expr| match expr {
+                        Expr::Sequence(exprs) => exprs,
+                        expr => vec![expr],
+                    })
+                    .collect(),
+            ),
+            Frame::Node(Expr::Choice(_)) => Expr::Choice(
+                children
+                    .into_iter()
+                    .flat_map(|expr| match expr {
+                        Expr::Choice(exprs) => exprs,
+                        expr => vec![expr],
+                    })
+                    .collect(),
+            ),
+            Frame::Node(Expr::Repeat { min, max, .. }) => Expr::Repeat {
+                min,
+                max,
+                expr: Box::new(single(children)),
+            },
+            Frame::Node(_) => Expr::Optional(Box::new(single(children))),
+            Frame::Label(label) => Expr::Label(label, Box::new(single(children))),
+            Frame::Rule {
+                key: (module, name),
+                index,
+                visited,
+            } => {
+                if visited {
+                    self.visiting.pop();
+                }
+                match index {
+                    Some(index) => {
+                        self.rules[index].expr = single(children);
+                        Expr::Rule { module, name }
+                    }
+                    None if visited => single(children),
+                    None => Expr::Rule { module, name },
+                }
+            }
+        };
+        self.push(expr);
+    }
+}
+
+#[cfg(feature = "std")]
+impl Grammar {
+    /// Collects the grammar of `parser`.
+    pub fn of<Input, P>(parser: &mut P) -> Grammar
+    where
+        Input: Stream,
+        Input::Token: fmt::Debug,
+        Input::Range: fmt::Debug,
+        P: ?Sized + Parser<Input>,
+    {
+        let mut builder = Builder {
+            stack: vec![(Frame::Label(String::new()), Vec::new())],
+            visiting: Vec::new(),
+            rules: Vec::new(),
+        };
+        parser.reflect(&mut builder);
+        let (_, children) = builder.stack.pop().expect("frame");
+        Grammar {
+            start: single(children),
+            rules: builder.rules,
+        }
+    }
+
+    /// Writes the grammar as a GraphViz graph, with a node for each combinator and an edge from
+    /// each rule reference to the rule.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph grammar {\n    node [shape=box];\n");
+        let mut next = 0;
+        let start = dot_node(&mut dot, &mut next, &self.rules, &self.start);
+        dot.push_str(&format!(
+            "    start [shape=ellipse];\n    start -> n{};\n",
+            start
+        ));
+        for rule in &self.rules {
+            let expr = dot_node(&mut dot, &mut next, &self.rules, &rule.expr);
+            dot.push_str(&format!(
+                "    \"rule {}\" [label=\"{0}\", shape=ellipse];\n    \"rule {0}\" -> n{};\n",
+                rule_name(&self.rules, rule.module, rule.name),
+                expr
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(feature = "std")]
+fn dot_node(dot: &mut String, next: &mut usize, rules: &[Rule], expr: &Expr) -> usize {
+    let id = *next;
+    *next += 1;
+    let (label, children): (String, Vec<&Expr>) = match expr {
+        Expr::Terminal(s) => (s.clone(), vec![]),
+        Expr::Rule { module, name } => {
+            dot.push_str(&format!(
+                "    n{} [label=\"{}\", shape=ellipse];\n    n{0} -> \"rule {1}\" [style=dashed];\n",
+                id,
+                rule_name(rules, module, name)
+            ));
+            return id;
+        }
+        Expr::Sequence(exprs) => ("sequence".into(), exprs.iter().collect()),
+        Expr::Choice(exprs) => ("choice".into(), exprs.iter().collect()),
+        Expr::Repeat { min, max, expr } => (
+            match max {
+                Some(max) => format!("repeat {}..={}", min, max),
+                None => format!("repeat {}..", min),
+            },
+            vec![expr],
+        ),
+        Expr::Optional(expr) => ("optional".into(), vec![expr]),
+        Expr::Label(label, expr) => (format!("<{}>", label), vec![expr]),
+        Expr::Opaque => ("?".into(), vec![]),
+    };
+    dot.push_str(&format!(
+        "    n{} [label=\"{}\"];\n",
+        id,
+        label.replace('\\', "\\\\").replace('"', "\\\"")
+    ));
+    for child in children {
+        if let Expr::Opaque = child {
+            if let Expr::Label(..) = expr {
+                continue;
+            }
+        }
+        let child = dot_node(dot, next, rules, child);
+        dot.push_str(&format!("    n{} -> n{};\n", id, child));
+    }
+    id
+}
+
+/// Writes the grammar as EBNF.
+#[cfg(feature = "std")]
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Expr::Rule { .. } = self.start {
+        } else {
+            write!(f, "start ::= ")?;
+            self.start.fmt_prec(f, 0, &self.rules)?;
+            writeln!(f)?;
+        }
+        for rule in &self.rules {
+            write!(f, "{} ::= ", rule_name(&self.rules, rule.module, rule.name))?;
+            rule.expr.fmt_prec(f, 0, &self.rules)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 //! Module containing regex parsers on streams returning ranges of `&str` or `&[u8]`.
 //!
 //! All regex parsers are overloaded on `&str` and `&[u8]` ranges and can take a `Regex` by value
-//! or shared reference (`&`).
+//! or shared reference (`&`). Regexes are searched through the rest of the input unless they start
+//! with `^` or are wrapped with [`anchored`][].
 //!
 //! Enabled using the `regex` feature (for `regex-0.2`) or the `regex-1` feature for `regex-1.0`.
 //!
@@ -30,8 +31,13 @@
 //!     );
 //! }
 //! ```
+//!
+//! [`anchored`]: fn.anchored.html
 
-use std::{iter::FromIterator, marker::PhantomData};
+use std::{
+    iter::{self, FromIterator},
+    marker::PhantomData,
+};
 
 use crate::{
     error::{
@@ -98,6 +104,121 @@ where
     }
 }
 
+/// Regexes which can be compiled into a regex which only matches at the start of the input, see
+/// [`anchored`][]. Only the pattern is recompiled, options set with a `RegexBuilder` are lost.
+///
+/// [`anchored`]: fn.anchored.html
+pub trait Anchor {
+    type Anchored;
+    fn anchor(&self) -> Self::Anchored;
+}
+
+impl<R> Anchor for &R
+where
+    R: ?Sized + Anchor,
+{
+    type Anchored = R::Anchored;
+    fn anchor(&self) -> Self::Anchored {
+        (**self).anchor()
+    }
+}
+
+/// A regex which only matches at the start of the input, see [`anchored`][].
+///
+/// [`anchored`]: fn.anchored.html
+#[derive(Clone, Debug)]
+pub struct Anchored<R>(R);
+
+/// Anchors `regex` at the current position of the input, as if it started with `^`.
+///
+/// Passing the anchored regex to [`match_`][], [`find`][] or [`captures`][] makes them fail
+/// (without consuming any input) unless `regex` matches at the current position instead of
+/// skipping ahead to the first match. [`find_many`][] and [`captures_many`][] return the matches
+/// which directly follow each other from the current position.
+///
+/// The pattern of `regex` is compiled again as `^(?:pattern)` so matching never searches through
+/// the rest of the input. This happens on every call, so create the anchored regex once and pass a
+/// reference to it (`find(&number)`) instead of calling `anchored` each time a parser is built.
+///
+/// # Note
+///
+/// Only the pattern of `regex` is recompiled, so any options set with a `RegexBuilder` (such as
+/// `case_insensitive`, `multi_line` or the size limits) are lost. Write them as inline flags in
+/// the pattern instead, for example `(?i)abc`.
+///
+/// Like the other regex parsers, the anchored regex only sees the input starting at the current
+/// position, so assertions which look behind it, such as `\b` or `\B` at the start of the
+/// pattern, treat the current position as the start of the input.
+///
+/// # Panics
+///
+/// Panics if the anchored pattern fails to compile, which can happen if it exceeds the default
+/// size limit of the regex crate.
+///
+/// ```
+/// use regex::Regex;
+/// use combine::Parser;
+/// use combine::parser::regex::{anchored, captures, find, find_many};
+///
+/// let number = anchored(Regex::new("[0-9]+").unwrap());
+/// assert_eq!(find(&number).parse("12 34"), Ok(("12", " 34")));
+/// assert!(find(&number).parse("a 12").is_err());
+///
+/// let keyword = anchored(Regex::new("(?i)let").unwrap());
+/// assert_eq!(find(&keyword).parse("LET x"), Ok(("LET", " x")));
+/// assert_eq!(
+///     find_many(anchored(Regex::new("[0-9]").unwrap())).parse("12 3"),
+///     Ok((vec!["1", "2"], " 3"))
+/// );
+///
+/// let field = Regex::new("([a-z]+)=([0-9]+)").unwrap();
+/// assert_eq!(
+///     captures(anchored(&field)).parse("x=1;y=2"),
+///     Ok((vec!["x=1", "x", "1"], ";y=2"))
+/// );
+/// assert!(captures::<Vec<_>, _, _>(anchored(&field)).parse(";y=2").is_err());
+/// ```
+///
+/// [`match_`]: fn.match_.html
+/// [`find`]: fn.find.html
+/// [`captures`]: fn.captures.html
+/// [`find_many`]: fn.find_many.html
+/// [`captures_many`]: fn.captures_many.html
+pub fn anchored<R>(regex: R) -> Anchored<R::Anchored>
+where
+    R: Anchor,
+{
+    Anchored(regex.anchor())
+}
+
+/// Collects the matches of an anchored regex which directly follow each other. `next` matches
+/// the regex at an offset into the input, returning the length of the match and its value.
+fn anchored_iter<T, F>(mut next: impl FnMut(usize) -> Option<(usize, T)>) -> (usize, F)
+where
+    F: FromIterator<T>,
+{
+    let mut end = 0;
+    let mut done = false;
+    let value = iter::from_fn(|| {
+        if done {
+            return None;
+        }
+        let (len, value) = next(end)?;
+        if len == 0 {
+            // An empty match would be found again at the same offset, so stop after it (or
+            // before it if it directly follows another match)
+            done = true;
+            if end != 0 {
+                return None;
+            }
+        }
+        end += len;
+        Some(value)
+    })
+    .collect();
+    (end, value)
+}
+
 fn find_iter<'a, Input, F>(iterable: Input) -> (usize, F)
 where
     Input: IntoIterator,
@@ -121,7 +242,7 @@ mod regex {
 
     use std::iter::FromIterator;
 
-    use super::{find_iter, MatchFind, Regex};
+    use super::{anchored_iter, find_iter, Anchor, Anchored, MatchFind, Regex};
 
     pub use self::regex::*;
 
@@ -216,6 +337,88 @@ mod regex {
             regex::bytes::Regex::as_str(self)
         }
     }
+
+    impl Anchor for regex::Regex {
+        type Anchored = regex::Regex;
+        fn anchor(&self) -> regex::Regex {
+            regex::Regex::new(&format!("^(?:{})", self.as_str()))
+                .expect("anchored pattern of a valid regex")
+        }
+    }
+
+    impl<'a> Regex<&'a str> for Anchored<regex::Regex> {
+        fn is_match(&self, range: &'a str) -> bool {
+            self.0.is_match(range)
+        }
+        fn find_iter<F>(&self, range: &'a str) -> (usize, F)
+        where
+            F: FromIterator<&'a str>,
+        {
+            anchored_iter(|offset| {
+                let m = self.0.find(&range[offset..])?;
+                Some((m.end(), m.as_match()))
+            })
+        }
+        fn captures<F, G>(&self, range: &'a str) -> (usize, G)
+        where
+            F: FromIterator<&'a str>,
+            G: FromIterator<F>,
+        {
+            anchored_iter(|offset| {
+                let captures = self.0.captures(&range[offset..])?;
+                let len = captures.get(0).unwrap().end();
+                let value = captures
+                    .iter()
+                    .filter_map(|match_| match_.map(|m| m.as_match()))
+                    .collect();
+                Some((len, value))
+            })
+        }
+        fn as_str(&self) -> &str {
+            self.0.as_str()
+        }
+    }
+
+    impl Anchor for regex::bytes::Regex {
+        type Anchored = regex::bytes::Regex;
+        fn anchor(&self) -> regex::bytes::Regex {
+            regex::bytes::Regex::new(&format!("^(?:{})", self.as_str()))
+                .expect("anchored pattern of a valid regex")
+        }
+    }
+
+    impl<'a> Regex<&'a [u8]> for Anchored<regex::bytes::Regex> {
+        fn is_match(&self, range: &'a [u8]) -> bool {
+            self.0.is_match(range)
+        }
+        fn find_iter<F>(&self, range: &'a [u8]) -> (usize, F)
+        where
+            F: FromIterator<&'a [u8]>,
+        {
+            anchored_iter(|offset| {
+                let m = self.0.find(&range[offset..])?;
+                Some((m.end(), m.as_match()))
+            })
+        }
+        fn captures<F, G>(&self, range: &'a [u8]) -> (usize, G)
+        where
+            F: FromIterator<&'a [u8]>,
+            G: FromIterator<F>,
+        {
+            anchored_iter(|offset| {
+                let captures = self.0.captures(&range[offset..])?;
+                let len = captures.get(0).unwrap().end();
+                let value = captures
+                    .iter()
+                    .filter_map(|match_| match_.map(|m| m.as_match()))
+                    .collect();
+                Some((len, value))
+            })
+        }
+        fn as_str(&self) -> &str {
+            self.0.as_str()
+        }
+    }
 }
 
 pub struct Match<R, Input>(R, PhantomData<Input>);
@@ -532,9 +735,12 @@ where
 #[cfg(test)]
 mod tests {
 
-    use regex::Regex;
+    use regex::{bytes, Regex};
 
-    use crate::{parser::regex::find, Parser};
+    use crate::{
+        parser::regex::{anchored, captures_many, find, find_many, match_, Regex as _},
+        Parser,
+    };
 
     #[test]
     fn test() {
@@ -546,4 +752,33 @@ mod tests {
         assert_eq!(digits2.parse("123 456 "), Ok(("123", " 456 ")));
         assert_eq!(digits2.parse("abc 123 456 "), Ok(("123", " 456 ")));
     }
+
+    #[test]
+    fn anchored_regex() {
+        let word = bytes::Regex::new("[a-z]+").unwrap();
+        assert_eq!(
+            find(anchored(&word)).parse(&b"ab cd"[..]),
+            Ok((&b"ab"[..], &b" cd"[..]))
+        );
+        assert!(find(anchored(&word)).parse(&b" cd"[..]).is_err());
+        assert!(match_(anchored(&word)).parse(&b"ab"[..]).is_ok());
+        assert!(match_(anchored(&word)).parse(&b"1ab"[..]).is_err());
+
+        let pair = Regex::new("([a-z])([0-9]),?").unwrap();
+        assert_eq!(
+            captures_many(anchored(pair)).parse("a1,b2 c3"),
+            Ok((vec![vec!["a1,", "a", "1"], vec!["b2", "b", "2"]], " c3"))
+        );
+
+        let a = Regex::new("a*").unwrap();
+        assert_eq!(anchored(&a).as_str(), "^(?:a*)");
+        assert_eq!(
+            find_many::<Vec<_>, _, _>(anchored(&a)).parse("aab"),
+            Ok((vec!["aa"], "b"))
+        );
+        assert_eq!(
+            find_many::<Vec<_>, _, _>(anchored(&a)).parse("b"),
+            Ok((vec![""], "b"))
+        );
+    }
 }
@@ -6,19 +6,67 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, StdParseResult, StreamError, Tracked,
     },
-    lib::{borrow::BorrowMut, cmp, marker::PhantomData, mem},
+    lib::{
+        borrow::BorrowMut,
+        cmp, iter,
+        marker::PhantomData,
+        mem,
+        ops::{Bound, RangeBounds},
+    },
     parser::{
         choice::{optional, Optional, Or},
-        combinator::{ignore, Ignore},
+        combinator::{ignore, Either, Ignore},
         function::{parser, FnParser},
-        sequence::With,
+        reflect::{visit, Node, Visitor},
+        sequence::{Skip, With},
         token::{value, Value},
         FirstMode, ParseMode,
     },
-    stream::{uncons, Stream, StreamOnce},
+    stream::{uncons, Stream, StreamErrorFor, StreamOnce},
     ErrorOffset, Parser,
 };
 
+fn reflect_repeat<Input, P>(
+    visitor: &mut dyn Visitor<Input>,
+    min: usize,
+    max: Option<usize>,
+    parser: &mut P,
+) where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    visit(visitor, Node::Repeat { min, max }, |v| parser.reflect(v))
+}
+
+/// Describes `parser (separator parser)* separator?` where the trailing separator is only allowed
+/// if `trailing` is set and the whole sequence is optional if `min` is `0`.
+fn reflect_sep_by<Input, P, S>(
+    visitor: &mut dyn Visitor<Input>,
+    min: usize,
+    trailing: bool,
+    parser: &mut P,
+    separator: &mut S,
+) where
+    Input: Stream,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    let mut elements = |visitor: &mut dyn Visitor<Input>| {
+        visit(visitor, Node::Sequence, |visitor| {
+            parser.reflect(visitor);
+            reflect_repeat(visitor, 0, None, &mut (&mut *separator, &mut *parser));
+            if trailing {
+                visit(visitor, Node::Optional, |v| separator.reflect(v));
+            }
+        })
+    };
+    if min == 0 {
+        visit(visitor, Node::Optional, elements)
+    } else {
+        elements(visitor)
+    }
+}
+
 parser! {
 pub struct Count;
 
@@ -47,6 +95,257 @@ where [
 }
 }
 
+/// Fixed-size buffer used by [`count_exact`] to collect the elements before turning them into an
+/// array.
+///
+/// [`count_exact`]: fn.count_exact.html
+#[doc(hidden)]
+pub struct ArrayBuffer<T, const N: usize> {
+    values: [Option<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayBuffer<T, N> {
+    const NONE: Option<T> = None;
+
+    fn into_array(mut self) -> [T; N] {
+        assert_eq!(self.len, N, "ArrayBuffer is not full");
+        let mut array = mem::MaybeUninit::<[T; N]>::uninit();
+        let ptr = array.as_mut_ptr() as *mut T;
+        for (i, value) in self.values.iter_mut().enumerate() {
+            let value = value.take().expect("ArrayBuffer is not full");
+            // SAFETY: `i < N` so the write is in bounds of the array
+            unsafe { ptr.add(i).write(value) };
+        }
+        // SAFETY: All `N` elements were written above
+        unsafe { array.assume_init() }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayBuffer<T, N> {
+    fn default() -> Self {
+        ArrayBuffer {
+            values: [Self::NONE; N],
+            len: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayBuffer<T, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            if self.len < N {
+                self.values[self.len] = Some(value);
+                self.len += 1;
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct CountExact<P, const N: usize>(P);
+
+impl<Input, P, const N: usize> Parser<Input> for CountExact<P, N>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = [P::Output; N];
+    type PartialState = (usize, ArrayBuffer<P::Output, N>, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        count_min_max::<ArrayBuffer<P::Output, N>, _, _>(N, N, &mut self.0)
+            .map(ArrayBuffer::into_array)
+            .parse_mode(mode, input, state)
+    }
+
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(error)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, N, Some(N), &mut self.0)
+    }
+}
+
+/// Parses `parser` exactly `N` times, returning the values in an array.
+///
+/// Unlike [`count`] no `Vec` (or other `Extend` collection) is needed, which makes this useful for
+/// fixed-length fields in binary formats.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::byte::num::be_u16;
+/// # use combine::parser::repeat::count_exact;
+/// # fn main() {
+/// let mut parser = count_exact(any());
+/// let result: Result<([u8; 4], _), _> = parser.parse(&b"abcde"[..]);
+/// assert_eq!(result, Ok((*b"abcd", &b"e"[..])));
+///
+/// let result = count_exact::<_, _, 2>(be_u16()).parse(&b"\x00\x01\x00\x02"[..]);
+/// assert_eq!(result, Ok(([1, 2], &b""[..])));
+///
+/// let result = count_exact::<_, _, 3>(token(b'a')).parse(&b"aab"[..]);
+/// assert!(result.is_err());
+/// # }
+/// ```
+///
+/// [`count`]: fn.count.html
+pub fn count_exact<Input, P, const N: usize>(parser: P) -> CountExact<P, N>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    CountExact(parser)
+}
+
+/// `ArrayVec` wrapper which can be collected into by [`many`], [`count`], [`sep_by`] etc. without
+/// panicking once its capacity is reached. Used together with [`collect_array_vec`].
+///
+/// The buffer stops taking elements from the repetition as soon as one more element than fits has
+/// been parsed, so the repetition ends there instead of parsing the rest of its input. (Repetitions
+/// of single token parsers on range streams take all of their tokens in a single scan of the
+/// input and are only stopped after that scan.)
+///
+/// [`many`]: fn.many.html
+/// [`count`]: fn.count.html
+/// [`sep_by`]: fn.sep_by.html
+/// [`collect_array_vec`]: fn.collect_array_vec.html
+#[cfg(feature = "arrayvec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrayvec")))]
+pub struct ArrayVecBuffer<T, const N: usize> {
+    values: arrayvec::ArrayVec<T, N>,
+    overflowed: bool,
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T, const N: usize> Default for ArrayVecBuffer<T, N> {
+    fn default() -> Self {
+        ArrayVecBuffer {
+            values: arrayvec::ArrayVec::new(),
+            overflowed: false,
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T, const N: usize> Extend<T> for ArrayVecBuffer<T, N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        if self.overflowed {
+            return;
+        }
+        for value in iter {
+            if self.values.try_push(value).is_err() {
+                // Stop pulling from `iter` which ends the repetition which is parsing the values
+                self.overflowed = true;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+#[derive(Copy, Clone)]
+pub struct CollectArrayVec<P>(P);
+
+#[cfg(feature = "arrayvec")]
+impl<Input, P, T, const N: usize> Parser<Input> for CollectArrayVec<P>
+where
+    Input: Stream,
+    P: Parser<Input, Output = ArrayVecBuffer<T, N>>,
+{
+    type Output = arrayvec::ArrayVec<T, N>;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        (&mut self.0)
+            .and_then(|buffer: ArrayVecBuffer<T, N>| {
+                if buffer.overflowed {
+                    Err(crate::stream::StreamErrorFor::<Input>::message_format(
+                        format_args!("expected at most {} elements", N),
+                    ))
+                } else {
+                    Ok(buffer.values)
+                }
+            })
+            .parse_mode(mode, input, state)
+    }
+
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(error)
+    }
+
+    fn add_committed_expected_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_committed_expected_error(error)
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.0.parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, Some(N), &mut self.0)
+    }
+}
+
+/// Turns a repetition parser which collects into an [`ArrayVecBuffer`] into one that returns an
+/// `ArrayVec`, failing instead of panicking if more than `N` elements were parsed.
+///
+/// ```
+/// # extern crate combine;
+/// # extern crate arrayvec;
+/// # use arrayvec::ArrayVec;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::repeat::collect_array_vec;
+/// # fn main() {
+/// let mut parser = collect_array_vec(sep_by(digit(), char(',')));
+/// let result: Result<(ArrayVec<char, 3>, _), _> = parser.parse("1,2,3");
+/// assert_eq!(result.map(|(v, rest)| (v.into_inner().unwrap(), rest)), Ok((['1', '2', '3'], "")));
+///
+/// let result: Result<(ArrayVec<char, 3>, _), _> = parser.parse("1,2,3,4");
+/// assert!(result.is_err());
+/// # }
+/// ```
+///
+/// [`ArrayVecBuffer`]: struct.ArrayVecBuffer.html
+#[cfg(feature = "arrayvec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrayvec")))]
+pub fn collect_array_vec<Input, P, T, const N: usize>(parser: P) -> CollectArrayVec<P>
+where
+    Input: Stream,
+    P: Parser<Input, Output = ArrayVecBuffer<T, N>>,
+{
+    CollectArrayVec(parser)
+}
+
 parser! {
     pub struct SkipCount;
     type PartialState = <With<Count<Sink, Input, P>, Value<Input, ()>> as Parser<Input>>::PartialState;
@@ -162,6 +461,10 @@ where
     fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
         self.parser.add_error(error)
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, self.min, Some(self.max), &mut self.parser)
+    }
 }
 
 /// Parses `parser` from `min` to `max` times (including `min` and `max`).
@@ -199,6 +502,54 @@ where
     }
 }
 
+/// Parses `parser` as many times as allowed by `range`, where `range` is any range of `usize`
+/// (`n..`, `..=m`, `n..m`, `n..=m`, `..` or `n..=n`).
+///
+/// This covers [`many`][], [`many1`][], [`count`][] and [`count_min_max`][] so the bounds can be
+/// decided at runtime without choosing a different parser.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::repeat::repeat;
+/// # fn main() {
+/// let mut parser = repeat(2..=3, token(b'a'));
+/// assert_eq!(parser.parse(&b"aaaab"[..]), Ok((b"aaa".to_vec(), &b"ab"[..])));
+/// assert!(parser.parse(&b"ab"[..]).is_err());
+///
+/// let mut parser = repeat(.., token(b'a'));
+/// assert_eq!(parser.parse(&b"b"[..]), Ok((vec![], &b"b"[..])));
+/// # }
+/// ```
+///
+/// [`many`]: fn.many.html
+/// [`many1`]: fn.many1.html
+/// [`count`]: fn.count.html
+/// [`count_min_max`]: fn.count_min_max.html
+///
+/// # Panics
+///
+/// If `range` is empty.
+pub fn repeat<F, Input, P, R>(range: R, parser: P) -> CountMinMax<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Extend<P::Output> + Default,
+    R: RangeBounds<usize>,
+{
+    let min = match range.start_bound() {
+        Bound::Included(&min) => min,
+        Bound::Excluded(&min) => min.checked_add(1).expect("empty repeat range"),
+        Bound::Unbounded => 0,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(&max) => max,
+        Bound::Excluded(&max) => max.checked_sub(1).expect("empty repeat range"),
+        Bound::Unbounded => usize::MAX,
+    };
+    count_min_max(min, max, parser)
+}
+
 parser! {
     pub struct SkipCountMinMax;
     type PartialState = <With<CountMinMax<Sink, P>, Value<Input, ()>> as Parser<Input>>::PartialState;
@@ -366,6 +717,81 @@ where
     }
 }
 
+/// Iterator returned by [`Parser::parse_iter`] which applies a parser repeatedly until the end of
+/// the input is reached.
+///
+/// [`Parser::parse_iter`]: ../trait.Parser.html#method.parse_iter
+pub struct ParseIter<Input, P> {
+    parser: P,
+    input: Input,
+    done: bool,
+}
+
+impl<Input, P> ParseIter<Input, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    pub(crate) fn new(parser: P, input: Input) -> Self {
+        ParseIter {
+            parser,
+            input,
+            done: false,
+        }
+    }
+
+    /// Returns the input which has not been parsed yet.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Consumes the iterator, returning the input which has not been parsed yet.
+    pub fn into_input(self) -> Input {
+        self.input
+    }
+}
+
+impl<Input, P> Iterator for ParseIter<Input, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Item = Result<P::Output, <Input as StreamOnce>::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let before = self.input.checkpoint();
+        match self.input.uncons() {
+            Err(ref err) if err.is_unexpected_end_of_input() => {
+                self.done = true;
+                return None;
+            }
+            _ => {
+                if let Err(err) = self.input.reset(before) {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        match self.parser.parse_stream(&mut self.input).into_result() {
+            Ok((value, Commit::Commit(()))) => Some(Ok(value)),
+            Ok((value, Commit::Peek(()))) => {
+                // Parsing the same input again would yield the same value forever
+                self.done = true;
+                Some(Ok(value))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err.into_inner().into_error()))
+            }
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Many<F, P>(P, PhantomData<F>);
 
@@ -392,6 +818,14 @@ where
         // TODO
         let (ref mut elements, ref mut child_state) = *state;
 
+        // Parsers of a single token can take all of their tokens in one pass over the input
+        if let Some(result) = self
+            .0
+            .parse_many_tokens(input, &mut |x| elements.extend(Some(x)))
+        {
+            return result.map(|()| mem::take(elements));
+        }
+
         let mut iter = (&mut self.0).partial_iter(mode, input, child_state);
         elements.extend(iter.by_ref());
         iter.into_result_fast(elements)
@@ -408,6 +842,10 @@ where
     fn parser_count(&self) -> ErrorOffset {
         self.0.parser_count()
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, None, &mut self.0)
+    }
 }
 
 /// Parses `p` zero or more times returning a collection with the values from `p`.
@@ -439,33 +877,387 @@ where
     Many(p, PhantomData)
 }
 
+/// Creates an iterator which lazily parses `p` zero or more times from `input`, yielding each
+/// value as it is parsed instead of collecting them like [`many`].
+///
+/// Iteration stops when `p` fails. As errors can't be returned through the iterator,
+/// [`Iter::into_result`] should be called afterwards to check that `p` did not fail after
+/// committing input.
+///
+/// NOTE: If `p` can succeed without consuming any input this may loop forever as the iterator
+/// will repeatedly use `p` to parse the same location in the input every time
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, spaces};
+/// # use combine::error::Commit;
+/// # use combine::parser::repeat::many_iter;
+/// # fn main() {
+/// let mut input = "1 2 3 !";
+/// let mut iter = many_iter(digit().skip(spaces()), &mut input);
+/// let sum: u32 = iter.by_ref().map(|c| c.to_digit(10).unwrap()).sum();
+/// assert_eq!(iter.into_result(sum), Ok((6, Commit::Commit(()))));
+/// assert_eq!(input, "!");
+/// # }
+/// ```
+///
+/// [`many`]: fn.many.html
+/// [`Iter::into_result`]: struct.Iter.html#method.into_result
+pub fn many_iter<Input, P>(
+    p: P,
+    input: &mut Input,
+) -> Iter<'_, Input, P, P::PartialState, FirstMode>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    Iter::new(p, FirstMode, input, Default::default())
+}
+
 #[derive(Copy, Clone)]
-pub struct Many1<F, P>(P, PhantomData<fn() -> F>);
-impl<F, Input, P> Parser<Input> for Many1<F, P>
+pub struct FoldMany<P, A, F> {
+    parser: P,
+    init: A,
+    f: F,
+}
+
+impl<Input, P, A, F> Parser<Input> for FoldMany<P, A, F>
 where
     Input: Stream,
-    F: Extend<P::Output> + Default,
     P: Parser<Input>,
+    A: Clone,
+    F: FnMut(A, P::Output) -> A,
 {
-    type Output = F;
-    type PartialState = (bool, bool, F, P::PartialState);
+    type Output = A;
+    type PartialState = (Option<A>, P::PartialState);
 
     parse_mode!(Input);
     #[inline]
     fn parse_mode_impl<M>(
         &mut self,
-        mut mode: M,
+        mode: M,
         input: &mut Input,
         state: &mut Self::PartialState,
-    ) -> ParseResult<F, Input::Error>
+    ) -> ParseResult<Self::Output, Input::Error>
     where
         M: ParseMode,
     {
-        let (ref mut parsed_one, ref mut committed_state, ref mut elements, ref mut child_state) =
-            *state;
+        let FoldMany {
+            ref mut parser,
+            ref init,
+            ref mut f,
+        } = *self;
+        let (ref mut acc, ref mut child_state) = *state;
+
+        let mut value = acc.take().unwrap_or_else(|| init.clone());
+        let mut iter = parser.partial_iter(mode, input, child_state);
+        for x in iter.by_ref() {
+            value = f(value, x);
+        }
+        match iter.into_result_(()) {
+            CommitErr(err) => {
+                // Keep the accumulated value around so that partial parsing can resume
+                *acc = Some(value);
+                CommitErr(err)
+            }
+            result => result.map(|()| value),
+        }
+    }
 
-        if mode.is_first() || !*parsed_one {
-            debug_assert!(!*parsed_one);
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.add_error(errors);
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.parser.parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, None, &mut self.parser)
+    }
+}
+
+/// Parses `p` zero or more times, folding each value into an accumulator which starts out as
+/// `init`.
+///
+/// Unlike [`many`] no intermediate collection is built, which is useful when the values only need
+/// to be summed, counted or inserted into some other structure.
+///
+/// NOTE: If `p` can succeed without consuming any input this may hang forever as `fold_many` will
+/// repeatedly use `p` to parse the same location in the input every time
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # fn main() {
+/// let result = fold_many(0, digit(), |sum, c: char| sum + c.to_digit(10).unwrap())
+///     .parse("123A")
+///     .map(|x| x.0);
+/// assert_eq!(result, Ok(6));
+/// # }
+/// ```
+///
+/// [`many`]: fn.many.html
+pub fn fold_many<Input, P, A, F>(init: A, p: P, f: F) -> FoldMany<P, A, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    A: Clone,
+    F: FnMut(A, P::Output) -> A,
+{
+    FoldMany { parser: p, init, f }
+}
+
+pub struct ManyInto<'a, F, P> {
+    parser: P,
+    collection: &'a mut F,
+}
+
+impl<'a, F, Input, P> Parser<Input> for ManyInto<'a, F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Extend<P::Output>,
+{
+    type Output = ();
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        // The values parsed so far live in `collection` so resuming a partial parse only needs
+        // the state of the inner parser
+        let mut iter = (&mut self.parser).partial_iter(mode, input, state);
+        self.collection.extend(iter.by_ref());
+        iter.into_result_(())
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.add_error(errors);
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.parser.parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, None, &mut self.parser)
+    }
+}
+
+/// Parses `p` zero or more times, appending each value to `collection` instead of returning a new
+/// collection like [`many`] does.
+///
+/// The collection is not cleared beforehand, so a single buffer can be cleared and reused across
+/// many parses to avoid allocating a fresh collection every time.
+///
+/// NOTE: If `p` can succeed without consuming any input this may hang forever as `many_into` will
+/// repeatedly use `p` to parse the same location in the input every time
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::repeat::many_into;
+/// # fn main() {
+/// let mut buffer = Vec::new();
+/// for frame in &["12;", "345;"] {
+///     buffer.clear();
+///     let result = many_into(&mut buffer, digit()).skip(char(';')).parse(*frame);
+///     assert_eq!(result, Ok(((), "")));
+/// }
+/// assert_eq!(buffer, ['3', '4', '5']);
+/// # }
+/// ```
+///
+/// [`many`]: fn.many.html
+pub fn many_into<F, Input, P>(collection: &mut F, p: P) -> ManyInto<'_, F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Extend<P::Output>,
+{
+    ManyInto {
+        parser: p,
+        collection,
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ManySink<P, F> {
+    parser: P,
+    sink: F,
+}
+
+impl<Input, P, F, E> Parser<Input> for ManySink<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<(), E>,
+    E: Into<StreamErrorFor<Input>>,
+{
+    type Output = ();
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        // Values are handed to the sink as soon as they are parsed so resuming a partial parse only
+        // needs the state of the inner parser
+        let mut iter = (&mut self.parser).partial_iter(mode, input, state);
+        while let Some(value) = iter.next() {
+            if let Err(err) = (self.sink)(value) {
+                return iter.fail(err.into());
+            }
+        }
+        iter.into_result_(())
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.add_error(errors);
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.parser.parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, None, &mut self.parser)
+    }
+}
+
+/// Parses `p` zero or more times, passing each value to `sink` as soon as it is parsed instead of
+/// collecting the values.
+///
+/// If `sink` returns an error, parsing stops and the error is returned at the position after the
+/// value which was rejected.
+///
+/// NOTE: If `p` can succeed without consuming any input this may hang forever as `many_sink` will
+/// repeatedly use `p` to parse the same location in the input every time
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, spaces};
+/// # use combine::parser::repeat::many_sink;
+/// # use combine::stream::easy;
+/// # fn main() {
+/// let mut sum = 0;
+/// let mut parser = many_sink(digit().skip(spaces()), |c: char| {
+///     sum += c.to_digit(10).unwrap();
+///     if sum > 10 {
+///         Err(easy::Error::Message("sum exceeds 10".into()))
+///     } else {
+///         Ok(())
+///     }
+/// });
+/// assert_eq!(parser.easy_parse("1 2 3"), Ok(((), "")));
+/// assert!(parser.easy_parse("4 5 6").is_err());
+/// drop(parser);
+/// // Parsing stopped as soon as the sink returned an error
+/// assert_eq!(sum, 15);
+/// # }
+/// ```
+pub fn many_sink<Input, P, F, E>(p: P, sink: F) -> ManySink<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<(), E>,
+    E: Into<StreamErrorFor<Input>>,
+{
+    ManySink { parser: p, sink }
+}
+
+/// Parses `input` with `parser` repeatedly until it fails without committing, passing each value
+/// to `sink` and returning the remaining input.
+///
+/// Equivalent to `many_sink(parser, sink).parse(input)`, see [`many_sink`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, letter};
+/// # use combine::parser::repeat::parse_into;
+/// # use combine::stream::StreamErrorFor;
+/// # fn main() {
+/// let mut words = 0;
+/// let result = parse_into(many1::<String, _, _>(letter()).skip(char(';')), "ab;c;1", |_| {
+///     words += 1;
+///     Ok::<_, StreamErrorFor<&str>>(())
+/// });
+/// assert_eq!(result, Ok("1"));
+/// assert_eq!(words, 2);
+/// # }
+/// ```
+///
+/// [`many_sink`]: fn.many_sink.html
+pub fn parse_into<Input, P, F, E>(parser: P, input: Input, sink: F) -> Result<Input, Input::Error>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<(), E>,
+    E: Into<StreamErrorFor<Input>>,
+{
+    many_sink(parser, sink).parse(input).map(|((), rest)| rest)
+}
+
+#[derive(Copy, Clone)]
+pub struct Many1<F, P>(P, PhantomData<fn() -> F>);
+impl<F, Input, P> Parser<Input> for Many1<F, P>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+{
+    type Output = F;
+    type PartialState = (bool, bool, F, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<F, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut parsed_one, ref mut committed_state, ref mut elements, ref mut child_state) =
+            *state;
+
+        if mode.is_first() || !*parsed_one {
+            debug_assert!(!*parsed_one);
 
             let (first, committed) = ctry!(self.0.parse_mode(mode, input, child_state));
             elements.extend(Some(first));
@@ -496,6 +1288,10 @@ where
     }
 
     forward_parser!(Input, add_error parser_count, 0);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 1, None, &mut self.0)
+    }
 }
 
 /// Parses `p` one or more times returning a collection with the values from `p`.
@@ -641,6 +1437,10 @@ where
     }
 
     forward_parser!(Input, add_error parser_count, parser);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 0, false, &mut self.parser, &mut self.separator)
+    }
 }
 
 /// Parses `parser` zero or more time separated by `separator`, returning a collection with the
@@ -739,6 +1539,10 @@ where
     }
 
     forward_parser!(Input, add_error parser_count, parser);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 1, false, &mut self.parser, &mut self.separator)
+    }
 }
 
 /// Parses `parser` one or more time separated by `separator`, returning a collection with the
@@ -783,25 +1587,111 @@ where
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct SepEndBy<F, P, S> {
-    parser: P,
-    separator: S,
-    _marker: PhantomData<fn() -> F>,
+/// A collection which is statically known to contain at least one element, returned by
+/// [`many1_non_empty`] and [`sep_by1_non_empty`].
+///
+/// [`many1_non_empty`]: fn.many1_non_empty.html
+/// [`sep_by1_non_empty`]: fn.sep_by1_non_empty.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonEmpty<T, F> {
+    /// The first element.
+    pub head: T,
+    /// The elements after the first.
+    pub tail: F,
 }
 
-impl<F, Input, P, S> Parser<Input> for SepEndBy<F, P, S>
+impl<T, F> NonEmpty<T, F> {
+    /// Returns an iterator over all the elements, starting with `head`.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        &'a F: IntoIterator<Item = &'a T>,
+    {
+        iter::once(&self.head).chain(&self.tail)
+    }
+}
+
+impl<T, F> IntoIterator for NonEmpty<T, F>
+where
+    F: IntoIterator<Item = T>,
+{
+    type Item = T;
+    type IntoIter = iter::Chain<iter::Once<T>, F::IntoIter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        iter::once(self.head).chain(self.tail)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<NonEmpty<T, Vec<T>>> for Vec<T> {
+    fn from(non_empty: NonEmpty<T, Vec<T>>) -> Vec<T> {
+        let mut vec = non_empty.tail;
+        vec.insert(0, non_empty.head);
+        vec
+    }
+}
+
+/// Collection used by [`many1_non_empty`] and [`sep_by1_non_empty`] to build a [`NonEmpty`].
+///
+/// [`many1_non_empty`]: fn.many1_non_empty.html
+/// [`sep_by1_non_empty`]: fn.sep_by1_non_empty.html
+/// [`NonEmpty`]: struct.NonEmpty.html
+#[doc(hidden)]
+pub struct NonEmptyBuffer<T, F> {
+    head: Option<T>,
+    tail: F,
+}
+
+impl<T, F> NonEmptyBuffer<T, F> {
+    fn into_non_empty(self) -> NonEmpty<T, F> {
+        NonEmpty {
+            head: self
+                .head
+                .expect("NonEmptyBuffer must have been extended with at least one element"),
+            tail: self.tail,
+        }
+    }
+}
+
+impl<T, F> Default for NonEmptyBuffer<T, F>
+where
+    F: Default,
+{
+    fn default() -> Self {
+        NonEmptyBuffer {
+            head: None,
+            tail: F::default(),
+        }
+    }
+}
+
+impl<T, F> Extend<T> for NonEmptyBuffer<T, F>
+where
+    F: Extend<T>,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        if self.head.is_none() {
+            self.head = iter.next();
+        }
+        self.tail.extend(iter);
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Many1NonEmpty<F, P>(P, PhantomData<fn() -> F>);
+
+impl<F, Input, P> Parser<Input> for Many1NonEmpty<F, P>
 where
     Input: Stream,
     F: Extend<P::Output> + Default,
     P: Parser<Input>,
-    S: Parser<Input>,
 {
-    type Output = F;
-    type PartialState = <Or<
-        SepEndBy1<F, P, S>,
-        FnParser<Input, fn(&mut Input) -> StdParseResult<F, Input>>,
-    > as Parser<Input>>::PartialState;
+    type Output = NonEmpty<P::Output, F>;
+    type PartialState = <Many1<NonEmptyBuffer<P::Output, F>, P> as Parser<Input>>::PartialState;
 
     parse_mode!(Input);
     #[inline]
@@ -814,13 +1704,281 @@ where
     where
         M: ParseMode,
     {
-        sep_end_by1(&mut self.parser, &mut self.separator)
-            .or(parser(|_| Ok((F::default(), Commit::Peek(())))))
+        many1::<NonEmptyBuffer<P::Output, F>, _, _>(&mut self.0)
+            .map(NonEmptyBuffer::into_non_empty)
             .parse_mode(mode, input, state)
     }
 
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        self.parser.add_error(errors)
+        self.0.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.add_error(errors);
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.0.parser_count()
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 1, None, &mut self.0)
+    }
+}
+
+/// Parses `p` one or more times like [`many1`], but returns the values as a [`NonEmpty`] so that
+/// the first value can be accessed without checking whether the collection is empty.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::repeat::{many1_non_empty, NonEmpty};
+/// # fn main() {
+/// let result = many1_non_empty::<Vec<_>, _, _>(digit()).parse("123A");
+/// assert_eq!(result, Ok((NonEmpty { head: '1', tail: vec!['2', '3'] }, "A")));
+/// assert!(many1_non_empty::<Vec<_>, _, _>(digit()).parse("A").is_err());
+/// # }
+/// ```
+///
+/// [`many1`]: fn.many1.html
+/// [`NonEmpty`]: struct.NonEmpty.html
+pub fn many1_non_empty<F, Input, P>(p: P) -> Many1NonEmpty<F, P>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+{
+    Many1NonEmpty(p, PhantomData)
+}
+
+#[derive(Copy, Clone)]
+pub struct SepBy1NonEmpty<F, P, S> {
+    parser: P,
+    separator: S,
+    _marker: PhantomData<fn() -> F>,
+}
+
+impl<F, Input, P, S> Parser<Input> for SepBy1NonEmpty<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = NonEmpty<P::Output, F>;
+    type PartialState = <SepBy1<NonEmptyBuffer<P::Output, F>, P, S> as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        sep_by1::<NonEmptyBuffer<P::Output, F>, _, _, _>(&mut self.parser, &mut self.separator)
+            .map(NonEmptyBuffer::into_non_empty)
+            .parse_mode(mode, input, state)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.separator.add_error(errors)
+    }
+
+    forward_parser!(Input, add_error parser_count, parser);
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 1, false, &mut self.parser, &mut self.separator)
+    }
+}
+
+/// Parses `parser` one or more times separated by `separator` like [`sep_by1`], but returns the
+/// values as a [`NonEmpty`] so that the first value can be accessed without checking whether the
+/// collection is empty.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::repeat::{sep_by1_non_empty, NonEmpty};
+/// # fn main() {
+/// let mut parser = sep_by1_non_empty::<Vec<_>, _, _, _>(digit(), token(','));
+/// let result = parser.parse("1,2,3");
+/// assert_eq!(result, Ok((NonEmpty { head: '1', tail: vec!['2', '3'] }, "")));
+///
+/// let values: Vec<char> = result.unwrap().0.into();
+/// assert_eq!(values, ['1', '2', '3']);
+/// # }
+/// ```
+///
+/// [`sep_by1`]: fn.sep_by1.html
+/// [`NonEmpty`]: struct.NonEmpty.html
+pub fn sep_by1_non_empty<F, Input, P, S>(parser: P, separator: S) -> SepBy1NonEmpty<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    SepBy1NonEmpty {
+        parser,
+        separator,
+        _marker: PhantomData,
+    }
+}
+
+/// Parser used by [`sep_by_iter`] which parses `parser` on the first call and `separator`
+/// followed by `parser` on every call after that.
+///
+/// [`sep_by_iter`]: fn.sep_by_iter.html
+#[derive(Copy, Clone)]
+pub struct SepByItem<P, S> {
+    parser: P,
+    separator: S,
+    first: bool,
+}
+
+impl<Input, P, S> Parser<Input> for SepByItem<P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = <With<S, P> as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        if self.first {
+            let result = self.parser.parse_mode(mode, input, &mut state.B.state);
+            if result.is_ok() {
+                self.first = false;
+            }
+            result
+        } else {
+            (&mut self.separator)
+                .with(&mut self.parser)
+                .parse_mode(mode, input, state)
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if self.first {
+            self.parser.add_error(errors)
+        } else {
+            self.separator.add_error(errors)
+        }
+    }
+}
+
+/// Iterator returned by [`sep_by_iter`].
+///
+/// [`sep_by_iter`]: fn.sep_by_iter.html
+pub type SepByIter<'a, Input, P, S> =
+    Iter<'a, Input, SepByItem<P, S>, <SepByItem<P, S> as Parser<Input>>::PartialState, FirstMode>;
+
+/// Creates an iterator which lazily parses `parser` zero or more times separated by `separator`,
+/// yielding each value from `parser` as it is parsed instead of collecting them like
+/// [`sep_by`].
+///
+/// Iteration stops when there are no more elements. As errors can't be returned through the
+/// iterator, [`Iter::into_result`] should be called afterwards to check that parsing did not fail
+/// after committing input (for instance after a trailing separator).
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::error::Commit;
+/// # use combine::parser::repeat::sep_by_iter;
+/// # fn main() {
+/// let mut input = "1,2,3";
+/// let mut iter = sep_by_iter(digit(), token(','), &mut input);
+/// let count = iter.by_ref().count();
+/// assert_eq!(iter.into_result(count), Ok((3, Commit::Commit(()))));
+///
+/// let mut input = "1,2,";
+/// let mut iter = sep_by_iter(digit(), token(','), &mut input);
+/// let count = iter.by_ref().count();
+/// assert!(iter.into_result(count).is_err());
+/// # }
+/// ```
+///
+/// [`sep_by`]: fn.sep_by.html
+/// [`Iter::into_result`]: struct.Iter.html#method.into_result
+pub fn sep_by_iter<Input, P, S>(
+    parser: P,
+    separator: S,
+    input: &mut Input,
+) -> SepByIter<'_, Input, P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    let item = SepByItem {
+        parser,
+        separator,
+        first: true,
+    };
+    Iter::new(item, FirstMode, input, Default::default())
+}
+
+#[derive(Copy, Clone)]
+pub struct SepEndBy<F, P, S> {
+    parser: P,
+    separator: S,
+    _marker: PhantomData<fn() -> F>,
+}
+
+impl<F, Input, P, S> Parser<Input> for SepEndBy<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = F;
+    type PartialState = <Or<
+        SepEndBy1<F, P, S>,
+        FnParser<Input, fn(&mut Input) -> StdParseResult<F, Input>>,
+    > as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        sep_end_by1(&mut self.parser, &mut self.separator)
+            .or(parser(|_| Ok((F::default(), Commit::Peek(())))))
+            .parse_mode(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 0, true, &mut self.parser, &mut self.separator)
     }
 }
 
@@ -857,6 +2015,88 @@ where
     }
 }
 
+/// Whether [`separated`][] allows, requires or forbids a separator after the last element.
+///
+/// [`separated`]: fn.separated.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trailing {
+    /// The last element must not be followed by a separator (as in [`sep_by`](fn.sep_by.html)).
+    Forbidden,
+    /// The last element may be followed by a separator (as in
+    /// [`sep_end_by`](fn.sep_end_by.html)).
+    Optional,
+    /// Every element must be followed by a separator.
+    Required,
+}
+
+type SeparatedInner<F, P, S> =
+    Either<SepBy<F, P, S>, Either<SepEndBy<F, P, S>, Many<F, Skip<P, S>>>>;
+
+pub struct Separated<F, P, S>(SeparatedInner<F, P, S>);
+impl<F, Input, P, S> Parser<Input> for Separated<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = F;
+    type PartialState = <SeparatedInner<F, P, S> as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode(mode, input, state)
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
+}
+
+/// Parses `parser` zero or more times separated by `separator`, returning a collection with the
+/// values from `parser`. `trailing` decides whether the last element may, must or must not be
+/// followed by a separator.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::repeat::{separated, Trailing};
+/// # fn main() {
+/// let mut forbidden = separated(digit(), char(','), Trailing::Forbidden);
+/// assert_eq!(forbidden.parse("1,2"), Ok((vec!['1', '2'], "")));
+/// assert!(forbidden.parse("1,2,").is_err());
+///
+/// let mut optional = separated(digit(), char(','), Trailing::Optional);
+/// assert_eq!(optional.parse("1,2"), Ok((vec!['1', '2'], "")));
+/// assert_eq!(optional.parse("1,2,"), Ok((vec!['1', '2'], "")));
+///
+/// let mut required = separated(digit(), char(';'), Trailing::Required);
+/// assert_eq!(required.parse("1;2;"), Ok((vec!['1', '2'], "")));
+/// assert!(required.parse("1;2").is_err());
+/// # }
+/// ```
+pub fn separated<F, Input, P, S>(parser: P, separator: S, trailing: Trailing) -> Separated<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    Separated(match trailing {
+        Trailing::Forbidden => Either::Left(sep_by(parser, separator)),
+        Trailing::Optional => Either::Right(Either::Left(sep_end_by(parser, separator))),
+        Trailing::Required => Either::Right(Either::Right(many(parser.skip(separator)))),
+    })
+}
+
 #[derive(Copy, Clone)]
 pub struct SepEndBy1<F, P, S> {
     parser: P,
@@ -925,6 +2165,10 @@ where
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
         self.parser.add_error(errors)
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 1, true, &mut self.parser, &mut self.separator)
+    }
 }
 
 /// Parses `parser` one or more times separated and ended by `separator`, returning a collection
@@ -1019,7 +2263,7 @@ where
                 }
                 Err(Commit::Commit(err)) => {
                     *l_state = Some((l, committed));
-                    return CommitErr(err.error);
+                    return CommitErr(err.into_error());
                 }
                 Err(Commit::Peek(_)) => {
                     ctry!(input.reset(before).committed());
@@ -1027,123 +2271,458 @@ where
                 }
             }
         }
-        Ok((l, committed)).into()
+        Ok((l, committed)).into()
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 1, false, &mut self.0, &mut self.1)
+    }
+}
+
+/// Parses `p` 1 or more times separated by `op`. The value returned is the one produced by the
+/// left associative application of the function returned by the parser `op`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # fn main() {
+/// let number = digit().map(|c: char| c.to_digit(10).unwrap());
+/// let sub = token('-').map(|_| |l: u32, r: u32| l - r);
+/// let mut parser = chainl1(number, sub);
+/// assert_eq!(parser.parse("9-3-5"), Ok((1, "")));
+/// # }
+/// ```
+pub fn chainl1<Input, P, Op>(parser: P, op: Op) -> Chainl1<P, Op>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    Op: Parser<Input>,
+    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
+{
+    Chainl1(parser, op)
+}
+
+#[derive(Copy, Clone)]
+pub struct Chainr1<P, Op>(P, Op);
+impl<Input, P, Op> Parser<Input> for Chainr1<P, Op>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    Op: Parser<Input>,
+    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
+{
+    type Output = P::Output;
+    type PartialState = ();
+    #[cfg(feature = "std")]
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
+        // FIXME FastResult
+        let (first, mut committed) = ctry!(self.0.parse_lazy(input));
+        // Collect the operators and operands and fold them from the right afterwards so that long
+        // chains do not recurse
+        let mut rest = Vec::new();
+        loop {
+            let before = input.checkpoint();
+            let op = match self.1.parse_lazy(input).into() {
+                Ok((x, c)) => {
+                    committed = committed.merge(c);
+                    x
+                }
+                Err(Commit::Commit(err)) => return CommitErr(err.into_error()),
+                Err(Commit::Peek(_)) => {
+                    ctry!(input.reset(before).committed());
+                    break;
+                }
+            };
+            let before = input.checkpoint();
+            match self.0.parse_lazy(input).into() {
+                Ok((r, c)) => {
+                    rest.push((op, r));
+                    committed = committed.merge(c);
+                }
+                Err(Commit::Commit(err)) => return CommitErr(err.into_error()),
+                Err(Commit::Peek(_)) => {
+                    ctry!(input.reset(before).committed());
+                    break;
+                }
+            }
+        }
+        let value = match rest.pop() {
+            Some((mut op, mut r)) => {
+                while let Some((prev_op, l)) = rest.pop() {
+                    r = op(l, r);
+                    op = prev_op;
+                }
+                op(first, r)
+            }
+            None => first,
+        };
+        Ok((value, committed)).into()
+    }
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
+        // FIXME FastResult
+        let (mut l, mut committed) = ctry!(self.0.parse_lazy(input));
+        loop {
+            let before = input.checkpoint();
+            let op = match self.1.parse_lazy(input).into() {
+                Ok((x, rest)) => {
+                    committed = committed.merge(rest);
+                    x
+                }
+                Err(Commit::Commit(err)) => return CommitErr(err.into_error()),
+                Err(Commit::Peek(_)) => {
+                    ctry!(input.reset(before).committed());
+                    break;
+                }
+            };
+            let before = input.checkpoint();
+            match self.parse_lazy(input).into() {
+                Ok((r, rest)) => {
+                    l = op(l, r);
+                    committed = committed.merge(rest);
+                }
+                Err(Commit::Commit(err)) => return CommitErr(err.into_error()),
+                Err(Commit::Peek(_)) => {
+                    ctry!(input.reset(before).committed());
+                    break;
+                }
+            }
+        }
+        Ok((l, committed)).into()
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_sep_by(visitor, 1, false, &mut self.0, &mut self.1)
+    }
+}
+
+/// Parses `p` one or more times separated by `op`. The value returned is the one produced by the
+/// right associative application of the function returned by `op`.
+///
+/// With the `std` feature the chain is parsed in a loop, so arbitrarily long chains can not
+/// overflow the call stack.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # fn main() {
+/// let number = digit().map(|c: char| c.to_digit(10).unwrap());
+/// let pow = token('^').map(|_| |l: u32, r: u32| l.pow(r));
+/// let mut parser = chainr1(number, pow);
+///     assert_eq!(parser.parse("2^3^2"), Ok((512, "")));
+///
+///     let cons = token(':').map(|_| |l: u32, r: u32| l + r);
+///     let mut parser = chainr1(digit().map(|c: char| c.to_digit(10).unwrap()), cons);
+///     let long = vec!["1"; 100_000].join(":");
+///     assert_eq!(parser.parse(&long[..]), Ok((100_000, "")));
+/// }
+/// ```
+pub fn chainr1<Input, P, Op>(parser: P, op: Op) -> Chainr1<P, Op>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    Op: Parser<Input>,
+    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
+{
+    Chainr1(parser, op)
+}
+
+/// The part of the input [`nested`] is currently parsing.
+///
+/// [`nested`]: fn.nested.html
+#[doc(hidden)]
+#[derive(Copy, Clone, PartialEq)]
+pub enum NestedPhase {
+    Open,
+    Inner,
+    Close,
+}
+
+// `#[default]` on enum variants requires a newer compiler than the one this crate supports
+#[allow(clippy::derivable_impls)]
+impl Default for NestedPhase {
+    fn default() -> Self {
+        NestedPhase::Open
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Nested<F, O, P, C> {
+    open: O,
+    inner: P,
+    close: C,
+    _marker: PhantomData<fn() -> F>,
+}
+impl<F, Input, O, P, C> Parser<Input> for Nested<F, O, P, C>
+where
+    Input: Stream,
+    O: Parser<Input>,
+    P: Parser<Input>,
+    C: Parser<Input>,
+    F: Extend<O::Output> + Default,
+{
+    type Output = (F, P::Output);
+    type PartialState = (
+        NestedPhase,
+        bool,
+        F,
+        usize,
+        Option<P::Output>,
+        O::PartialState,
+        P::PartialState,
+        C::PartialState,
+    );
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (
+            ref mut phase,
+            ref mut committed,
+            ref mut opens,
+            ref mut depth,
+            ref mut value,
+            ref mut open_state,
+            ref mut inner_state,
+            ref mut close_state,
+        ) = *state;
+
+        if mode.is_first() {
+            *phase = NestedPhase::Open;
+            *committed = false;
+            *depth = 0;
+        }
+
+        if *phase == NestedPhase::Open {
+            let mut iter = (&mut self.open).partial_iter(mode, input, open_state);
+            opens.extend(iter.by_ref().inspect(|_| *depth += 1));
+            let ((), c) = ctry!(iter.into_result_fast(&mut ()));
+            *committed |= !c.is_peek();
+            *phase = NestedPhase::Inner;
+            mode.set_first();
+        }
+
+        if *phase == NestedPhase::Inner {
+            match self.inner.parse_mode(mode, input, inner_state) {
+                PeekOk(x) => *value = Some(x),
+                CommitOk(x) => {
+                    *committed = true;
+                    *value = Some(x);
+                }
+                PeekErr(mut err) => {
+                    *phase = NestedPhase::Open;
+                    *opens = F::default();
+                    return if *committed {
+                        self.open.add_error(&mut err);
+                        self.inner.add_error(&mut err);
+                        CommitErr(err.into_error())
+                    } else {
+                        PeekErr(err)
+                    };
+                }
+                CommitErr(err) => return CommitErr(err),
+            }
+            *phase = NestedPhase::Close;
+            mode.set_first();
+        }
+
+        while *depth > 0 {
+            match self.close.parse_mode(mode, input, close_state) {
+                PeekOk(_) => (),
+                CommitOk(_) => *committed = true,
+                PeekErr(mut err) => {
+                    *phase = NestedPhase::Open;
+                    *opens = F::default();
+                    *value = None;
+                    return if *committed {
+                        self.close.add_error(&mut err);
+                        CommitErr(err.into_error())
+                    } else {
+                        PeekErr(err)
+                    };
+                }
+                CommitErr(err) => return CommitErr(err),
+            }
+            *depth -= 1;
+            mode.set_first();
+        }
+
+        *phase = NestedPhase::Open;
+        let output = (mem::take(opens), value.take().unwrap());
+        if mem::replace(committed, false) {
+            CommitOk(output)
+        } else {
+            PeekOk(output)
+        }
     }
 
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        self.0.add_error(errors)
+        self.open.add_error(errors);
+        self.inner.add_error(errors);
     }
 }
 
-/// Parses `p` 1 or more times separated by `op`. The value returned is the one produced by the
-/// left associative application of the function returned by the parser `op`.
+/// Parses any number of `open`, then `inner` and then `close` once for each `open`, the
+/// non-recursive equivalent of `nested = open nested close | inner`.
+///
+/// Returns the outputs of `open`, outermost first, together with the output of `inner`. As the
+/// nesting is counted in a loop instead of through recursion, arbitrarily deep input can not
+/// overflow the call stack.
+///
+/// NOTE: `open` must consume input when it succeeds or this may hang forever.
 ///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
-/// # use combine::parser::char::digit;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::repeat::nested;
 /// # fn main() {
-/// let number = digit().map(|c: char| c.to_digit(10).unwrap());
-/// let sub = token('-').map(|_| |l: u32, r: u32| l - r);
-/// let mut parser = chainl1(number, sub);
-/// assert_eq!(parser.parse("9-3-5"), Ok((1, "")));
+/// let mut parser = nested::<Vec<_>, _, _, _, _>(char('['), digit(), char(']'));
+/// assert_eq!(parser.parse("[[[1]]]"), Ok(((vec!['['; 3], '1'), "")));
+/// assert_eq!(parser.parse("2"), Ok(((vec![], '2'), "")));
+/// assert!(parser.parse("[[1]").is_err());
+///
+/// let deep = format!("{}1{}", "[".repeat(100_000), "]".repeat(100_000));
+/// let result = parser.parse(&deep[..]).map(|((opens, value), _)| (opens.len(), value));
+/// assert_eq!(result, Ok((100_000, '1')));
 /// # }
 /// ```
-pub fn chainl1<Input, P, Op>(parser: P, op: Op) -> Chainl1<P, Op>
+pub fn nested<F, Input, O, P, C>(open: O, inner: P, close: C) -> Nested<F, O, P, C>
 where
     Input: Stream,
+    O: Parser<Input>,
     P: Parser<Input>,
-    Op: Parser<Input>,
-    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
+    C: Parser<Input>,
+    F: Extend<O::Output> + Default,
 {
-    Chainl1(parser, op)
+    Nested {
+        open,
+        inner,
+        close,
+        _marker: PhantomData,
+    }
 }
 
 #[derive(Copy, Clone)]
-pub struct Chainr1<P, Op>(P, Op);
-impl<Input, P, Op> Parser<Input> for Chainr1<P, Op>
+pub struct TakeUntil<F, P> {
+    end: P,
+    _marker: PhantomData<fn() -> F>,
+}
+impl<F, Input, P> Parser<Input> for TakeUntil<F, P>
 where
     Input: Stream,
+    F: Extend<<Input as StreamOnce>::Token> + Default,
     P: Parser<Input>,
-    Op: Parser<Input>,
-    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
 {
-    type Output = P::Output;
-    type PartialState = ();
+    type Output = F;
+    type PartialState = (F, P::PartialState);
+
+    parse_mode!(Input);
     #[inline]
-    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<P::Output, Input::Error> {
-        // FIXME FastResult
-        let (mut l, mut committed) = ctry!(self.0.parse_lazy(input));
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut output, ref mut end_state) = *state;
+
+        let mut committed = Commit::Peek(());
         loop {
             let before = input.checkpoint();
-            let op = match self.1.parse_lazy(input).into() {
-                Ok((x, rest)) => {
-                    committed = committed.merge(rest);
-                    x
+            match self.end.parse_mode(mode, input, end_state).into() {
+                Ok((_, rest)) => {
+                    ctry!(input.reset(before).committed());
+                    return match committed.merge(rest) {
+                        Commit::Commit(()) => CommitOk(mem::replace(output, F::default())),
+                        Commit::Peek(()) => PeekOk(mem::replace(output, F::default())),
+                    };
                 }
-                Err(Commit::Commit(err)) => return CommitErr(err.error),
                 Err(Commit::Peek(_)) => {
                     ctry!(input.reset(before).committed());
-                    break;
-                }
-            };
-            let before = input.checkpoint();
-            match self.parse_lazy(input).into() {
-                Ok((r, rest)) => {
-                    l = op(l, r);
-                    committed = committed.merge(rest);
+                    output.extend(Some(ctry!(uncons(input)).0));
+                    committed = Commit::Commit(());
                 }
-                Err(Commit::Commit(err)) => return CommitErr(err.error),
-                Err(Commit::Peek(_)) => {
+                Err(Commit::Commit(e)) => {
                     ctry!(input.reset(before).committed());
-                    break;
+                    return CommitErr(e.into_error());
                 }
-            }
+            };
         }
-        Ok((l, committed)).into()
-    }
-    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        self.0.add_error(errors)
     }
 }
 
-/// Parses `p` one or more times separated by `op`. The value returned is the one produced by the
-/// right associative application of the function returned by `op`.
+/// Takes input until `end` is encountered or `end` indicates that it has committed input before
+/// failing (`attempt` can be used to make it look like it has not committed any input)
 ///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
-/// # use combine::parser::char::digit;
+/// # use combine::parser::char;
+/// # use combine::parser::byte;
+/// # use combine::parser::combinator::attempt;
+/// # use combine::parser::repeat::take_until;
 /// # fn main() {
-/// let number = digit().map(|c: char| c.to_digit(10).unwrap());
-/// let pow = token('^').map(|_| |l: u32, r: u32| l.pow(r));
-/// let mut parser = chainr1(number, pow);
-///     assert_eq!(parser.parse("2^3^2"), Ok((512, "")));
+///     let mut char_parser = take_until(char::digit());
+///     assert_eq!(char_parser.parse("abc123"), Ok(("abc".to_string(), "123")));
+///
+///     let mut byte_parser = take_until(byte::bytes(&b"TAG"[..]));
+///     assert_eq!(byte_parser.parse(&b"123TAG"[..]), Ok((b"123".to_vec(), &b"TAG"[..])));
+///     assert!(byte_parser.parse(&b"123TATAG"[..]).is_err());
+///
+///     // `attempt` must be used if the `end` should be consume input before failing
+///     let mut byte_parser = take_until(attempt(byte::bytes(&b"TAG"[..])));
+///     assert_eq!(byte_parser.parse(&b"123TATAG"[..]), Ok((b"123TA".to_vec(), &b"TAG"[..])));
 /// }
 /// ```
-pub fn chainr1<Input, P, Op>(parser: P, op: Op) -> Chainr1<P, Op>
+pub fn take_until<F, Input, P>(end: P) -> TakeUntil<F, P>
 where
     Input: Stream,
+    F: Extend<<Input as StreamOnce>::Token> + Default,
     P: Parser<Input>,
-    Op: Parser<Input>,
-    Op::Output: FnOnce(P::Output, P::Output) -> P::Output,
 {
-    Chainr1(parser, op)
+    TakeUntil {
+        end,
+        _marker: PhantomData,
+    }
 }
 
 #[derive(Copy, Clone)]
-pub struct TakeUntil<F, P> {
+pub struct TakeUntilMax<F, P> {
     end: P,
+    limit: usize,
     _marker: PhantomData<fn() -> F>,
 }
-impl<F, Input, P> Parser<Input> for TakeUntil<F, P>
+impl<F, Input, P> Parser<Input> for TakeUntilMax<F, P>
 where
     Input: Stream,
     F: Extend<<Input as StreamOnce>::Token> + Default,
     P: Parser<Input>,
 {
     type Output = F;
-    type PartialState = (F, P::PartialState);
+    type PartialState = (F, usize, P::PartialState);
 
     parse_mode!(Input);
     #[inline]
@@ -1156,7 +2735,7 @@ where
     where
         M: ParseMode,
     {
-        let (ref mut output, ref mut end_state) = *state;
+        let (ref mut output, ref mut count, ref mut end_state) = *state;
 
         let mut committed = Commit::Peek(());
         loop {
@@ -1164,56 +2743,70 @@ where
             match self.end.parse_mode(mode, input, end_state).into() {
                 Ok((_, rest)) => {
                     ctry!(input.reset(before).committed());
+                    *count = 0;
                     return match committed.merge(rest) {
-                        Commit::Commit(()) => CommitOk(mem::replace(output, F::default())),
-                        Commit::Peek(()) => PeekOk(mem::replace(output, F::default())),
+                        Commit::Commit(()) => CommitOk(mem::take(output)),
+                        Commit::Peek(()) => PeekOk(mem::take(output)),
                     };
                 }
                 Err(Commit::Peek(_)) => {
                     ctry!(input.reset(before).committed());
+                    if *count >= self.limit {
+                        let err = Input::Error::from_error(
+                            input.position(),
+                            StreamError::message_format(format_args!(
+                                "end was not found within {} items",
+                                self.limit
+                            )),
+                        );
+                        let taken = mem::replace(count, 0);
+                        *output = F::default();
+                        return if taken == 0 {
+                            PeekErr(err.into())
+                        } else {
+                            CommitErr(err)
+                        };
+                    }
                     output.extend(Some(ctry!(uncons(input)).0));
+                    *count += 1;
                     committed = Commit::Commit(());
                 }
                 Err(Commit::Commit(e)) => {
                     ctry!(input.reset(before).committed());
-                    return CommitErr(e.error);
+                    return CommitErr(e.into_error());
                 }
             };
         }
     }
 }
 
-/// Takes input until `end` is encountered or `end` indicates that it has committed input before
-/// failing (`attempt` can be used to make it look like it has not committed any input)
+/// Takes input until `end` is encountered, like [`take_until`][], but fails once `limit` items
+/// have been taken without `end` being found.
+///
+/// Useful for bounding the memory and time spent searching for a delimiter in untrusted input.
+///
+/// [`take_until`]: fn.take_until.html
 ///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
 /// # use combine::parser::char;
-/// # use combine::parser::byte;
-/// # use combine::parser::combinator::attempt;
-/// # use combine::parser::repeat::take_until;
+/// # use combine::parser::repeat::take_until_max;
 /// # fn main() {
-///     let mut char_parser = take_until(char::digit());
-///     assert_eq!(char_parser.parse("abc123"), Ok(("abc".to_string(), "123")));
-///
-///     let mut byte_parser = take_until(byte::bytes(&b"TAG"[..]));
-///     assert_eq!(byte_parser.parse(&b"123TAG"[..]), Ok((b"123".to_vec(), &b"TAG"[..])));
-///     assert!(byte_parser.parse(&b"123TATAG"[..]).is_err());
-///
-///     // `attempt` must be used if the `end` should be consume input before failing
-///     let mut byte_parser = take_until(attempt(byte::bytes(&b"TAG"[..])));
-///     assert_eq!(byte_parser.parse(&b"123TATAG"[..]), Ok((b"123TA".to_vec(), &b"TAG"[..])));
+///     let mut parser = take_until_max(3, char::digit());
+///     assert_eq!(parser.parse("abc123"), Ok(("abc".to_string(), "123")));
+///     assert!(parser.parse("abcd123").is_err());
 /// }
 /// ```
-pub fn take_until<F, Input, P>(end: P) -> TakeUntil<F, P>
+pub fn take_until_max<F, Input, P>(limit: usize, end: P) -> TakeUntilMax<F, P>
 where
     Input: Stream,
     F: Extend<<Input as StreamOnce>::Token> + Default,
     P: Parser<Input>,
 {
-    TakeUntil {
+    TakeUntilMax {
         end,
+        limit,
         _marker: PhantomData,
     }
 }
@@ -1253,6 +2846,34 @@ parser! {
     }
 }
 
+parser! {
+    pub struct SkipUntilMax;
+    type PartialState = <With<TakeUntilMax<Sink, P>, Value<Input, ()>> as Parser<Input>>::PartialState;
+    /// Skips input until `end` is encountered, like [`skip_until`][], but fails once `limit` items
+    /// have been skipped without `end` being found.
+    ///
+    /// [`skip_until`]: fn.skip_until.html
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::byte;
+    /// # use combine::parser::repeat::skip_until_max;
+    /// # fn main() {
+    ///     let mut parser = skip_until_max(4, byte::bytes(&b"\r\n"[..]));
+    ///     assert_eq!(parser.parse(&b"1234\r\n"[..]), Ok(((), &b"\r\n"[..])));
+    ///     assert!(parser.parse(&b"12345\r\n"[..]).is_err());
+    /// }
+    /// ```
+    pub fn skip_until_max[Input, P](limit: usize, end: P)(Input) -> ()
+    where [
+        P: Parser<Input>,
+    ]
+    {
+        take_until_max::<Sink, _, _>(*limit, end).with(value(()))
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct RepeatUntil<F, P, E> {
     parser: P,
@@ -1306,12 +2927,16 @@ where
                     }
                     Err(Commit::Commit(e)) => {
                         ctry!(input.reset(before).committed());
-                        return CommitErr(e.error);
+                        return CommitErr(e.into_error());
                     }
                 }
             }
         }
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        reflect_repeat(visitor, 0, None, &mut self.parser)
+    }
 }
 
 pub fn repeat_until<F, Input, P, E>(parser: P, end: E) -> RepeatUntil<F, P, E>
@@ -1405,7 +3030,7 @@ where
                                 }
                                 CommitErr(err) => return CommitErr(err),
                                 PeekErr(err) => {
-                                    return CommitErr(err.error);
+                                    return CommitErr(err.into_error());
                                 }
                             }
                         }
@@ -1478,6 +3103,145 @@ where
     }
 }
 
+pub struct EscapedTransform<F, P, Q, I> {
+    parser: P,
+    escape: I,
+    transform: Q,
+    _marker: PhantomData<fn() -> F>,
+}
+impl<Input, F, P, Q> Parser<Input> for EscapedTransform<F, P, Q, Input::Token>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    <Input as StreamOnce>::Token: PartialEq,
+    Q: Parser<Input>,
+    F: Default + Extend<P::Output> + Extend<Q::Output>,
+{
+    type Output = F;
+    // The collected elements, whether `escape` was parsed and the states of `parser` and
+    // `transform`
+    type PartialState = (F, bool, P::PartialState, Q::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut elements, ref mut escaped, ref mut parser_state, ref mut transform_state) =
+            *state;
+        let mut committed = Commit::Peek(());
+        loop {
+            if *escaped {
+                match self
+                    .transform
+                    .parse_committed_mode(mode, input, transform_state)
+                {
+                    PeekOk(x) | CommitOk(x) => {
+                        *escaped = false;
+                        *transform_state = Default::default();
+                        elements.extend(Some(x));
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                    PeekErr(err) => return CommitErr(err.into_error()),
+                }
+                mode.set_first();
+            }
+
+            match self.parser.parse_mode(mode, input, parser_state) {
+                PeekOk(x) => elements.extend(Some(x)),
+                CommitOk(x) => {
+                    committed = Commit::Commit(());
+                    elements.extend(Some(x));
+                }
+                PeekErr(_) => {
+                    *parser_state = Default::default();
+                    let checkpoint = input.checkpoint();
+                    match uncons(input) {
+                        CommitOk(ref c) | PeekOk(ref c) if *c == self.escape => {
+                            committed = Commit::Commit(());
+                            *escaped = true;
+                        }
+                        CommitErr(err) => {
+                            return CommitErr(err);
+                        }
+                        _ => {
+                            ctry!(input.reset(checkpoint).committed());
+                            let elements = mem::take(elements);
+                            return if committed.is_peek() {
+                                PeekOk(elements)
+                            } else {
+                                CommitOk(elements)
+                            };
+                        }
+                    }
+                }
+                CommitErr(err) => return CommitErr(err),
+            }
+            mode.set_first();
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        use crate::error;
+
+        self.parser.add_error(errors);
+
+        errors.error.add_expected(error::Token(self.escape.clone()));
+    }
+}
+
+/// Parses an escaped string like [`escaped`][] but collects the output of `parser` and the
+/// output of `transform`, which parses the token(s) after `escape`, into `F`.
+///
+/// Unlike `escaped` this can be resumed when used with partial parsing.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::repeat::escaped_transform;
+/// # use combine::parser::range::take_while1;
+/// # fn main() {
+///     let mut parser = escaped_transform::<String, _, _, _>(
+///         take_while1(|c| c != '"' && c != '\\'),
+///         '\\',
+///         choice((
+///             token('n').map(|_| '\n'),
+///             token('"'),
+///             token('\\'),
+///         )),
+///     );
+///     assert_eq!(parser.parse(r#"ab\"12\nc""#), Ok(("ab\"12\nc".to_string(), r#"""#)));
+///     assert!(parser.parse(r#"\a"#).is_err());
+/// }
+/// ```
+///
+/// [`escaped`]: fn.escaped.html
+pub fn escaped_transform<F, Input, P, Q>(
+    parser: P,
+    escape: <Input as StreamOnce>::Token,
+    transform: Q,
+) -> EscapedTransform<F, P, Q, Input::Token>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    <Input as StreamOnce>::Token: PartialEq,
+    Q: Parser<Input>,
+    F: Default + Extend<P::Output> + Extend<Q::Output>,
+{
+    EscapedTransform {
+        parser,
+        escape,
+        transform,
+        _marker: PhantomData,
+    }
+}
+
 pub struct Iterate<F, I, P> {
     parser: P,
     iterable: I,
@@ -1540,7 +3304,7 @@ where
                     Err((
                         item,
                         if *committed {
-                            CommitErr(err.error)
+                            CommitErr(err.into_error())
                         } else {
                             PeekErr(err)
                         },
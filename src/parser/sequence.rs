@@ -6,9 +6,10 @@ use crate::{
         ParseResult::{self, *},
         StreamError, Tracked,
     },
-    lib::marker::PhantomData,
+    lib::{fmt, marker::PhantomData},
     parser::{
         combinator::{ignore, Ignore, Map},
+        reflect::{visit, Node, Visitor},
         ParseMode,
     },
     ErrorOffset, Parser, Stream, StreamOnce,
@@ -119,7 +120,7 @@ macro_rules! tuple_parser {
                         );
                         true
                     }; $h $(, $id)*);
-                    CommitErr(err.error)
+                    CommitErr(err.into_error())
                 } else {
                     PeekErr(err)
                 }
@@ -274,6 +275,14 @@ macro_rules! tuple_parser {
                 let (ref mut $h, $(ref mut $id),*) = *self;
                 last_ident!($h $(, $id)*).add_committed_expected_error(errors)
             }
+
+            fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+                let (ref mut $h, $(ref mut $id),*) = *self;
+                visit(visitor, Node::Sequence, |visitor| {
+                    $h.reflect(visitor);
+                    $($id.reflect(visitor);)*
+                })
+            }
         }
     }
 }
@@ -305,6 +314,20 @@ macro_rules! seq_parser_expr {
     (; $($tt: tt)*) => {
         ( $($tt)* )
     };
+    ( ($first_field: ident ? : $first_parser: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
+        $crate::seq_parser_expr!(
+            ( $($remaining)+ ) ; $($tt)* $crate::parser::choice::optional($first_parser),
+        )
+    };
+    ( ($first_field: ident ? : $first_parser: expr $(,)? ); $($tt: tt)*) => {
+        $crate::seq_parser_expr!(; $($tt)* $crate::parser::choice::optional($first_parser),)
+    };
+    ( ($first_field: ident = $value: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
+        $crate::seq_parser_expr!( ( $($remaining)+ ) ; $($tt)* )
+    };
+    ( ($first_field: ident = $value: expr $(,)? ); $($tt: tt)*) => {
+        $crate::seq_parser_expr!(; $($tt)* )
+    };
     ( (_ : $first_parser: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
         $crate::seq_parser_expr!( ( $($remaining)+ ) ; $($tt)* $first_parser, )
     };
@@ -331,6 +354,18 @@ macro_rules! seq_parser_pattern {
     (; $($tt: tt)*) => {
        ( $($tt)* )
     };
+    ( ($first_field: ident ? : $first_parser: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
+        $crate::seq_parser_pattern!( ( $($remaining)+ ) ; $($tt)* $first_field, )
+    };
+    ( ($first_field: ident ? : $first_parser: expr $(,)? ); $($tt: tt)*) => {
+        $crate::seq_parser_pattern!(; $($tt)* $first_field,)
+    };
+    ( ($first_field: ident = $value: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
+        $crate::seq_parser_pattern!( ( $($remaining)+ ) ; $($tt)* )
+    };
+    ( ($first_field: ident = $value: expr $(,)? ); $($tt: tt)*) => {
+        $crate::seq_parser_pattern!(; $($tt)* )
+    };
     ( (_ : $first_parser: expr, $($remaining: tt)+ ); $($tt: tt)*) => {
         $crate::seq_parser_pattern!( ( $($remaining)+ ) ; $($tt)* _, )
     };
@@ -357,6 +392,20 @@ macro_rules! seq_parser_impl {
     (; $name: ident $($tt: tt)*) => {
         $name { $($tt)* }
     };
+    ( ($first_field: ident ? : $first_parser: expr, $($remaining: tt)+ );
+        $name: ident $($tt: tt)*) =>
+    {
+        $crate::seq_parser_impl!( ( $($remaining)+ ) ; $name $($tt)* $first_field: $first_field, )
+    };
+    ( ($first_field: ident ? : $first_parser: expr $(,)? ); $name: ident $($tt: tt)*) => {
+        $crate::seq_parser_impl!(; $name $($tt)* $first_field: $first_field,)
+    };
+    ( ($first_field: ident = $value: expr, $($remaining: tt)+ ); $name: ident $($tt: tt)*) => {
+        $crate::seq_parser_impl!( ( $($remaining)+ ) ; $name $($tt)* $first_field: $first_field, )
+    };
+    ( ($first_field: ident = $value: expr $(,)? ); $name: ident $($tt: tt)*) => {
+        $crate::seq_parser_impl!(; $name $($tt)* $first_field: $first_field,)
+    };
     ( (_ : $first_parser: expr, $($remaining: tt)+ ); $name: ident $($tt: tt)*) => {
         $crate::seq_parser_impl!( ( $($remaining)+ ) ; $name $($tt)* )
     };
@@ -379,6 +428,26 @@ macro_rules! seq_parser_impl {
     };
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! seq_parser_bindings {
+    () => {};
+    ( _ : $first_parser: expr $(, $($remaining: tt)*)? ) => {
+        $crate::seq_parser_bindings!( $($($remaining)*)? );
+    };
+    ( $first_field: ident : $first_parser: expr $(, $($remaining: tt)*)? ) => {
+        $crate::seq_parser_bindings!( $($($remaining)*)? );
+    };
+    ( $first_field: ident ? : $first_parser: expr $(, $($remaining: tt)*)? ) => {
+        let $first_field = $first_field.unwrap_or_default();
+        $crate::seq_parser_bindings!( $($($remaining)*)? );
+    };
+    ( $first_field: ident = $value: expr $(, $($remaining: tt)*)? ) => {
+        let $first_field = $value;
+        $crate::seq_parser_bindings!( $($($remaining)*)? );
+    };
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! seq_tuple_extract {
@@ -429,6 +498,17 @@ macro_rules! seq_tuple_parser_impl {
 
 /// Sequences multiple parsers and builds a struct out of them.
 ///
+/// Each field of a struct with named fields is given as one of
+///
+/// * `field: parser`, the output of `parser` is stored in `field`
+/// * `_: parser`, `parser` is parsed but its output is not stored
+/// * `field?: parser`, `parser` is parsed with [`optional`][] and `Default::default()` is stored
+///   if it did not parse anything
+/// * `field = expression`, nothing is parsed and `field` is set to `expression`, which may refer
+///   to the fields before it
+///
+/// [`optional`]: parser/choice/fn.optional.html
+///
 /// ```
 /// use combine::{Parser, between, from_str, many, struct_parser, token};
 /// use combine::parser::range::take_while1;
@@ -469,13 +549,64 @@ macro_rules! seq_tuple_parser_impl {
 ///     );
 /// }
 /// ```
+///
+/// Optional and computed fields
+///
+/// ```
+/// use combine::{Parser, many1, struct_parser, token};
+/// use combine::parser::char::{digit, letter, spaces};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Entry {
+///     name: String,
+///     len: usize,
+///     value: u32,
+///     comment: String,
+/// }
+/// fn main() {
+///     let mut parser = struct_parser!{
+///         Entry {
+///             name: many1::<String, _, _>(letter()),
+///             len = name.len(),
+///             _: spaces(),
+///             value?: digit().map(|c| c.to_digit(10).unwrap()),
+///             comment?: token('#').with(many1(letter())),
+///         }
+///     };
+///     assert_eq!(
+///         parser.parse("key 1#note"),
+///         Ok((
+///             Entry {
+///                 name: "key".to_string(),
+///                 len: 3,
+///                 value: 1,
+///                 comment: "note".to_string(),
+///             },
+///             ""
+///         )),
+///     );
+///     assert_eq!(
+///         parser.parse("key"),
+///         Ok((
+///             Entry {
+///                 name: "key".to_string(),
+///                 len: 3,
+///                 value: 0,
+///                 comment: String::new(),
+///             },
+///             ""
+///         )),
+///     );
+/// }
+/// ```
 #[macro_export]
 macro_rules! struct_parser {
     ($name: ident { $($tt: tt)* }) => {
         $crate::seq_parser_expr!( ( $($tt)* ); )
-            .map(|$crate::seq_parser_pattern!( ( $($tt)* ); )|
+            .map(|$crate::seq_parser_pattern!( ( $($tt)* ); )| {
+                $crate::seq_parser_bindings!( $($tt)* );
                 $crate::seq_parser_impl!(( $($tt)* ); $name )
-            )
+            })
     };
 
     ($name: ident ( $($arg: tt)* )) => {
@@ -523,7 +654,7 @@ where
         self.0.parse_mode(mode, input, state).map(|(_, b)| b)
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 /// Equivalent to [`p1.with(p2)`].
@@ -563,7 +694,7 @@ where
         self.0.parse_mode(mode, input, state).map(|(a, _)| a)
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count reflect, 0);
 }
 
 pub fn skip<Input, P1, P2>(p1: P1, p2: P2) -> Skip<P1, P2>
@@ -608,6 +739,142 @@ where [
 }
 }
 
+type Opening<Input, L, P> = (crate::parser::token::Position<Input>, L, P);
+
+pub struct Delimited<Input, L, R, P>
+where
+    Input: Stream,
+{
+    opening: Opening<Input, L, P>,
+    close: R,
+}
+impl<Input, L, R, P> Parser<Input> for Delimited<Input, L, R, P>
+where
+    Input: Stream,
+    L: Parser<Input>,
+    R: Parser<Input>,
+    P: Parser<Input>,
+    L::Output: fmt::Display,
+    Input::Position: fmt::Display,
+{
+    type Output = P::Output;
+    type PartialState = (
+        <Opening<Input, L, P> as Parser<Input>>::PartialState,
+        Option<(bool, Input::Position, L::Output, P::Output)>,
+        R::PartialState,
+    );
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut opening_state, ref mut opened, ref mut close_state) = *state;
+
+        if mode.is_first() || opened.is_none() {
+            debug_assert!(opened.is_none());
+
+            let ((position, delimiter, value), committed) =
+                match self.opening.parse_mode(mode, input, opening_state) {
+                    PeekOk(value) => (value, false),
+                    CommitOk(value) => (value, true),
+
+                    PeekErr(err) => return PeekErr(err),
+                    CommitErr(err) => return CommitErr(err),
+                };
+
+            *opened = Some((committed, position, delimiter, value));
+            mode.set_first();
+        }
+
+        let mut err = match self.close.parse_committed_mode(mode, input, close_state) {
+            PeekOk(_) => {
+                let (committed, _, _, value) = opened.take().unwrap();
+                return if committed {
+                    CommitOk(value)
+                } else {
+                    PeekOk(value)
+                };
+            }
+            CommitOk(_) => return CommitOk(opened.take().unwrap().3),
+            PeekErr(err) if !opened.as_ref().unwrap().0 => {
+                *opened = None;
+                return PeekErr(err);
+            }
+            PeekErr(err) => err.into_error(),
+            CommitErr(err) => err,
+        };
+        if input.is_partial() && err.is_unexpected_end_of_input() {
+            return CommitErr(err);
+        }
+        let (_, position, delimiter, _) = opened.take().unwrap();
+        err.add(StreamError::message_format(format_args!(
+            "unclosed `{}` opened at {}",
+            delimiter, position
+        )));
+        CommitErr(err)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.opening.add_error(errors)
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let (_, ref mut open, ref mut parser) = self.opening;
+        let close = &mut self.close;
+        visit(visitor, Node::Sequence, |visitor| {
+            open.reflect(visitor);
+            parser.reflect(visitor);
+            close.reflect(visitor);
+        })
+    }
+}
+
+/// Parses `open` followed by `parser` followed by `close`, like [`between`][], but if `close`
+/// fails after `open` succeeded the error also reports which delimiter was left unclosed and where
+/// it was opened (instead of only expecting `close` at the end of the input).
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, letter};
+/// # use combine::parser::sequence::delimited;
+/// # use combine::stream::position;
+/// # fn main() {
+/// let mut parser = delimited(char('('), char(')'), many::<String, _, _>(letter()));
+/// let result = parser.easy_parse(position::Stream::new("(abc)")).map(|x| x.0);
+/// assert_eq!(result, Ok(String::from("abc")));
+///
+/// let err = parser.easy_parse(position::Stream::new("(abc")).unwrap_err();
+/// assert!(err
+///     .to_string()
+///     .contains("unclosed `(` opened at line: 1, column: 1"));
+/// # }
+/// ```
+///
+/// [`between`]: fn.between.html
+pub fn delimited<Input, L, R, P>(open: L, close: R, parser: P) -> Delimited<Input, L, R, P>
+where
+    Input: Stream,
+    L: Parser<Input>,
+    R: Parser<Input>,
+    P: Parser<Input>,
+    L::Output: fmt::Display,
+    Input::Position: fmt::Display,
+{
+    Delimited {
+        opening: (crate::parser::token::position(), open, parser),
+        close,
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Then<P, F>(P, F);
 impl<Input, P, N, F> Parser<Input> for Then<P, F>
@@ -671,7 +938,7 @@ where
                 let (committed, _) = *n_parser_cache.as_ref().unwrap();
                 *n_parser_cache = None;
                 if committed {
-                    CommitErr(x.error)
+                    CommitErr(x.into_error())
                 } else {
                     PeekErr(x)
                 }
@@ -757,7 +1024,7 @@ where
             PeekErr(x) => {
                 let (committed, _) = n_parser_cache.take().unwrap();
                 if committed {
-                    CommitErr(x.error)
+                    CommitErr(x.into_error())
                 } else {
                     PeekErr(x)
                 }
@@ -784,6 +1051,92 @@ where
     ThenPartial(p, f)
 }
 
+#[derive(Copy, Clone)]
+pub struct ThenPartialRef<P, F>(P, F);
+impl<Input, P, N, F> Parser<Input> for ThenPartialRef<P, F>
+where
+    Input: Stream,
+    F: FnMut(&mut P::Output) -> N,
+    P: Parser<Input>,
+    N: Parser<Input>,
+{
+    type Output = (P::Output, N::Output);
+    type PartialState = (P::PartialState, Option<(bool, P::Output)>, N::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut p_state, ref mut n_parser_cache, ref mut n_state) = *state;
+
+        if mode.is_first() || n_parser_cache.is_none() {
+            debug_assert!(n_parser_cache.is_none());
+
+            match self.0.parse_mode(mode, input, p_state) {
+                PeekOk(value) => {
+                    *n_parser_cache = Some((false, value));
+                }
+                CommitOk(value) => {
+                    *n_parser_cache = Some((true, value));
+                }
+                PeekErr(err) => return PeekErr(err),
+                CommitErr(err) => return CommitErr(err),
+            }
+            mode.set_first();
+        }
+
+        let result = (self.1)(&mut n_parser_cache.as_mut().unwrap().1)
+            .parse_committed_mode(mode, input, n_state);
+        match result {
+            PeekOk(x) => {
+                let (committed, value) = n_parser_cache.take().unwrap();
+                if committed {
+                    CommitOk((value, x))
+                } else {
+                    PeekOk((value, x))
+                }
+            }
+            CommitOk(x) => {
+                let (_, value) = n_parser_cache.take().unwrap();
+                CommitOk((value, x))
+            }
+            PeekErr(x) => {
+                let (committed, _) = n_parser_cache.take().unwrap();
+                if committed {
+                    CommitErr(x.into_error())
+                } else {
+                    PeekErr(x)
+                }
+            }
+            CommitErr(x) => CommitErr(x),
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors);
+    }
+}
+
+/// Equivalent to [`p.then_partial_ref(f)`].
+///
+/// [`p.then_partial_ref(f)`]: ../trait.Parser.html#method.then_partial_ref
+pub fn then_partial_ref<Input, P, F, N>(p: P, f: F) -> ThenPartialRef<P, F>
+where
+    Input: Stream,
+    F: FnMut(&mut P::Output) -> N,
+    P: Parser<Input>,
+    N: Parser<Input>,
+{
+    ThenPartialRef(p, f)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -864,7 +1217,7 @@ where
                 let (committed, _, _) = n_parser_cache.take().unwrap();
                 *n_parser_cache = None;
                 if committed {
-                    CommitErr(x.error)
+                    CommitErr(x.into_error())
                 } else {
                     PeekErr(x)
                 }
@@ -890,3 +1243,93 @@ where
 {
     ThenRef(p, f)
 }
+
+#[derive(Copy, Clone)]
+pub struct ThenInput<P, F>(P, F);
+impl<Input, P, N, F> Parser<Input> for ThenInput<P, F>
+where
+    Input: Stream,
+    F: FnMut(P::Output, &mut Input) -> N,
+    P: Parser<Input>,
+    N: Parser<Input>,
+{
+    type Output = N::Output;
+    type PartialState = (P::PartialState, Option<(bool, N)>, N::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut p_state, ref mut n_parser_cache, ref mut n_state) = *state;
+
+        if mode.is_first() || n_parser_cache.is_none() {
+            debug_assert!(n_parser_cache.is_none());
+
+            let (value, committed) = match self.0.parse_mode(mode, input, p_state) {
+                PeekOk(value) => (value, false),
+                CommitOk(value) => (value, true),
+
+                PeekErr(err) => return PeekErr(err),
+                CommitErr(err) => return CommitErr(err),
+            };
+
+            *n_parser_cache = Some((committed, (self.1)(value, input)));
+            mode.set_first();
+        }
+
+        let result = n_parser_cache
+            .as_mut()
+            .unwrap()
+            .1
+            .parse_committed_mode(mode, input, n_state);
+        match result {
+            PeekOk(x) => {
+                let (committed, _) = *n_parser_cache.as_ref().unwrap();
+                *n_parser_cache = None;
+                if committed {
+                    CommitOk(x)
+                } else {
+                    PeekOk(x)
+                }
+            }
+            CommitOk(x) => {
+                *n_parser_cache = None;
+                CommitOk(x)
+            }
+            PeekErr(x) => {
+                let (committed, _) = *n_parser_cache.as_ref().unwrap();
+                *n_parser_cache = None;
+                if committed {
+                    CommitErr(x.into_error())
+                } else {
+                    PeekErr(x)
+                }
+            }
+            CommitErr(x) => CommitErr(x),
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors);
+    }
+}
+
+/// Equivalent to [`p.then_input(f)`].
+///
+/// [`p.then_input(f)`]: ../trait.Parser.html#method.then_input
+pub fn then_input<Input, P, F, N>(p: P, f: F) -> ThenInput<P, F>
+where
+    Input: Stream,
+    F: FnMut(P::Output, &mut Input) -> N,
+    P: Parser<Input>,
+    N: Parser<Input>,
+{
+    ThenInput(p, f)
+}
@@ -2,12 +2,16 @@
 
 use crate::{
     error::{
-        self, ErrorInfo, ParseError,
+        self, ErrorInfo, Info, ParseError,
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
     lib::marker::PhantomData,
-    stream::{uncons, Stream, StreamOnce},
+    parser::reflect::{visit, Node, Visitor},
+    stream::{
+        position::{Column, Line},
+        uncons, uncons_tokens_while, Stream, StreamOnce,
+    },
     Parser,
 };
 
@@ -88,6 +92,22 @@ where
             }
         })
     }
+
+    fn parse_many_tokens(
+        &mut self,
+        input: &mut Input,
+        f: &mut dyn FnMut(Input::Token),
+    ) -> Option<ParseResult<(), Input::Error>> {
+        let predicate = &mut self.predicate;
+        uncons_tokens_while(input, &mut |c| {
+            if predicate(c.clone()) {
+                f(c);
+                true
+            } else {
+                false
+            }
+        })
+    }
 }
 
 /// Parses a token and succeeds depending on the result of `predicate`.
@@ -188,9 +208,30 @@ where
     fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Input::Token, Input::Error> {
         satisfy_impl(input, |c| if c == self.c { Some(c) } else { None })
     }
+
+    fn parse_many_tokens(
+        &mut self,
+        input: &mut Input,
+        f: &mut dyn FnMut(Input::Token),
+    ) -> Option<ParseResult<(), Input::Error>> {
+        let expected = &self.c;
+        uncons_tokens_while(input, &mut |c| {
+            if c == *expected {
+                f(c);
+                true
+            } else {
+                false
+            }
+        })
+    }
+
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
         errors.error.add_expected(error::Token(self.c.clone()));
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visitor.token(&self.c)
+    }
 }
 
 /// Parses a character and succeeds if the character is equal to `c`.
@@ -216,6 +257,75 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct TokenBy<C, Input>
+where
+    Input: Stream,
+{
+    c: Input::Token,
+    cmp: C,
+    _marker: PhantomData<Input>,
+}
+
+impl<Input, C> Parser<Input> for TokenBy<C, Input>
+where
+    Input: Stream,
+    Input::Token: Clone,
+    C: FnMut(&Input::Token, &Input::Token) -> bool,
+{
+    type Output = Input::Token;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Input::Token, Input::Error> {
+        let TokenBy {
+            ref c, ref mut cmp, ..
+        } = *self;
+        satisfy_impl(
+            input,
+            |other| if cmp(c, &other) { Some(other) } else { None },
+        )
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected(error::Token(self.c.clone()));
+    }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visitor.token(&self.c)
+    }
+}
+
+/// Parses a token and succeeds if `cmp(&c, &token)` returns `true`. Like [`token`][] but for
+/// tokens which should not be compared using `PartialEq`. The matched token from the input is
+/// returned and `c` is reported as the expected token if parsing fails.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::token::token_by;
+/// # fn main() {
+/// // Tokens carrying the offset they were lexed at
+/// let input = &[("let", 0), ("x", 4)][..];
+/// let result = token_by(("let", 10), |l: &(&str, usize), r: &(&str, usize)| l.0 == r.0)
+///     .parse(input)
+///     .map(|x| x.0);
+/// assert_eq!(result, Ok(("let", 0)));
+/// # }
+/// ```
+///
+/// [`token`]: fn.token.html
+pub fn token_by<Input, C>(c: Input::Token, cmp: C) -> TokenBy<C, Input>
+where
+    Input: Stream,
+    C: FnMut(&Input::Token, &Input::Token) -> bool,
+{
+    TokenBy {
+        c,
+        cmp,
+        _marker: PhantomData,
+    }
+}
+
 #[derive(Clone)]
 pub struct Tokens<C, E, T, Input>
 where
@@ -260,7 +370,7 @@ where
                 PeekErr(mut error) => {
                     error.error.set_position(start);
                     return if committed {
-                        CommitErr(error.error)
+                        CommitErr(error.into_error())
                     } else {
                         PeekErr(error.into())
                     };
@@ -280,6 +390,15 @@ where
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
         errors.error.add_expected(&self.expected);
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        match self.expected.into_info() {
+            Info::Token(token) => visitor.token(&token),
+            Info::Range(range) => visitor.range(&range),
+            Info::Static(label) => visit(visitor, Node::Label(&label), |v| v.opaque()),
+            Info::Format(label) => visit(visitor, Node::Label(&label), |v| v.opaque()),
+        }
+    }
 }
 
 /// Parses multiple tokens.
@@ -363,7 +482,7 @@ where
                 PeekErr(mut error) => {
                     error.error.set_position(start);
                     return if committed {
-                        CommitErr(error.error)
+                        CommitErr(error.into_error())
                     } else {
                         PeekErr(error)
                     };
@@ -439,6 +558,10 @@ where
     fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Input::Position, Input::Error> {
         PeekOk(input.position())
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visit(visitor, Node::Sequence, |_| ())
+    }
 }
 
 /// Parser which just returns the current position in the stream.
@@ -465,6 +588,159 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+enum PositionPredicate {
+    Line(i32),
+    Column(i32),
+    LineStart(bool),
+}
+
+#[derive(Copy, Clone)]
+pub struct PositionIs<Input> {
+    predicate: PositionPredicate,
+    _marker: PhantomData<Input>,
+}
+
+impl<Input> Parser<Input> for PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    type Output = ();
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<(), Input::Error> {
+        let position = input.position();
+        let error = match self.predicate {
+            PositionPredicate::Line(line) if position.line() != line => {
+                StreamError::message_format(format_args!("expected a token on line {}", line))
+            }
+            PositionPredicate::Column(column) if position.column() != column => {
+                StreamError::message_format(format_args!("expected a token at column {}", column))
+            }
+            PositionPredicate::LineStart(true) if position.column() != 1 => {
+                StreamError::message_static_message("expected a token at the start of a line")
+            }
+            PositionPredicate::LineStart(false) if position.column() == 1 => {
+                StreamError::message_static_message("unexpected token at the start of a line")
+            }
+            _ => return PeekOk(()),
+        };
+        PeekErr(Input::Error::from_error(position, error).into())
+    }
+}
+
+fn position_is<Input>(predicate: PositionPredicate) -> PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    PositionIs {
+        predicate,
+        _marker: PhantomData,
+    }
+}
+
+/// Succeeds without consuming input if the next token is on the same line as `position`, a
+/// position returned by an earlier [`position`] parser.
+///
+/// [`position`]: fn.position.html
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, spaces};
+/// # use combine::parser::token::same_line;
+/// # use combine::stream::position;
+/// # fn main() {
+/// // Arguments of a command must be on the same line as the command itself
+/// let mut parser = position().and(letter()).skip(spaces()).then(|(start, command)| {
+///     many::<String, _, _>(same_line(start).with(letter()).skip(spaces()))
+///         .map(move |args| (command, args))
+/// });
+/// let result = parser.parse(position::Stream::new("a bc\nd"));
+/// assert_eq!(result.map(|x| x.0), Ok(('a', "bc".to_string())));
+/// # }
+/// ```
+pub fn same_line<Input>(position: Input::Position) -> PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    position_is(PositionPredicate::Line(position.line()))
+}
+
+/// Succeeds without consuming input if the next token is at `column`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, spaces};
+/// # use combine::parser::token::at_column;
+/// # use combine::stream::position;
+/// # fn main() {
+/// let mut parser = spaces().with(at_column(3)).with(letter());
+/// assert_eq!(parser.parse(position::Stream::new("  a")).map(|x| x.0), Ok('a'));
+/// assert!(parser.parse(position::Stream::new(" a")).is_err());
+/// # }
+/// ```
+pub fn at_column<Input>(column: i32) -> PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    position_is(PositionPredicate::Column(column))
+}
+
+/// Succeeds without consuming input if the next token is at the start of a line.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, spaces};
+/// # use combine::parser::token::at_line_start;
+/// # use combine::stream::position;
+/// # fn main() {
+/// // Each statement must start on a new line
+/// let mut parser = many1::<Vec<_>, _, _>(at_line_start().with(letter()).skip(spaces()));
+/// let result = parser.parse(position::Stream::new("a\nb c"));
+/// assert_eq!(result.map(|x| x.0), Ok(vec!['a', 'b']));
+/// # }
+/// ```
+pub fn at_line_start<Input>() -> PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    position_is(PositionPredicate::LineStart(true))
+}
+
+/// Succeeds without consuming input if the next token is not at the start of a line.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, spaces};
+/// # use combine::parser::token::not_at_line_start;
+/// # use combine::stream::position;
+/// # fn main() {
+/// // Continuation lines are indented
+/// let mut parser = letter().skip(spaces()).and(many::<String, _, _>(
+///     not_at_line_start().with(letter()).skip(spaces()),
+/// ));
+/// let result = parser.parse(position::Stream::new("a\n bc\nd"));
+/// assert_eq!(result.map(|x| x.0), Ok(('a', "bc".to_string())));
+/// # }
+/// ```
+pub fn not_at_line_start<Input>() -> PositionIs<Input>
+where
+    Input: Stream,
+    Input::Position: Line + Column,
+{
+    position_is(PositionPredicate::LineStart(false))
+}
+
 #[derive(Copy, Clone)]
 pub struct OneOf<T, Input>
 where
@@ -493,6 +769,15 @@ where
             errors.error.add_expected(error::Token(expected));
         }
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        let tokens = self.tokens.clone();
+        visit(visitor, Node::Choice, |visitor| {
+            for token in tokens {
+                visitor.token(&token);
+            }
+        })
+    }
 }
 
 /// Extract one token and succeeds if it is part of `tokens`.
@@ -589,6 +874,10 @@ where
     fn parse_lazy(&mut self, _: &mut Input) -> ParseResult<T, Input::Error> {
         PeekOk(self.0.clone())
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visit(visitor, Node::Sequence, |_| ())
+    }
 }
 
 /// Always returns the value `v` without consuming any input.
@@ -624,6 +913,10 @@ where
     fn parse_lazy(&mut self, _: &mut Input) -> ParseResult<R, Input::Error> {
         PeekOk((self.0)())
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visit(visitor, Node::Sequence, |_| ())
+    }
 }
 
 /// Always returns the value produced by calling `f`.
@@ -673,6 +966,10 @@ where
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
         errors.error.add_expected("end of input");
     }
+
+    fn reflect(&mut self, visitor: &mut dyn Visitor<Input>) {
+        visit(visitor, Node::Label(&"end of input"), |v| v.opaque())
+    }
 }
 
 /// Succeeds only if the stream is at end of input, fails otherwise.
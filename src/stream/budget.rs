@@ -0,0 +1,210 @@
+//! Stream wrapper which limits the amount of work a parse may do.
+//!
+//! Some grammars backtrack exponentially on specially crafted inputs, independent of how long the
+//! input is. Wrapping the input in [`Stream`][] bounds the number of tokens that may be taken from
+//! the stream and the number of times the stream may be reset to an earlier position, so such an
+//! input fails with a "budget exceeded" error instead of running for a very long time.
+//!
+//! [`Stream`]: struct.Stream.html
+
+use crate::{
+    error::{ParseError, ParseResult, StreamError},
+    stream::{Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
+};
+
+const STEPS_EXCEEDED: &str = "step budget exceeded";
+const BACKTRACKS_EXCEEDED: &str = "backtrack budget exceeded";
+
+/// Stream wrapper which fails once more than `max_steps` tokens or ranges have been taken from
+/// `stream`, or once `stream` has been reset more than `max_backtracks` times.
+///
+/// Once a budget is exceeded every further attempt to take input fails, but a parser which may
+/// stop early (such as `many` or `optional`) can still treat such a failure as the end of what it
+/// parses. Use [`is_exhausted`] to find out if a parse failed or stopped because of the budget.
+///
+/// [`is_exhausted`]: #method.is_exhausted
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::letter;
+/// # use combine::stream::budget;
+/// # fn main() {
+/// let mut input = budget::Stream::new("abcdef").max_steps(3);
+/// let result = many::<String, _, _>(letter()).skip(eof()).parse_stream(&mut input);
+/// assert!(result.is_err());
+/// assert!(input.is_exhausted());
+///
+/// let mut input = budget::Stream::new("abc").max_steps(10);
+/// let result = many::<String, _, _>(letter()).skip(eof()).parse_stream(&mut input);
+/// assert_eq!(result.into_result().map(|(s, _)| s), Ok("abc".to_string()));
+/// assert!(!input.is_exhausted());
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Stream<S> {
+    pub stream: S,
+    steps: usize,
+    max_steps: usize,
+    backtracks: usize,
+    max_backtracks: usize,
+}
+
+impl<S> From<S> for Stream<S> {
+    fn from(stream: S) -> Self {
+        Stream::new(stream)
+    }
+}
+
+impl<S> Stream<S> {
+    /// Wraps `stream` without any limits. Use [`max_steps`] and [`max_backtracks`] to set them.
+    ///
+    /// [`max_steps`]: #method.max_steps
+    /// [`max_backtracks`]: #method.max_backtracks
+    pub fn new(stream: S) -> Self {
+        Stream {
+            stream,
+            steps: 0,
+            max_steps: usize::MAX,
+            backtracks: 0,
+            max_backtracks: usize::MAX,
+        }
+    }
+
+    /// Sets the maximum number of times a token or range may be taken from the stream.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Sets the maximum number of times the stream may be reset to an earlier checkpoint.
+    pub fn max_backtracks(mut self, max_backtracks: usize) -> Self {
+        self.max_backtracks = max_backtracks;
+        self
+    }
+
+    /// Returns the number of tokens or ranges taken from the stream so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the number of times the stream has been reset so far.
+    pub fn backtracks(&self) -> usize {
+        self.backtracks
+    }
+
+    /// Returns `true` if either budget has been exceeded.
+    pub fn is_exhausted(&self) -> bool {
+        self.steps > self.max_steps || self.backtracks > self.max_backtracks
+    }
+}
+
+impl<S> Stream<S>
+where
+    S: StreamOnce,
+{
+    fn step(&mut self) -> Result<(), StreamErrorFor<Self>> {
+        self.steps = self.steps.saturating_add(1);
+        if self.steps > self.max_steps {
+            Err(StreamErrorFor::<Self>::message_static_message(
+                STEPS_EXCEEDED,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<S> Positioned for Stream<S>
+where
+    S: Positioned,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.stream.position()
+    }
+}
+
+impl<S> ResetStream for Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    type Checkpoint = S::Checkpoint;
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.stream.checkpoint()
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), Self::Error> {
+        self.backtracks = self.backtracks.saturating_add(1);
+        if self.backtracks > self.max_backtracks {
+            return Err(S::Error::from_error(
+                self.stream.position(),
+                StreamError::message_static_message(BACKTRACKS_EXCEEDED),
+            ));
+        }
+        self.stream.reset(checkpoint)
+    }
+}
+
+impl<S> StreamOnce for Stream<S>
+where
+    S: StreamOnce,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<S::Token, StreamErrorFor<Self>> {
+        self.step()?;
+        self.stream.uncons()
+    }
+
+    fn is_partial(&self) -> bool {
+        self.stream.is_partial()
+    }
+}
+
+impl<S> RangeStreamOnce for Stream<S>
+where
+    S: RangeStreamOnce + Positioned,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        self.step()?;
+        self.stream.uncons_range(size)
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.step()?;
+        self.stream.uncons_while(f)
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        if let Err(err) = self.step() {
+            return ParseResult::CommitErr(err);
+        }
+        self.stream.uncons_while1(f)
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.stream.distance(end)
+    }
+
+    #[inline]
+    fn range(&self) -> Self::Range {
+        self.stream.range()
+    }
+}
@@ -24,6 +24,10 @@ use futures_util_03::ready;
 pin_project! {
     /// `BufReader` used by `Decoder` when it is constructed with [`Decoder::new_bufferless`][]
     ///
+    /// Implements `BufRead` as well as `AsyncBufRead` of `tokio` (with the `tokio-02` feature) and
+    /// of `futures::io` (with the `futures-03` feature, for async-std, smol etc) so any data which
+    /// remains after decoding can be read through the `BufReader`.
+    ///
     /// [`Decoder::new_bufferless`]: ../decoder/struct.Decoder.html#method.new_bufferless
     #[derive(Debug)]
     pub struct BufReader<R> {
@@ -84,7 +88,7 @@ impl<R> BufReader<R> {
 
     /// Invalidates all data in the internal buffer.
     #[inline]
-    #[cfg(feature = "tokio-02")]
+    #[cfg(any(feature = "tokio-02", feature = "futures-03"))]
     fn discard_buffer(self: Pin<&mut Self>) {
         let me = self.project();
         me.buf.clear();
@@ -444,6 +448,75 @@ impl<R: AsyncRead + AsyncWrite> AsyncWrite for BufReader<R> {
     }
 }
 
+#[cfg(feature = "futures-03")]
+impl<R: futures_util_03::io::AsyncRead> futures_util_03::io::AsyncRead for BufReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        if self.buf.is_empty() && buf.len() >= self.buf.capacity() {
+            let res = ready!(self.as_mut().get_pin_mut().poll_read(cx, buf));
+            self.discard_buffer();
+            return Poll::Ready(res);
+        }
+        let mut rem = ready!(futures_util_03::io::AsyncBufRead::poll_fill_buf(
+            self.as_mut(),
+            cx
+        ))?;
+        let nread = rem.read(buf)?;
+        futures_util_03::io::AsyncBufRead::consume(self, nread);
+        Poll::Ready(Ok(nread))
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<R: futures_util_03::io::AsyncRead> futures_util_03::io::AsyncBufRead for BufReader<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let me = self.project();
+
+        // If we've reached the end of our internal buffer then we need to fetch
+        // some more data from the underlying reader.
+        if me.buf.is_empty() {
+            if !me.buf.has_remaining_mut() {
+                me.buf.reserve(8 * 1024);
+            }
+            for b in me.buf.bytes_mut() {
+                *b = MaybeUninit::new(0);
+            }
+            ready!(poll_extend_buf(me.buf, cx, me.inner))?;
+        }
+        Poll::Ready(Ok(&me.buf[..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let me = self.project();
+        me.buf.advance(amt);
+    }
+}
+
+#[cfg(feature = "futures-03")]
+impl<R: futures_util_03::io::AsyncWrite> futures_util_03::io::AsyncWrite for BufReader<R> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_pin_mut().poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_pin_mut().poll_close(cx)
+    }
+}
+
 impl<R: Read> Read for BufReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         // If we don't have any buffered data and we're doing a massive read
@@ -587,3 +660,48 @@ mod tests_sync {
         assert_eq!(read.buffer(), [1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
     }
 }
+
+#[cfg(all(test, feature = "futures-03"))]
+mod tests_futures {
+    use super::BufReader;
+
+    use futures_03_dep::{
+        executor::block_on,
+        io::{AsyncBufReadExt, AsyncReadExt},
+    };
+
+    #[test]
+    fn buf_reader() {
+        block_on(async {
+            let mut read = BufReader::with_capacity(3, &[1u8, 2, 3, 4, 5, 6, 7, 8, 9, 0][..]);
+
+            let mut buf = [0u8; 3];
+            read.read_exact(&mut buf).await.unwrap();
+            assert_eq!(buf, [1, 2, 3]);
+
+            let mut buf = [0u8; 2];
+            read.read_exact(&mut buf).await.unwrap();
+            assert_eq!(buf, [4, 5]);
+
+            let mut buf = Vec::new();
+            read.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, [6, 7, 8, 9, 0]);
+        })
+    }
+
+    #[test]
+    fn buf_reader_lines() {
+        block_on(async {
+            let mut read = BufReader::with_capacity(4, &b"abc\ndefgh\n"[..]);
+
+            let mut line = String::new();
+            read.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "abc\n");
+            assert_eq!(read.buffer(), b"");
+
+            line.clear();
+            read.read_line(&mut line).await.unwrap();
+            assert_eq!(line, "defgh\n");
+        })
+    }
+}
@@ -3,6 +3,9 @@ use crate::{
     stream::buf_reader::{Buffer, Bufferless, CombineBuffer},
 };
 
+#[cfg(feature = "futures-03")]
+use crate::stream::MaybePartialStream;
+
 use std::{
     fmt,
     io::{self, Read},
@@ -122,6 +125,15 @@ impl<S, P, C> Decoder<S, P, C> {
         &self.position
     }
 
+    /// Discards the state of any in-progress parse so that the next parse starts from the
+    /// beginning, for instance to resynchronize after a decoding error.
+    pub fn reset_state(&mut self)
+    where
+        S: Default,
+    {
+        self.state = S::default();
+    }
+
     #[doc(hidden)]
     pub fn __inner(&mut self) -> (&mut S, &mut P, &C, bool) {
         (
@@ -186,3 +198,90 @@ impl<S, P, C> Decoder<S, P, C> {
         Ok(())
     }
 }
+
+/// Parses the frames of a protocol from `read`, returning a stream which yields each frame once it
+/// has been read in its entirety.
+///
+/// `decode` is passed the data read so far along with the state of the frame which is being
+/// parsed and should call [`stream::decode`][] with the parser of a frame. The parser has to be
+/// constructed inside `decode` since the data it parses is only borrowed for the duration of the
+/// call (the same lack of Higher Ranked Types which makes [`decode_futures_03!`][] a macro).
+///
+/// The stream ends once `read` has reached its end without any data left over. Data which remains
+/// after the last frame, or any error returned from `decode` or `read`, is yielded as an error
+/// which ends the stream. The `position` of an `Error::Io` is the number of bytes in the frames
+/// which were parsed before the error.
+///
+/// ```
+/// # use futures_03_dep as futures;
+/// use futures::{executor::block_on, pin_mut, StreamExt};
+///
+/// use combine::{
+///     parser::byte::{digit, newline},
+///     stream::{decode, frames},
+///     many1, Parser,
+/// };
+///
+/// let input: &[u8] = b"1\n23\n456\n";
+/// let frames = frames(
+///     |input, state| decode(many1(digit()).skip(newline()), input, state),
+///     input,
+/// );
+/// pin_mut!(frames);
+///
+/// block_on(async {
+///     let mut numbers = Vec::new();
+///     while let Some(frame) = frames.next().await {
+///         numbers.push(String::from_utf8(frame.unwrap()).unwrap());
+///     }
+///     assert_eq!(numbers, ["1", "23", "456"]);
+/// });
+/// ```
+///
+/// [`stream::decode`]: ../fn.decode.html
+/// [`decode_futures_03!`]: ../../macro.decode_futures_03.html
+#[cfg(feature = "futures-03")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-03")))]
+pub fn frames<R, F, S, O, E>(
+    decode: F,
+    read: R,
+) -> impl futures_util_03::stream::Stream<Item = Result<O, Error<E, usize>>>
+where
+    R: futures_io_03::AsyncRead + Unpin,
+    F: for<'a> FnMut(&mut MaybePartialStream<&'a [u8]>, &mut S) -> Result<(Option<O>, usize), E>,
+    S: Default,
+{
+    let decoder = Decoder::<S, usize>::new();
+    // `None` once the stream has ended, either at the end of `read` or after an error
+    futures_util_03::stream::unfold(Some((decoder, read, decode)), |frames| async move {
+        let (mut decoder, mut read, mut decode) = frames?;
+        // Set once the frame being parsed has consumed data which is no longer in the buffer
+        let mut in_frame = false;
+        loop {
+            let (opt, removed) = {
+                let (state, _, buffer, end_of_input) = decoder.__inner();
+                let buffer = CombineBuffer::<R>::buffer(buffer, &read);
+                if end_of_input && buffer.is_empty() && !in_frame {
+                    return None;
+                }
+                match decode(&mut MaybePartialStream(buffer, !end_of_input), state) {
+                    Ok(x) => x,
+                    Err(err) => return Some((Err(Error::Parse(err)), None)),
+                }
+            };
+
+            decoder.position += removed;
+            decoder.advance_pin(Pin::new(&mut read), removed);
+            in_frame |= removed != 0;
+
+            if let Some(frame) = opt {
+                return Some((Ok(frame), Some((decoder, read, decode))));
+            }
+
+            if let Err(error) = decoder.__before_parse_async(Pin::new(&mut read)).await {
+                let position = decoder.position;
+                return Some((Err(Error::Io { position, error }), None));
+            }
+        }
+    })
+}
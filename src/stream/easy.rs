@@ -82,10 +82,11 @@
 //! [`Parser::easy_parse`]: ../../parser/trait.Parser.html#method.easy_parse
 use std::{error::Error as StdError, fmt};
 
-use crate::error::{Info as PrimitiveInfo, ParseResult, StreamError, Tracked};
+use crate::error::{ErrorKind, Info as PrimitiveInfo, ParseResult, StreamError, Tracked};
 
 use crate::stream::{
-    Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce,
+    PointerOffset, Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor,
+    StreamOnce,
 };
 
 /// Enum holding error information. Variants are defined for `Stream::Token` and `Stream::Range` as
@@ -275,6 +276,16 @@ where
         *self == Self::end_of_input()
     }
 
+    fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::Unexpected(_) if self.is_unexpected_end_of_input() => ErrorKind::Eof,
+            Error::Unexpected(_) | Error::Expected(_) => ErrorKind::Unexpected,
+            Error::Message(_) => ErrorKind::Message,
+            Error::Other(ref err) if err.is::<std::io::Error>() => ErrorKind::Io,
+            Error::Other(_) => ErrorKind::Custom,
+        }
+    }
+
     #[inline]
     fn other<E>(err: E) -> Self
     where
@@ -349,6 +360,10 @@ where
         *self == Self::end_of_input()
     }
 
+    fn kind(&self) -> ErrorKind {
+        StreamError::kind(self)
+    }
+
     #[inline]
     fn into_other<T>(self) -> T
     where
@@ -375,6 +390,20 @@ where
         Self::new(position, Error::from(err))
     }
 
+    #[inline]
+    fn from_end_of_input(position: Position) -> Tracked<Self> {
+        // Most of these errors are discarded by `or`, `many` etc so avoid allocating the error
+        // vector until the error is actually returned
+        let mut error = Tracked::from(Errors::empty(position));
+        error.set_pending(Some(|errors: &mut Self| {
+            let error = Error::end_of_input();
+            if errors.errors.iter().all(|err| *err != error) {
+                errors.errors.insert(0, error);
+            }
+        }));
+        error
+    }
+
     #[inline]
     fn set_position(&mut self, position: Position) {
         self.position = position;
@@ -427,6 +456,10 @@ where
             .any(StreamError::is_unexpected_end_of_input)
     }
 
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::most_significant(self.errors.iter().map(StreamError::kind))
+    }
+
     #[inline]
     fn into_other<T>(mut self) -> T
     where
@@ -574,7 +607,43 @@ pub type ParseError<S> =
 
 /// Struct which hold information about an error that occurred at a specific position.
 /// Can hold multiple instances of `Error` if more that one error occurred in the same position.
+///
+/// With the `serde` feature enabled the errors can be serialized, rendering each `Info` to a
+/// string. The `PointerOffset` positions of errors from `&str` and `&[T]` inputs are only
+/// meaningful together with the input so they should be turned into indices with
+/// [`translate_position`] first.
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// let input = "1a";
+/// let err = (digit(), digit())
+///     .easy_parse(input)
+///     .unwrap_err()
+///     .translate_position(input);
+/// assert_eq!(
+///     serde_json::to_string(&err).unwrap(),
+///     r#"{"position":1,"errors":[{"kind":"unexpected","message":"a"},{"kind":"expected","message":"digit"}]}"#
+/// );
+///
+/// let err: easy::Errors<char, &str, usize> = serde_json::from_str(
+///     r#"{"position":1,"errors":[{"kind":"expected","message":"digit"}]}"#
+/// ).unwrap();
+/// assert_eq!(err, easy::Errors::new(1, easy::Error::Expected("digit".into())));
+/// # }
+/// ```
+///
+/// [`translate_position`]: #method.translate_position
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "P: serde::Serialize, T: fmt::Display, R: fmt::Display",
+        deserialize = "P: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Errors<T, R, P> {
     /// The position where the error occurred
     pub position: P,
@@ -710,6 +779,23 @@ impl<T, R, P> Errors<T, R, P> {
     }
 }
 
+impl<T, R, U> Errors<T, R, PointerOffset<U>>
+where
+    U: ?Sized,
+{
+    /// Translates the position into an index into `input`, the input the error was returned for.
+    ///
+    /// ```
+    /// # use combine::*;
+    /// let input = "ab";
+    /// let err = token('a').with(token('c')).easy_parse(input).unwrap_err();
+    /// assert_eq!(err.translate_position(input).position, 1);
+    /// ```
+    pub fn translate_position(self, input: &U) -> Errors<T, R, usize> {
+        self.map_position(|position| position.translate_position(input))
+    }
+}
+
 impl<T, R, P> StdError for Errors<T, R, P>
 where
     P: fmt::Display + fmt::Debug,
@@ -744,6 +830,65 @@ impl<T: fmt::Display, R: fmt::Display> fmt::Display for Error<T, R> {
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SerdeErrorKind {
+    Unexpected,
+    Expected,
+    Message,
+    Other,
+}
+
+/// How `Error` is (de)serialized, `{"kind": "unexpected", "message": "..."}`
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Error")]
+struct SerdeError {
+    kind: SerdeErrorKind,
+    message: String,
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<T, R> serde::Serialize for Error<T, R>
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let (kind, message) = match *self {
+            Error::Unexpected(ref info) => (SerdeErrorKind::Unexpected, info.to_string()),
+            Error::Expected(ref info) => (SerdeErrorKind::Expected, info.to_string()),
+            Error::Message(ref info) => (SerdeErrorKind::Message, info.to_string()),
+            Error::Other(ref err) => (SerdeErrorKind::Other, err.to_string()),
+        };
+        SerdeError { kind, message }.serialize(serializer)
+    }
+}
+
+/// Deserializes the message of every error into `Info::Owned` (or into a `String` for
+/// `Error::Other`) since tokens and ranges are serialized as their `Display` output.
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, T, R> serde::Deserialize<'de> for Error<T, R> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let SerdeError { kind, message } = SerdeError::deserialize(deserializer)?;
+        Ok(match kind {
+            SerdeErrorKind::Unexpected => Error::Unexpected(Info::Owned(message)),
+            SerdeErrorKind::Expected => Error::Expected(Info::Owned(message)),
+            SerdeErrorKind::Message => Error::Message(Info::Owned(message)),
+            SerdeErrorKind::Other => Error::Other(message.into()),
+        })
+    }
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct Stream<S>(pub S);
 
@@ -790,6 +935,16 @@ where
     fn is_partial(&self) -> bool {
         self.0.is_partial()
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        self.0
+            .uncons_tokens_while(f)
+            .map(|result| result.map_err(StreamError::into_other))
+    }
 }
 
 impl<S> RangeStreamOnce for Stream<S>
@@ -839,3 +994,59 @@ where
         self.0.position()
     }
 }
+
+/// Parses `input` with `parser`, only constructing `easy::Errors` if parsing actually fails.
+///
+/// `easy_parse` builds the expected and unexpected information of every failing branch, even if
+/// an alternative later succeeds, which can be a noticeable cost for grammars with many
+/// alternatives. This macro first runs the parser on `input` directly (using its cheaper error
+/// type) and only if that fails is the input reset and the parser re-run with `easy_parse` to
+/// materialize the error. The result is the same as calling `easy_parse` directly.
+///
+/// This is defined as a macro since the parser needs to be instantiated once for `input` and once
+/// for `easy::Stream<Input>`, which are different parser types for most parsers. The `parser`
+/// expression is bound once for the first parse and only evaluated a second time if that parse
+/// fails, so it should construct the parser in place and must not have side effects.
+///
+/// Note that this only helps callers which opt in to it: `easy_parse` (and `easy::Stream`) still
+/// construct the errors of every failing branch eagerly. Parsing invalid input with this macro
+/// costs a second, full parse.
+///
+/// ```
+/// use combine::{easy_parse_lazy, many1, EasyParser, Parser};
+/// use combine::parser::char::{digit, letter};
+///
+/// assert_eq!(
+///     easy_parse_lazy!(many1::<String, _, _>(letter()).or(many1(digit())), "123"),
+///     Ok(("123".to_string(), ""))
+/// );
+/// assert_eq!(
+///     easy_parse_lazy!(many1::<String, _, _>(letter()).or(many1(digit())), "!"),
+///     many1::<String, _, _>(letter()).or(many1(digit())).easy_parse("!")
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[macro_export]
+macro_rules! easy_parse_lazy {
+    ($parser: expr, $input: expr $(,)?) => {
+        match $input {
+            mut input => {
+                let before = $crate::stream::ResetStream::checkpoint(&input);
+                let result = {
+                    let mut parser = $parser;
+                    $crate::Parser::parse_stream(&mut parser, &mut input).into_result()
+                };
+                match result {
+                    Ok((value, _)) => Ok((value, input)),
+                    Err(_) => match $crate::stream::ResetStream::reset(&mut input, before) {
+                        Ok(()) => {
+                            let mut parser = $parser;
+                            $crate::EasyParser::easy_parse(&mut parser, input)
+                        }
+                        Err(err) => Err($crate::error::ParseError::into_other(err)),
+                    },
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,599 @@
+//! Stream wrapper which provides an informative error type without allocating.
+//!
+//! `fixed::Errors` works like [`easy::Errors`][] but stores its errors in an array of `N`
+//! elements instead of a `Vec`. Any error added after the array is full is dropped. Since nothing
+//! is allocated it can be used in `no_std` environments where `UnexpectedParse` does not give
+//! enough information.
+//!
+//! Formatted messages can not be stored without allocating so they are recorded as
+//! [`Info::Format`][] and only the fact that a message was emitted is kept.
+//!
+//! ```
+//! use combine::Parser;
+//! use combine::parser::byte::{byte, digit};
+//! use combine::stream::fixed;
+//!
+//! let result = byte(b'a')
+//!     .or(byte(b'b'))
+//!     .or(digit())
+//!     .parse(fixed::Stream::<_, 4>::from(&b"!"[..]));
+//! let err = result.unwrap_err();
+//! let errors: Vec<_> = err.errors().cloned().collect();
+//! assert_eq!(
+//!     errors,
+//!     [
+//!         fixed::Error::Unexpected(fixed::Info::Token(b'!')),
+//!         fixed::Error::Expected(fixed::Info::Token(b'a')),
+//!         fixed::Error::Expected(fixed::Info::Token(b'b')),
+//!         fixed::Error::Expected(fixed::Info::Static("digit")),
+//!     ]
+//! );
+//!
+//! // Only the first two errors fit, the rest is dropped
+//! let result = byte(b'a')
+//!     .or(byte(b'b'))
+//!     .or(digit())
+//!     .parse(fixed::Stream::<_, 2>::from(&b"!"[..]));
+//! assert_eq!(result.unwrap_err().errors().count(), 2);
+//! ```
+//!
+//! [`easy::Errors`]: ../easy/struct.Errors.html
+//! [`Info::Format`]: enum.Info.html#variant.Format
+use crate::lib::fmt;
+
+use crate::error::{ErrorKind, ParseResult, StreamError, Tracked};
+
+use crate::stream::{
+    Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce,
+};
+
+const FORMAT_MESSAGE: &str = "formatted message";
+
+/// Enum holding error information. Unlike `easy::Info` this does not have a variant for owned
+/// strings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Info<T, R> {
+    Token(T),
+    Range(R),
+    Static(&'static str),
+    /// A formatted message. The message itself is dropped as it can't be stored without allocating.
+    Format,
+}
+
+impl<T, R> Info<T, R> {
+    pub fn map_token<F, U>(self, f: F) -> Info<U, R>
+    where
+        F: FnOnce(T) -> U,
+    {
+        use self::Info::*;
+
+        match self {
+            Token(t) => Token(f(t)),
+            Range(r) => Range(r),
+            Static(x) => Static(x),
+            Format => Format,
+        }
+    }
+
+    pub fn map_range<F, S>(self, f: F) -> Info<T, S>
+    where
+        F: FnOnce(R) -> S,
+    {
+        use self::Info::*;
+
+        match self {
+            Token(t) => Token(t),
+            Range(r) => Range(f(r)),
+            Static(x) => Static(x),
+            Format => Format,
+        }
+    }
+}
+
+impl<T: fmt::Display, R: fmt::Display> fmt::Display for Info<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Info::Token(ref c) => write!(f, "{}", c),
+            Info::Range(ref c) => write!(f, "{}", c),
+            Info::Static(s) => write!(f, "{}", s),
+            Info::Format => write!(f, "{}", FORMAT_MESSAGE),
+        }
+    }
+}
+
+impl<T, R> From<&'static str> for Info<T, R> {
+    fn from(s: &'static str) -> Info<T, R> {
+        Info::Static(s)
+    }
+}
+
+/// Enum used to store information about an error that has occurred during parsing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error<T, R> {
+    /// Error indicating an unexpected token has been encountered in the stream
+    Unexpected(Info<T, R>),
+    /// Error indicating that the parser expected something else
+    Expected(Info<T, R>),
+    /// Generic message
+    Message(Info<T, R>),
+}
+
+impl<T, R> Error<T, R> {
+    /// Returns the `end_of_input` error.
+    pub fn end_of_input() -> Error<T, R> {
+        Error::Unexpected("end of input".into())
+    }
+
+    pub fn map_token<F, U>(self, f: F) -> Error<U, R>
+    where
+        F: FnOnce(T) -> U,
+    {
+        use self::Error::*;
+
+        match self {
+            Unexpected(x) => Unexpected(x.map_token(f)),
+            Expected(x) => Expected(x.map_token(f)),
+            Message(x) => Message(x.map_token(f)),
+        }
+    }
+
+    pub fn map_range<F, S>(self, f: F) -> Error<T, S>
+    where
+        F: FnOnce(R) -> S,
+    {
+        use self::Error::*;
+
+        match self {
+            Unexpected(x) => Unexpected(x.map_range(f)),
+            Expected(x) => Expected(x.map_range(f)),
+            Message(x) => Message(x.map_range(f)),
+        }
+    }
+}
+
+impl<T: fmt::Display, R: fmt::Display> fmt::Display for Error<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::Unexpected(ref c) => write!(f, "Unexpected `{}`", c),
+            Error::Expected(ref s) => write!(f, "Expected `{}`", s),
+            Error::Message(ref msg) => msg.fmt(f),
+        }
+    }
+}
+
+impl<Item, Range> StreamError<Item, Range> for Error<Item, Range>
+where
+    Item: PartialEq,
+    Range: PartialEq,
+{
+    #[inline]
+    fn unexpected_token(token: Item) -> Self {
+        Error::Unexpected(Info::Token(token))
+    }
+    #[inline]
+    fn unexpected_range(token: Range) -> Self {
+        Error::Unexpected(Info::Range(token))
+    }
+    #[inline]
+    fn unexpected_format<T>(_: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Unexpected(Info::Format)
+    }
+    #[inline]
+    fn unexpected_static_message(msg: &'static str) -> Self {
+        Error::Unexpected(Info::Static(msg))
+    }
+
+    #[inline]
+    fn expected_token(token: Item) -> Self {
+        Error::Expected(Info::Token(token))
+    }
+    #[inline]
+    fn expected_range(token: Range) -> Self {
+        Error::Expected(Info::Range(token))
+    }
+    #[inline]
+    fn expected_format<T>(_: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Expected(Info::Format)
+    }
+    #[inline]
+    fn expected_static_message(msg: &'static str) -> Self {
+        Error::Expected(Info::Static(msg))
+    }
+
+    #[inline]
+    fn message_format<T>(_: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Message(Info::Format)
+    }
+    #[inline]
+    fn message_static_message(msg: &'static str) -> Self {
+        Error::Message(Info::Static(msg))
+    }
+    #[inline]
+    fn message_token(token: Item) -> Self {
+        Error::Message(Info::Token(token))
+    }
+    #[inline]
+    fn message_range(token: Range) -> Self {
+        Error::Message(Info::Range(token))
+    }
+
+    fn is_unexpected_end_of_input(&self) -> bool {
+        *self == Self::end_of_input()
+    }
+
+    fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::Unexpected(_) if self.is_unexpected_end_of_input() => ErrorKind::Eof,
+            Error::Unexpected(_) | Error::Expected(_) => ErrorKind::Unexpected,
+            Error::Message(_) => ErrorKind::Message,
+        }
+    }
+
+    #[inline]
+    fn into_other<T>(self) -> T
+    where
+        T: StreamError<Item, Range>,
+    {
+        match self {
+            Error::Unexpected(info) => match info {
+                Info::Token(x) => T::unexpected_token(x),
+                Info::Range(x) => T::unexpected_range(x),
+                Info::Static(x) => T::unexpected_static_message(x),
+                Info::Format => T::unexpected_static_message(FORMAT_MESSAGE),
+            },
+            Error::Expected(info) => match info {
+                Info::Token(x) => T::expected_token(x),
+                Info::Range(x) => T::expected_range(x),
+                Info::Static(x) => T::expected_static_message(x),
+                Info::Format => T::expected_static_message(FORMAT_MESSAGE),
+            },
+            Error::Message(info) => match info {
+                Info::Token(x) => T::message_token(x),
+                Info::Range(x) => T::message_range(x),
+                Info::Static(x) => T::message_static_message(x),
+                Info::Format => T::message_static_message(FORMAT_MESSAGE),
+            },
+        }
+    }
+}
+
+/// Convenience alias over `Errors` for `StreamOnce` types which makes it possible to specify the
+/// `Errors` type from a `StreamOnce` by writing `ParseError<Input, N>` instead of
+/// `Errors<Input::Token, Input::Range, Input::Position, N>`
+pub type ParseError<S, const N: usize> =
+    Errors<<S as StreamOnce>::Token, <S as StreamOnce>::Range, <S as StreamOnce>::Position, N>;
+
+/// Struct which hold information about an error that occurred at a specific position.
+/// Can hold up to `N` instances of `Error`, any errors added beyond that are dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Errors<T, R, P, const N: usize> {
+    /// The position where the error occurred
+    pub position: P,
+    errors: [Option<Error<T, R>>; N],
+    len: usize,
+}
+
+impl<T, R, P, const N: usize> Errors<T, R, P, N> {
+    const NONE: Option<Error<T, R>> = None;
+
+    /// Constructs a new `Errors` which occurred at `position`.
+    #[inline]
+    pub fn new(position: P, error: Error<T, R>) -> Self
+    where
+        T: PartialEq,
+        R: PartialEq,
+    {
+        let mut errors = Self::empty(position);
+        errors.add_error(error);
+        errors
+    }
+
+    /// Constructs an error with no other information than the position it occurred at.
+    #[inline]
+    pub fn empty(position: P) -> Self {
+        Errors {
+            position,
+            errors: [Self::NONE; N],
+            len: 0,
+        }
+    }
+
+    /// Constructs an end of input error. Should be returned by parsers which encounter end of
+    /// input unexpectedly.
+    #[inline]
+    pub fn end_of_input(position: P) -> Self
+    where
+        T: PartialEq,
+        R: PartialEq,
+    {
+        Self::new(position, Error::end_of_input())
+    }
+
+    /// Returns an iterator over the errors that were stored.
+    pub fn errors(&self) -> impl Iterator<Item = &Error<T, R>> + '_ {
+        self.errors[..self.len].iter().filter_map(Option::as_ref)
+    }
+
+    /// Returns `true` if no more errors can be stored in `self`.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Adds an error if `error` does not exist in this `Errors` already (as determined by
+    /// `PartialEq`) and there is space left for it.
+    pub fn add_error(&mut self, error: Error<T, R>)
+    where
+        T: PartialEq,
+        R: PartialEq,
+    {
+        // Don't add duplicate errors
+        if self.len < N && self.errors().all(|err| *err != error) {
+            self.errors[self.len] = Some(error);
+            self.len += 1;
+        }
+    }
+
+    /// Removes all `Expected` errors in `self` and adds `info` instead.
+    pub fn set_expected(&mut self, info: Info<T, R>)
+    where
+        T: PartialEq,
+        R: PartialEq,
+    {
+        self.retain_from(0, |e| !matches!(*e, Error::Expected(_)));
+        self.add_error(Error::Expected(info));
+    }
+
+    /// Merges two `Errors`. If they exist at the same position the errors of `other` are
+    /// added to `self` (using `add_error` to skip duplicates). If they are not at the same
+    /// position the error furthest ahead are returned, ignoring the other `Errors`.
+    pub fn merge(mut self, mut other: Self) -> Self
+    where
+        P: Ord,
+        T: PartialEq,
+        R: PartialEq,
+    {
+        use crate::lib::cmp::Ordering;
+
+        // Only keep the errors which occurred after consuming the most amount of data
+        match self.position.cmp(&other.position) {
+            Ordering::Less => other,
+            Ordering::Greater => self,
+            Ordering::Equal => {
+                for message in other.errors[..other.len].iter_mut() {
+                    if let Some(message) = message.take() {
+                        self.add_error(message);
+                    }
+                }
+                self
+            }
+        }
+    }
+
+    /// Maps the position to a new value
+    pub fn map_position<F, Q>(self, f: F) -> Errors<T, R, Q, N>
+    where
+        F: FnOnce(P) -> Q,
+    {
+        Errors {
+            position: f(self.position),
+            errors: self.errors,
+            len: self.len,
+        }
+    }
+
+    // Removes all errors at `start..` for which `f` returns false, keeping the order of the
+    // remaining errors
+    fn retain_from(&mut self, start: usize, mut f: impl FnMut(&Error<T, R>) -> bool) {
+        let mut len = start;
+        for i in start..self.len {
+            if matches!(self.errors[i], Some(ref e) if f(e)) {
+                self.errors.swap(len, i);
+                len += 1;
+            } else {
+                self.errors[i] = None;
+            }
+        }
+        self.len = len;
+    }
+}
+
+impl<Item, Range, Position, const N: usize> crate::error::ParseError<Item, Range, Position>
+    for Errors<Item, Range, Position, N>
+where
+    Item: PartialEq,
+    Range: PartialEq,
+    Position: Ord,
+{
+    type StreamError = Error<Item, Range>;
+    #[inline]
+    fn empty(pos: Position) -> Self {
+        Errors::empty(pos)
+    }
+    #[inline]
+    fn from_error(position: Position, err: Self::StreamError) -> Self {
+        Self::new(position, err)
+    }
+
+    #[inline]
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    #[inline]
+    fn merge(self, other: Self) -> Self {
+        Errors::merge(self, other)
+    }
+
+    #[inline]
+    fn add(&mut self, err: Self::StreamError) {
+        self.add_error(err);
+    }
+
+    #[inline]
+    fn set_expected<F>(self_: &mut Tracked<Self>, info: Self::StreamError, f: F)
+    where
+        F: FnOnce(&mut Tracked<Self>),
+    {
+        let start = self_.error.len;
+        f(self_);
+        // Replace all expected errors that were added from the previous add_error
+        // with this expected error
+        self_
+            .error
+            .retain_from(start, |e| !matches!(*e, Error::Expected(_)));
+        self_.error.add(info);
+    }
+
+    fn clear_expected(&mut self) {
+        self.retain_from(0, |e| !matches!(*e, Error::Expected(_)))
+    }
+
+    fn is_unexpected_end_of_input(&self) -> bool {
+        self.errors().any(StreamError::is_unexpected_end_of_input)
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::most_significant(self.errors().map(StreamError::kind))
+    }
+
+    #[inline]
+    fn into_other<T>(mut self) -> T
+    where
+        T: crate::error::ParseError<Item, Range, Position>,
+    {
+        match self.len.checked_sub(1).and_then(|i| self.errors[i].take()) {
+            Some(err) => T::from_error(self.position, StreamError::into_other(err)),
+            None => T::empty(self.position),
+        }
+    }
+}
+
+impl<T, R, P, const N: usize> fmt::Display for Errors<T, R, P, N>
+where
+    P: fmt::Display,
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Parse error at {}", self.position)?;
+        for error in self.errors() {
+            writeln!(f, "{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, R, P, const N: usize> std::error::Error for Errors<T, R, P, N>
+where
+    P: fmt::Display + fmt::Debug,
+    T: fmt::Display + fmt::Debug,
+    R: fmt::Display + fmt::Debug,
+{
+}
+
+/// Stream wrapper which uses `Errors` with a capacity of `N` as its error type.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct Stream<S, const N: usize>(pub S);
+
+impl<S, const N: usize> From<S> for Stream<S, N> {
+    fn from(stream: S) -> Self {
+        Stream(stream)
+    }
+}
+
+impl<S, const N: usize> ResetStream for Stream<S, N>
+where
+    S: ResetStream + Positioned,
+    S::Token: PartialEq,
+    S::Range: PartialEq,
+{
+    type Checkpoint = S::Checkpoint;
+
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.0.checkpoint()
+    }
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), Self::Error> {
+        self.0
+            .reset(checkpoint)
+            .map_err(crate::error::ParseError::into_other)
+    }
+}
+
+impl<S, const N: usize> StreamOnce for Stream<S, N>
+where
+    S: StreamOnce + Positioned,
+    S::Token: PartialEq,
+    S::Range: PartialEq,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = ParseError<S, N>;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<Self::Token, StreamErrorFor<Self>> {
+        self.0.uncons().map_err(StreamError::into_other)
+    }
+
+    fn is_partial(&self) -> bool {
+        self.0.is_partial()
+    }
+}
+
+impl<S, const N: usize> RangeStreamOnce for Stream<S, N>
+where
+    S: RangeStream,
+    S::Token: PartialEq,
+    S::Range: PartialEq,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        self.0.uncons_range(size).map_err(StreamError::into_other)
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.0.uncons_while(f).map_err(StreamError::into_other)
+    }
+
+    #[inline]
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.0.uncons_while1(f).map_err(StreamError::into_other)
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.0.distance(end)
+    }
+
+    fn range(&self) -> Self::Range {
+        self.0.range()
+    }
+}
+
+impl<S, const N: usize> Positioned for Stream<S, N>
+where
+    S: StreamOnce + Positioned,
+    S::Token: PartialEq,
+    S::Range: PartialEq,
+{
+    fn position(&self) -> S::Position {
+        self.0.position()
+    }
+}
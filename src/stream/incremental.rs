@@ -0,0 +1,286 @@
+//! Incremental re-parsing of a sequence of top-level items.
+//!
+//! [`Incremental`][] remembers the span of every item parsed from an input along with how far
+//! ahead its parser looked. After the input is edited only the items which did not look at the
+//! edited input are kept and parsing resumes from the end of the last of them, which avoids
+//! re-parsing the entire input on every keystroke in an editor.
+//!
+//! [`Incremental`]: struct.Incremental.html
+
+use std::ops::Range;
+
+use crate::{
+    error::{ParseError, ParseResult, StreamError},
+    stream::{
+        input_at_eof, Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor,
+        StreamOnce,
+    },
+    Parser,
+};
+
+/// An item parsed by [`Incremental`][], along with the span of input it was parsed from.
+///
+/// [`Incremental`]: struct.Incremental.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Item<O> {
+    /// The span of the item, as distances from the start of the input (in the units of
+    /// `RangeStream::distance`, bytes for `&str` and `&[u8]`).
+    pub span: Range<usize>,
+    /// The end of the input which was inspected while parsing the item (including backtracked
+    /// and peeked tokens), one past the end of the input if the parser reached the end of input.
+    pub lookahead: usize,
+    pub value: O,
+}
+
+/// Stream wrapper used by [`Incremental::parse`][] which records how far ahead the parser has
+/// looked into the input.
+///
+/// [`Incremental::parse`]: struct.Incremental.html#method.parse
+#[derive(Clone)]
+pub struct Stream<S>
+where
+    S: ResetStream,
+{
+    stream: S,
+    start: S::Checkpoint,
+    lookahead: usize,
+}
+
+impl<S> Stream<S>
+where
+    S: RangeStreamOnce,
+{
+    fn look_ahead(&mut self, extra: usize) {
+        let distance = self.stream.distance(&self.start).saturating_add(extra);
+        if distance > self.lookahead {
+            self.lookahead = distance;
+        }
+    }
+
+    fn look_ahead_result<T, E>(&mut self, result: &Result<T, E>) {
+        // A failed (or stopped) read has inspected the token (or end of input) after the
+        // current position
+        self.look_ahead(if result.is_ok() { 0 } else { 1 });
+    }
+}
+
+impl<S> Positioned for Stream<S>
+where
+    S: RangeStream,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.stream.position()
+    }
+}
+
+impl<S> ResetStream for Stream<S>
+where
+    S: RangeStreamOnce,
+{
+    type Checkpoint = S::Checkpoint;
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.stream.checkpoint()
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), S::Error> {
+        self.stream.reset(checkpoint)
+    }
+}
+
+impl<S> StreamOnce for Stream<S>
+where
+    S: RangeStreamOnce,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<S::Token, StreamErrorFor<Self>> {
+        let result = self.stream.uncons();
+        self.look_ahead_result(&result);
+        result
+    }
+
+    fn is_partial(&self) -> bool {
+        self.stream.is_partial()
+    }
+}
+
+impl<S> RangeStreamOnce for Stream<S>
+where
+    S: RangeStreamOnce,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        let result = self.stream.uncons_range(size);
+        if result.is_err() {
+            // Not enough input remains so the result depends on all of it
+            self.lookahead = usize::MAX;
+        }
+        self.look_ahead(0);
+        result
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        let result = self.stream.uncons_while(f);
+        // The token which stopped `f` (or the end of input) was inspected as well
+        self.look_ahead(1);
+        result
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        let result = self.stream.uncons_while1(f);
+        self.look_ahead(1);
+        result
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.stream.distance(end)
+    }
+
+    #[inline]
+    fn range(&self) -> Self::Range {
+        self.stream.range()
+    }
+}
+
+/// Parses an input as a sequence of top-level items and keeps the items which are unaffected by
+/// later edits.
+///
+/// An edit at `offset` discards every item which inspected the input at or after `offset`,
+/// including input which the item's parser only peeked at or backtracked over (reaching the end
+/// of input counts as inspecting the input after it). Parsers which depend on earlier items must
+/// not be used.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, letter, spaces};
+/// # use combine::stream::incremental::Incremental;
+/// # fn main() {
+/// let item = || {
+///     many1::<String, _, _>(letter())
+///         .skip(char(';'))
+///         .skip(spaces())
+///         .map(|s| s.len())
+/// };
+///
+/// let mut incremental = Incremental::new();
+/// assert_eq!(incremental.parse(item(), "abc; de; f;"), Ok(""));
+/// assert_eq!(incremental.values().cloned().collect::<Vec<_>>(), [3, 2, 1]);
+///
+/// // Insert `xy` into the last item, only that item needs to be parsed again
+/// incremental.edit(10);
+/// assert_eq!(incremental.resume_offset(), 9);
+/// assert_eq!(incremental.parse(item(), "abc; de; fxy;"), Ok(""));
+/// assert_eq!(incremental.values().cloned().collect::<Vec<_>>(), [3, 2, 3]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Incremental<O> {
+    items: Vec<Item<O>>,
+}
+
+impl<O> Default for Incremental<O> {
+    fn default() -> Self {
+        Incremental { items: Vec::new() }
+    }
+}
+
+impl<O> Incremental<O> {
+    /// Constructs an `Incremental` which has not parsed any items yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the items which are currently valid, in input order.
+    pub fn items(&self) -> &[Item<O>] {
+        &self.items
+    }
+
+    /// Returns the values of the items which are currently valid, in input order.
+    pub fn values(&self) -> impl Iterator<Item = &O> {
+        self.items.iter().map(|item| &item.value)
+    }
+
+    /// Returns the offset where the next call to `parse` starts parsing.
+    pub fn resume_offset(&self) -> usize {
+        self.items.last().map_or(0, |item| item.span.end)
+    }
+
+    /// Records that the input was edited (by inserting or deleting input) starting at `offset`,
+    /// discarding every item which the edit may have affected.
+    pub fn edit(&mut self, offset: usize) {
+        let keep = self
+            .items
+            .iter()
+            .position(|item| item.lookahead > offset)
+            .unwrap_or(self.items.len());
+        self.items.truncate(keep);
+    }
+
+    /// Discards all items so the next call to `parse` parses the entire input.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Parses items with `parser` from `input`, starting after the last valid item, until the end
+    /// of input is reached.
+    ///
+    /// Returns the remaining input. If `parser` fails, or succeeds without consuming any input
+    /// before the end of input is reached, an error is returned and the items parsed before it
+    /// are kept so the next call resumes from there.
+    pub fn parse<Input, P>(
+        &mut self,
+        mut parser: P,
+        mut input: Input,
+    ) -> Result<Input, <Input as StreamOnce>::Error>
+    where
+        Input: RangeStream,
+        P: Parser<Stream<Input>, Output = O>,
+    {
+        let start = input.checkpoint();
+        let mut offset = self.resume_offset();
+        if let Err(err) = input.uncons_range(offset) {
+            return Err(Input::Error::from_error(input.position(), err));
+        }
+
+        let mut input = Stream {
+            stream: input,
+            start,
+            lookahead: 0,
+        };
+        while !input_at_eof(&mut input.stream) {
+            input.lookahead = offset;
+            let value = parser.parse_with_state(&mut input, &mut Default::default())?;
+            let end = input.distance(&input.start);
+            if end == offset {
+                return Err(Input::Error::from_error(
+                    input.position(),
+                    StreamError::message_static_message("parser succeeded without consuming input"),
+                ));
+            }
+            self.items.push(Item {
+                span: offset..end,
+                lookahead: input.lookahead,
+                value,
+            });
+            offset = end;
+        }
+        Ok(input.stream)
+    }
+}
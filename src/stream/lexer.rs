@@ -0,0 +1,241 @@
+//! Two-phase parsing where a lexer turns the input into tokens which are then parsed.
+//!
+//! [`Stream`][] runs a lexer (any parser over the source input) lazily, producing one token each
+//! time the token parser asks for one. The position of the token stream is the position in the
+//! source input, so wrapping it in an [`easy::Stream`][] reports errors at source positions.
+//!
+//! After each token the trivia parser skips any whitespace (and comments) so that the stream ends
+//! once the last token has been lexed. The trivia is not part of the span of the token before it.
+//! Whitespace at the start of the input must be skipped before the stream is created.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::char::{char, digit, spaces};
+//! # use combine::stream::{easy, lexer, position::{self, SourcePosition}};
+//! # fn main() {
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Token {
+//!     Number(u32),
+//!     Plus,
+//! }
+//!
+//! let lex = || {
+//!     choice((
+//!         many1(digit()).map(|s: String| Token::Number(s.parse().unwrap())),
+//!         char('+').map(|_| Token::Plus),
+//!     ))
+//! };
+//! let number = || {
+//!     satisfy_map(|t| match t {
+//!         Token::Number(n) => Some(n),
+//!         _ => None,
+//!     })
+//! };
+//! let mut sum = number()
+//!     .and(many::<Vec<_>, _, _>(token(Token::Plus).with(number())))
+//!     .map(|(n, rest)| n + rest.iter().sum::<u32>());
+//!
+//! let tokens = lexer::Stream::new(position::Stream::new("1 + 22 + 3"), lex(), spaces());
+//! assert_eq!(sum.parse(easy::Stream(tokens)).map(|t| t.0), Ok(26));
+//!
+//! let tokens = lexer::Stream::new(position::Stream::new("1 + + 3"), lex(), spaces());
+//! let err = sum.skip(eof()).parse(easy::Stream(tokens)).err().unwrap();
+//! assert_eq!(err.position, SourcePosition { line: 1, column: 5 });
+//! # }
+//! ```
+//!
+//! [`Stream`]: struct.Stream.html
+//! [`easy::Stream`]: ../easy/struct.Stream.html
+
+use crate::{
+    error::UnexpectedParse,
+    lib::ops::Range,
+    stream::{input_at_eof, Positioned, ResetStream, StreamErrorFor, StreamOnce},
+    Parser, Stream as StreamTrait,
+};
+
+/// A token together with the span of source input it was lexed from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Spanned<T, P> {
+    pub span: Range<P>,
+    pub value: T,
+}
+
+type Lexed<Input, T> = Result<
+    (T, <Input as StreamOnce>::Position),
+    (
+        <Input as StreamOnce>::Position,
+        <Input as StreamOnce>::Error,
+    ),
+>;
+
+/// A stream of the tokens lexed from `Input` by the lexer `L`, skipping the trivia `T` after each
+/// token.
+///
+/// Tokens are lexed on demand and lexed again if the stream is reset to an earlier checkpoint. As
+/// an `Iterator` it yields each token together with its span.
+pub struct Stream<Input, L, T>
+where
+    Input: StreamTrait,
+{
+    input: Input,
+    lexer: L,
+    trivia: T,
+    error: Option<(Input::Position, Input::Error)>,
+}
+
+impl<Input, L, T> Stream<Input, L, T>
+where
+    Input: StreamTrait,
+    L: Parser<Input>,
+    T: Parser<Input>,
+{
+    /// Creates a token stream which lexes `input` with `lexer`, skipping input with `trivia` after
+    /// each token.
+    pub fn new(input: Input, lexer: L, trivia: T) -> Self {
+        Stream {
+            input,
+            lexer,
+            trivia,
+            error: None,
+        }
+    }
+
+    /// Returns the source input which has not been lexed yet.
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Returns the error of the lexer if a token could not be lexed (and the input where it failed
+    /// has not been lexed successfully since).
+    pub fn lexer_error(&self) -> Option<&Input::Error> {
+        self.error.as_ref().map(|(_, err)| err)
+    }
+
+    /// Returns the source input which has not been lexed yet along with the error of the lexer if
+    /// a token could not be lexed.
+    pub fn into_inner(self) -> (Input, Option<Input::Error>) {
+        (self.input, self.error.map(|(_, err)| err))
+    }
+
+    /// Lexes a token, returning it along with the position where it ends (before any trivia).
+    ///
+    /// Errors are returned along with the position of the token which could not be lexed.
+    fn lex(&mut self) -> Option<Lexed<Input, L::Output>> {
+        if input_at_eof(&mut self.input) {
+            return None;
+        }
+        let start = self.input.position();
+        let checkpoint = self.input.checkpoint();
+        match self
+            .lexer
+            .parse_with_state(&mut self.input, &mut Default::default())
+        {
+            Ok(token) => {
+                // Only forget an error once the input where it occurred lexes successfully
+                if matches!(self.error, Some((ref at, _)) if *at == start) {
+                    self.error = None;
+                }
+                let end = self.input.position();
+                let before_trivia = self.input.checkpoint();
+                if self
+                    .trivia
+                    .parse_with_state(&mut self.input, &mut Default::default())
+                    .is_err()
+                {
+                    // Let the next token report the error
+                    let _ = self.input.reset(before_trivia);
+                }
+                Some(Ok((token, end)))
+            }
+            Err(err) => {
+                // Leave the source input at the start of the token which could not be lexed so
+                // that errors point at it
+                let _ = self.input.reset(checkpoint);
+                Some(Err((start, err)))
+            }
+        }
+    }
+}
+
+impl<Input, L, T> Iterator for Stream<Input, L, T>
+where
+    Input: StreamTrait,
+    L: Parser<Input>,
+    T: Parser<Input>,
+{
+    type Item = Result<Spanned<L::Output, Input::Position>, Input::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.input.position();
+        let token = self.lex()?;
+        Some(
+            token
+                .map(|(value, end)| Spanned {
+                    span: start..end,
+                    value,
+                })
+                .map_err(|(_, err)| err),
+        )
+    }
+}
+
+impl<Input, L, T> StreamOnce for Stream<Input, L, T>
+where
+    Input: StreamTrait,
+    Input::Position: Default,
+    L: Parser<Input>,
+    L::Output: Clone,
+    T: Parser<Input>,
+{
+    type Token = L::Output;
+    type Range = L::Output;
+    type Position = Input::Position;
+    type Error = UnexpectedParse;
+
+    fn uncons(&mut self) -> Result<Self::Token, StreamErrorFor<Self>> {
+        match self.lex() {
+            Some(Ok((token, _))) => Ok(token),
+            Some(Err(err)) => {
+                self.error = Some(err);
+                Err(UnexpectedParse::Unexpected)
+            }
+            None => Err(UnexpectedParse::Eoi),
+        }
+    }
+}
+
+impl<Input, L, T> ResetStream for Stream<Input, L, T>
+where
+    Input: StreamTrait,
+    Input::Position: Default,
+    L: Parser<Input>,
+    L::Output: Clone,
+    T: Parser<Input>,
+{
+    type Checkpoint = Input::Checkpoint;
+
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.input.checkpoint()
+    }
+
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), Self::Error> {
+        self.input
+            .reset(checkpoint)
+            .map_err(|_| UnexpectedParse::Unexpected)
+    }
+}
+
+impl<Input, L, T> Positioned for Stream<Input, L, T>
+where
+    Input: StreamTrait,
+    Input::Position: Default,
+    L: Parser<Input>,
+    L::Output: Clone,
+    T: Parser<Input>,
+{
+    fn position(&self) -> Self::Position {
+        self.input.position()
+    }
+}
@@ -12,7 +12,7 @@
 //! aren't combined and the latter is used in parsers such as `or` to try multiple alternative
 //! parses.
 
-use crate::lib::{cmp::Ordering, fmt, marker::PhantomData, mem, str::Chars};
+use crate::lib::{cmp::Ordering, fmt, marker::PhantomData, mem, ops, str::Chars};
 
 use crate::{
     error::{
@@ -26,6 +26,9 @@ use crate::{
 #[cfg(feature = "std")]
 pub use self::decoder::Decoder;
 
+#[cfg(feature = "futures-03")]
+pub use self::decoder::frames;
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! clone_resetable {
@@ -47,6 +50,7 @@ macro_rules! clone_resetable {
     }
 }
 
+pub mod budget;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod buf_reader;
@@ -56,6 +60,17 @@ pub mod buf_reader;
 pub mod buffered;
 #[cfg(feature = "std")]
 pub mod easy;
+pub mod fixed;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod incremental;
+pub mod lexer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod packrat;
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub mod parallel;
 /// Stream wrapper which provides more detailed position information.
 pub mod position;
 /// Stream wrapper allowing `std::io::Read` to be used
@@ -133,6 +148,21 @@ pub trait StreamOnce {
     fn is_partial(&self) -> bool {
         false
     }
+
+    /// Takes tokens from the stream for as long as `f` returns `true`.
+    ///
+    /// Returns `None`, without taking any tokens, if the stream can not do this any faster than
+    /// calling `uncons` repeatedly.
+    ///
+    /// Internal API: Lets `many` of a single token parser scan a `RangeStream` in one call.
+    #[doc(hidden)]
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        _f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        None
+    }
 }
 
 /// A `StreamOnce` which can create checkpoints which the stream can be reset to
@@ -149,6 +179,7 @@ pub trait ResetStream: StreamOnce {
 clone_resetable! {('a) &'a str}
 clone_resetable! {('a, T) &'a [T]}
 clone_resetable! {('a, T) SliceStream<'a, T> }
+clone_resetable! {('a, T, P: Clone) SpannedSliceStream<'a, T, P> }
 clone_resetable! {(T: Clone) IteratorStream<T>}
 
 /// A stream of tokens which can be duplicated
@@ -172,6 +203,9 @@ where
 {
     match input.uncons() {
         Ok(x) => CommitOk(x),
+        Err(ref err) if !input.is_partial() && err.is_unexpected_end_of_input() => {
+            PeekErr(Input::Error::from_end_of_input(input.position()))
+        }
         Err(err) => wrap_stream_error(input, err),
     }
 }
@@ -314,6 +348,40 @@ where
     }
 }
 
+#[doc(hidden)]
+#[inline]
+pub fn uncons_tokens_while<Input>(
+    input: &mut Input,
+    f: &mut dyn FnMut(Input::Token) -> bool,
+) -> Option<ParseResult<(), Input::Error>>
+where
+    Input: ?Sized + Stream,
+{
+    let mut committed = false;
+    let result = input.uncons_tokens_while(&mut |c| {
+        let ok = f(c);
+        committed |= ok;
+        ok
+    })?;
+    Some(match result {
+        Err(err) => wrap_stream_error(input, err),
+        Ok(()) => {
+            if input.is_partial() && input_at_eof(input) {
+                // Partial inputs which encounter end of file must fail to let more input be
+                // retrieved
+                CommitErr(Input::Error::from_error(
+                    input.position(),
+                    StreamError::end_of_input(),
+                ))
+            } else if committed {
+                CommitOk(())
+            } else {
+                PeekOk(())
+            }
+        }
+    })
+}
+
 #[inline]
 /// Takes items from stream, testing each one with `predicate`
 /// returns a range of at least one items which passed `predicate`.
@@ -400,6 +468,14 @@ where
     fn is_partial(&self) -> bool {
         (**self).is_partial()
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        (**self).uncons_tokens_while(f)
+    }
 }
 
 impl<'a, I> Positioned for &'a mut I
@@ -488,6 +564,15 @@ impl<'a> StreamOnce for &'a str {
             None => Err(StringStreamError::Eoi),
         }
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(char) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        str_uncons_while(self, self.chars(), f);
+        Some(Ok(()))
+    }
 }
 
 impl<'a> Positioned for &'a str {
@@ -724,6 +809,15 @@ where
             None => Err(UnexpectedParse::Eoi),
         }
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(T) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        slice_uncons_while(self, 0, f);
+        Some(Ok(()))
+    }
 }
 
 /// Stream type which indicates that the stream is partial if end of input is reached
@@ -780,6 +874,14 @@ where
     fn is_partial(&self) -> bool {
         true
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        self.0.uncons_tokens_while(f)
+    }
 }
 
 impl<S> RangeStreamOnce for PartialStream<S>
@@ -882,6 +984,14 @@ where
     fn is_partial(&self) -> bool {
         false
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        self.0.uncons_tokens_while(f)
+    }
 }
 
 impl<S> RangeStreamOnce for CompleteStream<S>
@@ -966,6 +1076,14 @@ where
     fn is_partial(&self) -> bool {
         self.1
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        self.0.uncons_tokens_while(f)
+    }
 }
 
 impl<S> RangeStreamOnce for MaybePartialStream<S>
@@ -1042,6 +1160,15 @@ where
             None => Err(UnexpectedParse::Eoi),
         }
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(&'a T) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        slice_uncons_while_ref(&mut self.0, 0, f);
+        Some(Ok(()))
+    }
 }
 
 fn slice_uncons_while_ref<'a, T, F>(slice: &mut &'a [T], mut i: usize, mut f: F) -> &'a [T]
@@ -1131,6 +1258,157 @@ where
     }
 }
 
+/// Stream over a slice of tokens which are paired with the span of source they were lexed from.
+///
+/// The position of the stream is the start of the span of the next token (or the end of the last
+/// token once all tokens are consumed) so errors are reported at source positions instead of at
+/// token indices.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::stream::{easy, SpannedSliceStream};
+/// # fn main() {
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token {
+///     Ident(&'static str),
+///     Comma,
+/// }
+///
+/// let tokens = [
+///     (Token::Ident("a"), 0..1),
+///     (Token::Comma, 1..2),
+///     (Token::Comma, 3..4),
+/// ];
+/// let ident = || satisfy(|t: &Token| matches!(t, Token::Ident(_)));
+/// let mut parser = sep_by::<Vec<_>, _, _, _>(ident(), token(&Token::Comma)).skip(eof());
+///
+/// let err = parser
+///     .easy_parse(SpannedSliceStream::new(&tokens[..]))
+///     .err()
+///     .unwrap();
+/// assert_eq!(err.position, 3);
+/// # }
+/// ```
+pub struct SpannedSliceStream<'a, T, P> {
+    tokens: &'a [(T, ops::Range<P>)],
+    end: P,
+}
+
+impl<'a, T, P> SpannedSliceStream<'a, T, P>
+where
+    P: Clone + Default,
+{
+    /// Creates a stream over `tokens`.
+    pub fn new(tokens: &'a [(T, ops::Range<P>)]) -> Self {
+        let end = tokens
+            .last()
+            .map_or_else(P::default, |(_, span)| span.end.clone());
+        SpannedSliceStream { tokens, end }
+    }
+}
+
+impl<'a, T, P> SpannedSliceStream<'a, T, P> {
+    /// Returns the tokens which have not been consumed yet.
+    pub fn tokens(&self) -> &'a [(T, ops::Range<P>)] {
+        self.tokens
+    }
+}
+
+impl<'a, T, P> Clone for SpannedSliceStream<'a, T, P>
+where
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        SpannedSliceStream {
+            tokens: self.tokens,
+            end: self.end.clone(),
+        }
+    }
+}
+
+impl<'a, T, P> fmt::Debug for SpannedSliceStream<'a, T, P>
+where
+    T: fmt::Debug,
+    P: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpannedSliceStream")
+            .field("tokens", &self.tokens)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl<'a, T, P> Positioned for SpannedSliceStream<'a, T, P>
+where
+    T: PartialEq + 'a,
+    P: Clone + Ord + Default,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.tokens
+            .first()
+            .map_or_else(|| self.end.clone(), |(_, span)| span.start.clone())
+    }
+}
+
+impl<'a, T, P> StreamOnce for SpannedSliceStream<'a, T, P>
+where
+    T: PartialEq + 'a,
+    P: Clone + Ord + Default,
+{
+    type Token = &'a T;
+    type Range = &'a [(T, ops::Range<P>)];
+    type Position = P;
+    type Error = UnexpectedParse;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<&'a T, StreamErrorFor<Self>> {
+        match self.tokens.split_first() {
+            Some(((first, _), rest)) => {
+                self.tokens = rest;
+                Ok(first)
+            }
+            None => Err(UnexpectedParse::Eoi),
+        }
+    }
+}
+
+impl<'a, T, P> RangeStreamOnce for SpannedSliceStream<'a, T, P>
+where
+    T: PartialEq + 'a,
+    P: Clone + Ord + Default,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        if size <= self.tokens.len() {
+            let (range, rest) = self.tokens.split_at(size);
+            self.tokens = rest;
+            Ok(range)
+        } else {
+            Err(UnexpectedParse::Eoi)
+        }
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, mut f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        Ok(slice_uncons_while_ref(&mut self.tokens, 0, |(t, _)| f(t)))
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self) -> usize {
+        end.tokens.len() - self.tokens.len()
+    }
+
+    fn range(&self) -> Self::Range {
+        self.tokens
+    }
+}
+
 /// Wrapper around iterators which allows them to be treated as a stream.
 /// Returned by [`from_iter`].
 ///
@@ -1182,6 +1460,134 @@ where
     }
 }
 
+/// Stream over an iterator of tokens paired with the span (as byte offsets) of source they were
+/// lexed from, such as the tokens of an external lexer.
+///
+/// The position of the stream is the start of the span of the next token (or the end of the last
+/// token once the iterator is exhausted). Like [`IteratorStream`] it can not be reset on its own so
+/// it needs to be wrapped in a [`buffered::Stream`] to be parsed.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::stream::{buffered, easy, SpannedIteratorStream};
+/// # fn main() {
+/// let tokens = vec![("let", 0..3), ("x", 4..5), ("=", 6..7), ("=", 8..9)];
+/// let input = buffered::Stream::new(
+///     easy::Stream(SpannedIteratorStream::new(tokens.into_iter())),
+///     1,
+/// );
+/// let result = (token("let"), any(), token("="), token("1")).parse(input);
+/// assert_eq!(result.err().map(|err| err.position), Some(8));
+/// # }
+/// ```
+///
+/// [`IteratorStream`]: struct.IteratorStream.html
+/// [`buffered::Stream`]: buffered/struct.Stream.html
+#[derive(Clone, Debug)]
+pub struct SpannedIteratorStream<I, T> {
+    iter: I,
+    next: Option<(T, ops::Range<usize>)>,
+    end: usize,
+}
+
+impl<I, T> SpannedIteratorStream<I, T>
+where
+    I: Iterator<Item = (T, ops::Range<usize>)>,
+{
+    /// Creates a stream over the tokens of `iter`.
+    pub fn new(mut iter: I) -> Self {
+        let next = iter.next();
+        SpannedIteratorStream { iter, next, end: 0 }
+    }
+}
+
+impl<I, T> Positioned for SpannedIteratorStream<I, T>
+where
+    I: Iterator<Item = (T, ops::Range<usize>)>,
+    T: Clone,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.next.as_ref().map_or(self.end, |(_, span)| span.start)
+    }
+}
+
+impl<I, T> StreamOnce for SpannedIteratorStream<I, T>
+where
+    I: Iterator<Item = (T, ops::Range<usize>)>,
+    T: Clone,
+{
+    type Token = T;
+    type Range = T;
+    type Position = usize;
+    type Error = UnexpectedParse;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<T, StreamErrorFor<Self>> {
+        match self.next.take() {
+            Some((token, span)) => {
+                self.end = span.end;
+                self.next = self.iter.next();
+                Ok(token)
+            }
+            None => Err(UnexpectedParse::Eoi),
+        }
+    }
+}
+
+/// Creates a stream over the tokens of a [`logos`] lexer, with positions at the start of the span
+/// of each token, `easy` errors and a buffer of `lookahead` tokens for backtracking.
+///
+/// ```
+/// # extern crate combine;
+/// # extern crate logos_012 as logos;
+/// # use combine::*;
+/// # use combine::stream::{easy, from_logos};
+/// # use logos::Logos;
+/// # fn main() {
+/// #[derive(Logos, Clone, Copy, Debug, PartialEq)]
+/// enum Token {
+///     #[token("(")]
+///     Open,
+///     #[token(")")]
+///     Close,
+///     #[regex("[a-z]+")]
+///     Word,
+///     #[error]
+///     #[regex(r"\s+", logos::skip)]
+///     Error,
+/// }
+///
+/// let words = || {
+///     let word = token(Token::Word);
+///     between(token(Token::Open), token(Token::Close), many::<Vec<_>, _, _>(word))
+/// };
+/// let result = words().parse(from_logos(Token::lexer("(a b c)"), 1));
+/// assert_eq!(result.map(|t| t.0.len()), Ok(3));
+///
+/// let err = words().parse(from_logos(Token::lexer("(a b ("), 1)).err().unwrap();
+/// assert_eq!(err.position, 5);
+/// assert!(err.errors.contains(&easy::Error::Unexpected(easy::Info::Token(Token::Open))));
+/// # }
+/// ```
+///
+/// [`logos`]: https://docs.rs/logos
+#[cfg(feature = "logos")]
+#[cfg_attr(docsrs, doc(cfg(feature = "logos")))]
+pub fn from_logos<'s, T>(
+    lexer: logos_012::Lexer<'s, T>,
+    lookahead: usize,
+) -> buffered::Stream<easy::Stream<SpannedIteratorStream<logos_012::SpannedIter<'s, T>, T>>>
+where
+    T: logos_012::Logos<'s> + Clone + PartialEq,
+{
+    buffered::Stream::new(
+        easy::Stream(SpannedIteratorStream::new(lexer.spanned())),
+        lookahead,
+    )
+}
+
 /// Newtype around a pointer offset into a slice stream (`&[T]`/`&str`).
 pub struct PointerOffset<T: ?Sized>(pub usize, PhantomData<T>);
 
@@ -1423,11 +1829,10 @@ macro_rules! decode {
 #[cfg_attr(docsrs, doc(cfg(feature = "futures-io-03")))]
 #[macro_export]
 macro_rules! decode_futures_03 {
-    ($decoder: expr, $read: expr, $parser: expr) => {
-        $crate::decode_futures_03!($decoder, $read, $parser, |x| x $(,)?)
+    ($decoder: expr, $read: expr, $parser: expr $(,)?) => {
+        $crate::decode_futures_03!($decoder, $read, $parser, |input, _position| input)
     };
 
-
     ($decoder: expr, $read: expr, $parser: expr, $input_stream: expr $(,)?) => {
         $crate::decode_futures_03!($decoder, $read, $parser, $input_stream, |x| x)
     };
@@ -1573,6 +1978,116 @@ macro_rules! decode_tokio_02 {
     };
 }
 
+/// Parses an entire `futures::io::AsyncRead` instance once, returning a future which resolves to
+/// the output of `$parser` or the error which stopped it.
+///
+/// Unlike [`decode_futures_03!`] no [`Decoder`] needs to be set up as the future owns its own. Any
+/// input remaining after `$parser` succeeds is discarded and, as the buffer is dropped along with
+/// the future, the error type of the input stream may not borrow from the input.
+///
+/// This is defined as a macro for the same reason as [`decode_futures_03!`], the parser must be
+/// constructed inside the macro call.
+///
+/// ```
+/// use async_std::task;
+///
+/// use combine::{parse_futures_03, satisfy, skip_many1, many1, sep_end_by, Parser};
+///
+/// fn main() {
+///     task::block_on(main_());
+/// }
+///
+/// async fn main_() {
+///     let read = &b"hello async world\n"[..];
+///     let is_whitespace = |b: u8| b == b' ' || b == b'\r' || b == b'\n';
+///     let result = parse_futures_03!(
+///         read,
+///         {
+///             let word = many1(satisfy(|b| !is_whitespace(b)));
+///             sep_end_by(word, skip_many1(satisfy(is_whitespace))).map(|words: Vec<Vec<u8>>| words.len())
+///         },
+///     ).await;
+///     assert!(matches!(result, Ok(3)));
+/// }
+/// ```
+///
+/// [`decode_futures_03!`]: macro.decode_futures_03.html
+/// [`Decoder`]: stream/decoder/struct.Decoder.html
+#[cfg(feature = "futures-io-03")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io-03")))]
+#[macro_export]
+macro_rules! parse_futures_03 {
+    ($read: expr, $parser: expr $(,)?) => {
+        $crate::parse_futures_03!(
+            $read,
+            $parser,
+            |input, _position: $crate::stream::PointerOffset<[u8]>| input
+        )
+    };
+
+    ($read: expr, $parser: expr, $input_stream: expr $(,)?) => {
+        async move {
+            let mut read = $read;
+            let mut decoder = $crate::stream::Decoder::new();
+            $crate::decode_futures_03!(decoder, &mut read, $parser, $input_stream)
+        }
+    };
+}
+
+/// Parses an entire `tokio::io::AsyncRead` instance once, returning a future which resolves to
+/// the output of `$parser` or the error which stopped it.
+///
+/// Unlike [`decode_tokio_02!`] no [`Decoder`] needs to be set up as the future owns its own. Any
+/// input remaining after `$parser` succeeds is discarded and, as the buffer is dropped along with
+/// the future, the error type of the input stream may not borrow from the input.
+///
+/// This is defined as a macro for the same reason as [`decode_tokio_02!`], the parser must be
+/// constructed inside the macro call.
+///
+/// ```
+/// # use tokio_02_dep as tokio;
+/// use tokio::fs::File;
+///
+/// use combine::{parse_tokio_02, satisfy, skip_many1, many1, sep_end_by, Parser};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let read = File::open("README.md").await.unwrap();
+///     let is_whitespace = |b: u8| b == b' ' || b == b'\r' || b == b'\n';
+///     let result = parse_tokio_02!(
+///         read,
+///         {
+///             let word = many1(satisfy(|b| !is_whitespace(b)));
+///             sep_end_by(word, skip_many1(satisfy(is_whitespace))).map(|words: Vec<Vec<u8>>| words.len())
+///         },
+///     ).await;
+///     assert!(matches!(result, Ok(819)));
+/// }
+/// ```
+///
+/// [`decode_tokio_02!`]: macro.decode_tokio_02.html
+/// [`Decoder`]: stream/decoder/struct.Decoder.html
+#[cfg(feature = "tokio-02")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-02")))]
+#[macro_export]
+macro_rules! parse_tokio_02 {
+    ($read: expr, $parser: expr $(,)?) => {
+        $crate::parse_tokio_02!(
+            $read,
+            $parser,
+            |input, _position: $crate::stream::PointerOffset<[u8]>| input
+        )
+    };
+
+    ($read: expr, $parser: expr, $input_stream: expr $(,)?) => {
+        async move {
+            let mut read = $read;
+            let mut decoder = $crate::stream::Decoder::new();
+            $crate::decode_tokio_02!(decoder, &mut read, $parser, $input_stream)
+        }
+    };
+}
+
 #[doc(hidden)]
 pub fn call_with2<F, A, B, R>(a: A, b: B, f: F) -> R
 where
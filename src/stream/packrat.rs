@@ -0,0 +1,229 @@
+//! Stream wrapper which stores a memo table for packrat parsing.
+//!
+//! Wrapping a stream in [`Stream`][] lets [`left_rec`][] remember the results of rules at each
+//! position, which is what makes it possible to parse left-recursive grammars. It also holds the
+//! results cached by [`memo`][].
+//!
+//! [`Stream`]: struct.Stream.html
+//! [`left_rec`]: ../../parser/packrat/fn.left_rec.html
+//! [`memo`]: ../../parser/combinator/fn.memo.html
+
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    fmt,
+    rc::Rc,
+};
+
+use crate::{
+    error::ParseResult,
+    stream::{Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
+};
+
+#[derive(Clone)]
+pub(crate) struct Memo<C> {
+    pub(crate) value: Rc<dyn Any>,
+    pub(crate) end: C,
+    pub(crate) committed: bool,
+}
+
+// `None` records that the rule failed (or is being grown from a failing seed) at that position.
+// Rules are keyed by their output type as well so rules which share a name can't see each other's
+// values.
+type MemoTable<P, C> = BTreeMap<(&'static str, TypeId, P), Option<Memo<C>>>;
+
+// Results of `memo` parsers, keyed by the id and output type of the parser.
+type CacheTable<P, C> = BTreeMap<(usize, TypeId, P), Option<Memo<C>>>;
+
+/// Stream wrapper which memoizes the results of left-recursive rules and `memo` parsers by
+/// position.
+///
+/// The memo table is only valid for the input it was built from, so a new `Stream` should be
+/// created for each parse.
+pub struct Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    pub stream: S,
+    memo: MemoTable<S::Position, S::Checkpoint>,
+    cache: CacheTable<S::Position, S::Checkpoint>,
+}
+
+impl<S> Clone for Stream<S>
+where
+    S: ResetStream + Positioned + Clone,
+{
+    fn clone(&self) -> Self {
+        Stream {
+            stream: self.stream.clone(),
+            memo: self.memo.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Stream<S>
+where
+    S: ResetStream + Positioned + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream")
+            .field("stream", &self.stream)
+            .field("memo", &self.memo.len())
+            .field("cache", &self.cache.len())
+            .finish()
+    }
+}
+
+impl<S> From<S> for Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    fn from(stream: S) -> Self {
+        Stream::new(stream)
+    }
+}
+
+impl<S> Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    /// Wraps `stream` with an empty memo table.
+    pub fn new(stream: S) -> Self {
+        Stream {
+            stream,
+            memo: BTreeMap::new(),
+            cache: BTreeMap::new(),
+        }
+    }
+
+    /// Removes all memoized results.
+    pub fn clear(&mut self) {
+        self.memo.clear();
+        self.cache.clear();
+    }
+
+    pub(crate) fn memo<T>(
+        &mut self,
+        rule: &'static str,
+        position: S::Position,
+    ) -> Option<&Option<Memo<S::Checkpoint>>>
+    where
+        T: Any,
+    {
+        self.memo.get(&(rule, TypeId::of::<T>(), position))
+    }
+
+    pub(crate) fn insert_memo<T>(
+        &mut self,
+        rule: &'static str,
+        position: S::Position,
+        memo: Option<Memo<S::Checkpoint>>,
+    ) where
+        T: Any,
+    {
+        self.memo.insert((rule, TypeId::of::<T>(), position), memo);
+    }
+
+    pub(crate) fn cached<T>(
+        &mut self,
+        id: usize,
+        position: S::Position,
+    ) -> Option<&Option<Memo<S::Checkpoint>>>
+    where
+        T: Any,
+    {
+        self.cache.get(&(id, TypeId::of::<T>(), position))
+    }
+
+    pub(crate) fn insert_cached<T>(
+        &mut self,
+        id: usize,
+        position: S::Position,
+        memo: Option<Memo<S::Checkpoint>>,
+    ) where
+        T: Any,
+    {
+        self.cache.insert((id, TypeId::of::<T>(), position), memo);
+    }
+}
+
+impl<S> Positioned for Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.stream.position()
+    }
+}
+
+impl<S> ResetStream for Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    type Checkpoint = S::Checkpoint;
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.stream.checkpoint()
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), Self::Error> {
+        self.stream.reset(checkpoint)
+    }
+}
+
+impl<S> StreamOnce for Stream<S>
+where
+    S: ResetStream + Positioned,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<S::Token, StreamErrorFor<Self>> {
+        self.stream.uncons()
+    }
+
+    fn is_partial(&self) -> bool {
+        self.stream.is_partial()
+    }
+}
+
+impl<S> RangeStreamOnce for Stream<S>
+where
+    S: ResetStream + Positioned + RangeStreamOnce,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        self.stream.uncons_range(size)
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.stream.uncons_while(f)
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.stream.uncons_while1(f)
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.stream.distance(end)
+    }
+
+    fn range(&self) -> Self::Range {
+        self.stream.range()
+    }
+}
@@ -0,0 +1,113 @@
+//! Parsing of record based formats on multiple threads.
+//!
+//! Formats such as newline delimited logs consist of records which can be parsed independently of
+//! each other. [`parse_parallel`][] splits such an input into chunks which end on a record boundary
+//! and parses the chunks on the `rayon` thread pool.
+//!
+//! [`parse_parallel`]: fn.parse_parallel.html
+
+use rayon_1::prelude::*;
+
+use crate::{
+    lib::iter,
+    parser::{repeat::many, token::eof},
+    stream::{input_at_eof, RangeStream, ResetStream, StreamOnce},
+    Parser,
+};
+
+/// Returns a splitter for [`parse_parallel`][] which ends each chunk after the first `delimiter`
+/// found once the chunk is at least `min_len` tokens long.
+///
+/// [`parse_parallel`]: fn.parse_parallel.html
+pub fn split_after<Input>(
+    delimiter: Input::Token,
+    min_len: usize,
+) -> impl FnMut(&mut Input) -> Option<Input::Range>
+where
+    Input: RangeStream,
+    Input::Token: PartialEq,
+{
+    move |input| {
+        if input_at_eof(input) {
+            return None;
+        }
+        let start = input.checkpoint();
+        let mut len = 0;
+        input
+            .uncons_while(|_| {
+                len += 1;
+                len <= min_len
+            })
+            .ok()?;
+        input.uncons_while(|t| t != delimiter).ok()?;
+        let _ = input.uncons();
+
+        let distance = input.distance(&start);
+        input.reset(start).ok()?;
+        input.uncons_range(distance).ok()
+    }
+}
+
+/// Parses `input` as a sequence of records, splitting it into chunks with `splitter` and parsing
+/// each chunk with repeated uses of a parser returned by `record_parser` on the `rayon` thread
+/// pool.
+///
+/// `splitter` is called with the input which has not been split yet and must return the next
+/// chunk, or `None` once the input is exhausted. Every chunk must end on a record boundary, see
+/// [`split_after`][] for a splitter for delimited records.
+///
+/// The values of all records are returned in input order. If any chunk fails to parse the error of
+/// the first such chunk is returned. As each chunk is a slice of `input` the positions of
+/// pointer based streams such as `&str`, `&[u8]` and `easy::Stream` stay correct and can be
+/// translated relative to the entire input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::stream::{easy, parallel::{parse_parallel, split_after}};
+/// # fn main() {
+/// let input = "1\n22\n333\n4444\n";
+/// let record = || many1::<String, _, _>(digit()).skip(char('\n')).map(|s| s.len());
+///
+/// let result = parse_parallel(easy::Stream(input), split_after('\n', 4), record);
+/// assert_eq!(result, Ok(vec![1, 2, 3, 4]));
+///
+/// let input = "1\n22\n3a3\n4444\n";
+/// let err = parse_parallel(easy::Stream(input), split_after('\n', 4), record).unwrap_err();
+/// assert_eq!(err.position.translate_position(input), 6);
+/// # }
+/// ```
+///
+/// [`split_after`]: fn.split_after.html
+pub fn parse_parallel<Input, S, F, P>(
+    mut input: Input,
+    mut splitter: S,
+    record_parser: F,
+) -> Result<Vec<P::Output>, <Input as StreamOnce>::Error>
+where
+    Input: RangeStream + From<<Input as StreamOnce>::Range> + Send,
+    Input::Range: Send,
+    Input::Error: Send,
+    S: FnMut(&mut Input) -> Option<Input::Range>,
+    F: Fn() -> P + Sync,
+    P: Parser<Input>,
+    P::Output: Send,
+{
+    let chunks: Vec<Input::Range> = iter::from_fn(|| splitter(&mut input)).collect();
+    let results: Vec<Result<Vec<P::Output>, Input::Error>> = chunks
+        .into_par_iter()
+        .map(|chunk| {
+            many(record_parser())
+                .skip(eof())
+                .parse(Input::from(chunk))
+                .map(|(values, _)| values)
+        })
+        .collect();
+
+    let mut values = Vec::new();
+    for result in results {
+        values.extend(result?);
+    }
+    Ok(values)
+}
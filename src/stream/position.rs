@@ -149,6 +149,22 @@ where
     fn is_partial(&self) -> bool {
         self.input.is_partial()
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        let positioner = &mut self.positioner;
+        self.input.uncons_tokens_while(&mut |c| {
+            if f(c.clone()) {
+                positioner.update(&c);
+                true
+            } else {
+                false
+            }
+        })
+    }
 }
 
 impl<Item, T> Positioner<Item> for &'_ mut T
@@ -247,6 +263,7 @@ where
 
 /// Struct which represents a position in a source file.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourcePosition {
     /// Current line of the input
     pub line: i32,
@@ -272,6 +289,28 @@ impl SourcePosition {
     }
 }
 
+/// Positions which know the line they are at.
+pub trait Line {
+    fn line(&self) -> i32;
+}
+
+/// Positions which know the column they are at.
+pub trait Column {
+    fn column(&self) -> i32;
+}
+
+impl Line for SourcePosition {
+    fn line(&self) -> i32 {
+        self.line
+    }
+}
+
+impl Column for SourcePosition {
+    fn column(&self) -> i32 {
+        self.column
+    }
+}
+
 impl Positioner<char> for SourcePosition {
     type Position = SourcePosition;
     type Checkpoint = Self;
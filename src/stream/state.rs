@@ -3,6 +3,9 @@ use crate::{
     stream::{Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
 };
 
+#[cfg(feature = "std")]
+use crate::lib::collections::HashMap;
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct Stream<S, U> {
     pub stream: S,
@@ -53,6 +56,14 @@ where
     fn is_partial(&self) -> bool {
         self.stream.is_partial()
     }
+
+    #[inline]
+    fn uncons_tokens_while(
+        &mut self,
+        f: &mut dyn FnMut(Self::Token) -> bool,
+    ) -> Option<Result<(), StreamErrorFor<Self>>> {
+        self.stream.uncons_tokens_while(f)
+    }
 }
 
 impl<S, U> RangeStreamOnce for Stream<S, U>
@@ -89,3 +100,76 @@ where
         self.stream.range()
     }
 }
+
+/// Maps strings to symbols so that a parser can return a cheap symbol for each occurrence of a
+/// string instead of allocating a new `String` every time.
+///
+/// Used as the `state` of a [`Stream`] together with [`Parser::interned`].
+///
+/// [`Stream`]: struct.Stream.html
+/// [`Parser::interned`]: ../../trait.Parser.html#method.interned
+pub trait Interner {
+    /// The symbol which identifies an interned string.
+    type Symbol;
+
+    /// Returns the symbol for `value`, adding `value` to the interner if it has not been seen
+    /// before.
+    fn intern(&mut self, value: &str) -> Self::Symbol;
+}
+
+impl<I> Interner for &mut I
+where
+    I: Interner + ?Sized,
+{
+    type Symbol = I::Symbol;
+
+    fn intern(&mut self, value: &str) -> Self::Symbol {
+        (**self).intern(value)
+    }
+}
+
+/// Simple `Interner` which assigns increasing `usize` symbols to strings in the order they are
+/// first seen.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+pub struct StringInterner {
+    symbols: HashMap<String, usize>,
+    strings: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl StringInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the string which `symbol` was created from.
+    pub fn resolve(&self, symbol: usize) -> Option<&str> {
+        self.strings.get(symbol).map(|s| &s[..])
+    }
+
+    /// Returns the number of distinct strings that have been interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Interner for StringInterner {
+    type Symbol = usize;
+
+    fn intern(&mut self, value: &str) -> usize {
+        if let Some(&symbol) = self.symbols.get(value) {
+            return symbol;
+        }
+        let symbol = self.strings.len();
+        self.strings.push(value.to_string());
+        self.symbols.insert(value.to_string(), symbol);
+        symbol
+    }
+}
@@ -0,0 +1,308 @@
+//! Helpers for testing parsers, in unit tests as well as property based tests and fuzzers.
+//!
+//! Besides assertions for the result of a parse this module checks that parsing an input in chunks,
+//! as a decoder does when the input arrives over the network, gives the same result as parsing the
+//! entire input at once. Parsers which keep their own partial state (or which combine parsers that
+//! do not resume correctly) often only fail that way once an unlucky chunking happens in
+//! production.
+//!
+//! ```
+//! # extern crate combine;
+//! # use combine::*;
+//! # use combine::parser::range::{range, take_while1};
+//! # use combine::test::{assert_parses_prefix, assert_partial_equivalent};
+//! # fn main() {
+//! fn header<'a, Input>() -> impl Parser<Input, Output = (&'a [u8], &'a [u8])>
+//! where
+//!     Input: RangeStream<Token = u8, Range = &'a [u8]>,
+//! {
+//!     (
+//!         take_while1(|b: u8| b != b':'),
+//!         range(&b": "[..]),
+//!         take_while1(|b: u8| b != b'\n'),
+//!         token(b'\n'),
+//!     )
+//!         .map(|(name, _, value, _)| (name, value))
+//! }
+//!
+//! assert_parses_prefix(header(), &b"a: b\nc"[..], (&b"a"[..], &b"b"[..]), &b"c"[..]);
+//! assert_partial_equivalent(header, &b"Host: example.com\n"[..]);
+//! # }
+//! ```
+
+use crate::{
+    lib::{fmt, ops::Range},
+    stream::{decode, input_at_eof, MaybePartialStream, RangeStream, StreamOnce},
+    Parser, Stream,
+};
+
+/// Asserts that `parser` parses all of `input`, returning `expected`.
+#[track_caller]
+pub fn assert_parses<Input, P>(mut parser: P, input: Input, expected: P::Output)
+where
+    Input: Stream + fmt::Debug,
+    Input::Error: fmt::Debug,
+    P: Parser<Input>,
+    P::Output: PartialEq + fmt::Debug,
+{
+    match parser.parse(input) {
+        Ok((output, mut remaining)) => {
+            assert_eq!(output, expected);
+            assert!(
+                input_at_eof(&mut remaining),
+                "expected the entire input to be parsed but `{:?}` remains",
+                remaining
+            );
+        }
+        Err(err) => panic!("expected the parse to succeed but it failed with {:?}", err),
+    }
+}
+
+/// Asserts that `parser` parses a prefix of `input`, returning `expected` and leaving `remaining`.
+#[track_caller]
+pub fn assert_parses_prefix<Input, P>(
+    mut parser: P,
+    input: Input,
+    expected: P::Output,
+    remaining: Input,
+) where
+    Input: Stream + PartialEq + fmt::Debug,
+    Input::Error: fmt::Debug,
+    P: Parser<Input>,
+    P::Output: PartialEq + fmt::Debug,
+{
+    match parser.parse(input) {
+        Ok((output, rest)) => {
+            assert_eq!(output, expected);
+            assert_eq!(rest, remaining);
+        }
+        Err(err) => panic!("expected the parse to succeed but it failed with {:?}", err),
+    }
+}
+
+/// Inputs which can be split into chunks by [`check_partial`][].
+///
+/// [`check_partial`]: fn.check_partial.html
+pub trait Chunkable {
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the input is empty (`Chunkable::len() == 0`)
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the input may be split at `index`.
+    fn is_boundary(&self, index: usize) -> bool;
+
+    fn slice(&self, range: Range<usize>) -> &Self;
+}
+
+impl Chunkable for str {
+    fn len(&self) -> usize {
+        str::len(self)
+    }
+
+    fn is_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+    }
+
+    fn slice(&self, range: Range<usize>) -> &Self {
+        &self[range]
+    }
+}
+
+impl<T> Chunkable for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn is_boundary(&self, _index: usize) -> bool {
+        true
+    }
+
+    fn slice(&self, range: Range<usize>) -> &Self {
+        &self[range]
+    }
+}
+
+/// The result of parsing an input, along with how much of the input was consumed.
+pub type Outcome<O, E> = Result<(O, usize), E>;
+
+/// A chunking for which parsing in chunks disagreed with parsing the entire input.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch<O, E> {
+    /// The indices the input was split at.
+    pub splits: Vec<usize>,
+    pub whole: Outcome<O, E>,
+    pub chunked: Outcome<O, E>,
+}
+
+impl<O, E> fmt::Display for Mismatch<O, E>
+where
+    O: fmt::Debug,
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parsing the input split at {:?} returned {:?} but parsing it at once returned {:?}",
+            self.splits, self.chunked, self.whole
+        )
+    }
+}
+
+type ErrorOf<'a, I> = <MaybePartialStream<&'a I> as StreamOnce>::Error;
+
+fn parse_whole<'a, I, P>(mut parser: P, input: &'a I) -> Outcome<P::Output, ErrorOf<'a, I>>
+where
+    I: ?Sized + Chunkable,
+    &'a I: RangeStream,
+    P: Parser<MaybePartialStream<&'a I>>,
+{
+    let mut stream = MaybePartialStream(input, false);
+    let output = parser.parse_with_state(&mut stream, &mut Default::default())?;
+    Ok((output, input.len() - stream.0.len()))
+}
+
+fn parse_chunked<'a, I, P>(
+    mut parser: P,
+    input: &'a I,
+    splits: &[usize],
+) -> Outcome<P::Output, ErrorOf<'a, I>>
+where
+    I: ?Sized + Chunkable,
+    &'a I: RangeStream,
+    P: Parser<MaybePartialStream<&'a I>>,
+{
+    let mut state = Default::default();
+    let mut offset = 0;
+    for end in splits.iter().cloned().chain(Some(input.len())) {
+        let end_of_input = end == input.len();
+        let mut stream = MaybePartialStream(input.slice(offset..end), !end_of_input);
+        match decode(&mut parser, &mut stream, &mut state)? {
+            (Some(output), consumed) => return Ok((output, offset + consumed)),
+            (None, consumed) => offset += consumed,
+        }
+    }
+    unreachable!("the last chunk is parsed as the end of input")
+}
+
+/// Checks that parsing `input` in chunks of the lengths in `chunk_lens` gives the same result as
+/// parsing it at once, using a new parser from `make_parser` for each parse.
+///
+/// Any `chunk_lens` is accepted: lengths which would split a character are extended to the next
+/// character and the lengths which extend past the end of the input are ignored. That makes it
+/// possible to pass lengths generated by `quickcheck`, `proptest` or a fuzzer directly, which then
+/// shrink any failure down to a minimal chunking.
+///
+/// The parses agree if they both succeed with the same output after consuming the same amount of
+/// input, or if they both fail. Errors are not compared since a parser which is resumed may report
+/// fewer expected tokens.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::test::check_partial;
+/// # fn main() {
+/// let number = || many1::<String, _, _>(digit());
+/// assert_eq!(check_partial(number, "123", &[1, 0, 1]), Ok(()));
+/// assert_eq!(check_partial(number, "12a", &[1, 99]), Ok(()));
+/// # }
+/// ```
+pub fn check_partial<'a, I, F, P>(
+    mut make_parser: F,
+    input: &'a I,
+    chunk_lens: &[usize],
+) -> Result<(), Mismatch<P::Output, ErrorOf<'a, I>>>
+where
+    I: ?Sized + Chunkable,
+    &'a I: RangeStream,
+    F: FnMut() -> P,
+    P: Parser<MaybePartialStream<&'a I>>,
+    P::Output: PartialEq,
+{
+    let mut splits = Vec::new();
+    let mut end: usize = 0;
+    for &len in chunk_lens {
+        end = match end.checked_add(len) {
+            Some(end) if end < input.len() => end,
+            _ => break,
+        };
+        while !input.is_boundary(end) {
+            end += 1;
+        }
+        if end < input.len() {
+            splits.push(end);
+        }
+    }
+    splits.dedup();
+
+    let whole = parse_whole(make_parser(), input);
+    let chunked = parse_chunked(make_parser(), input, &splits);
+    let agrees = match (&whole, &chunked) {
+        (Ok(whole), Ok(chunked)) => whole == chunked,
+        (Err(_), Err(_)) => true,
+        _ => false,
+    };
+    if agrees {
+        Ok(())
+    } else {
+        Err(Mismatch {
+            splits,
+            whole,
+            chunked,
+        })
+    }
+}
+
+/// Asserts that [`check_partial`][] succeeds for the chunkings of `input` which are most likely to
+/// expose bugs in partial parsing.
+///
+/// Every way of splitting `input` is checked for inputs of up to 12 tokens. Longer inputs are
+/// checked when split in two at every index, in three at every pair of indices for inputs of up to
+/// 64 tokens and when split into chunks of a single token.
+///
+/// [`check_partial`]: fn.check_partial.html
+#[track_caller]
+pub fn assert_partial_equivalent<'a, I, F, P>(mut make_parser: F, input: &'a I)
+where
+    I: ?Sized + Chunkable,
+    &'a I: RangeStream,
+    F: FnMut() -> P,
+    P: Parser<MaybePartialStream<&'a I>>,
+    P::Output: PartialEq + fmt::Debug,
+    ErrorOf<'a, I>: fmt::Debug,
+{
+    let len = input.len();
+    let mut check = |chunk_lens: &[usize]| {
+        if let Err(mismatch) = check_partial(&mut make_parser, input, chunk_lens) {
+            panic!("{}", mismatch);
+        }
+    };
+
+    if len <= 12 {
+        for mask in 0u32..1 << len.saturating_sub(1) {
+            let mut chunk_lens = Vec::new();
+            let mut last = 0;
+            for i in 1..len {
+                if mask & (1 << (i - 1)) != 0 {
+                    chunk_lens.push(i - last);
+                    last = i;
+                }
+            }
+            check(&chunk_lens);
+        }
+        return;
+    }
+
+    for i in 1..len {
+        check(&[i]);
+        if len <= 64 {
+            for j in 1..len - i {
+                check(&[i, j]);
+            }
+        }
+    }
+    check(&vec![1; len]);
+}
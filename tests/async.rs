@@ -16,16 +16,17 @@ use {
         parser::{
             byte::{num, take_until_bytes},
             char::{char, digit, letter, string},
-            choice::optional,
+            choice::{self, optional},
             combinator::{
                 any_partial_state, any_send_partial_state, attempt, from_str, no_partial,
                 recognize, AnyPartialState, AnySendPartialState,
             },
             range::{
-                self, range, recognize_with_value, take, take_fn, take_until_range, take_while,
-                take_while1,
+                self, length_prefix, line, number, range, recognize_with_value, take, take_fn,
+                take_until_item, take_until_range, take_until_range_caseless, take_until_unescaped,
+                take_while, take_while1, take_while_m_n,
             },
-            repeat,
+            repeat, sequence,
         },
         satisfy, sep_end_by, skip_many, skip_many1,
         stream::{easy, RangeStream, StreamErrorFor},
@@ -420,6 +421,27 @@ quickcheck! {
         assert_eq!(counter.get(), 26);
     }
 
+    fn take_while_m_n_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            |count: Rc<Cell<i32>>|
+                take_while_m_n(1, 3, move |c| { count.set(count.get() + 1); c != ',' })
+                    .map(String::from)
+                    .skip(optional(token(','))),
+            Rc<Cell<i32>>
+        }
+
+        let input = "1,123,4567,a,";
+
+        let counter = Rc::new(Cell::new(0));
+        let result = run_decoder(input, seq, TestParser(Default::default(), counter.clone()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(
+            result.unwrap(),
+            ["1", "123", "456", "7", "a"]
+        );
+    }
+
     fn take_while1_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
         impl_decoder!{ TestParser, String,
             |count: Rc<Cell<i32>>|
@@ -492,6 +514,75 @@ quickcheck! {
         assert_eq!(counter.get(), 3);
     }
 
+    fn take_until_max_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            |count: Rc<Cell<i32>>|
+                repeat::take_until_max(3, token(',').map(move |_| count.set(count.get() + 1))).skip(token(',')),
+            Rc<Cell<i32>>
+        }
+
+        let input = "123,456,789,";
+
+        let counter = Rc::new(Cell::new(0));
+        let result = run_decoder(input, seq, TestParser(Default::default(), counter.clone()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(
+            result.unwrap(),
+            ["123", "456", "789"]
+        );
+
+        assert_eq!(counter.get(), 3);
+    }
+
+    fn take_until_item_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            take_until_item(',').map(String::from).skip(token(','))
+        }
+
+        let input = "123,456,789,";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(
+            result.unwrap(),
+            ["123", "456", "789"]
+        );
+    }
+
+    fn line_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            line().map(String::from)
+        }
+
+        let input = "123\r\n456\n\n789\r0\n";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(
+            result.unwrap(),
+            ["123", "456", "", "789", "0"]
+        );
+    }
+
+    fn take_until_unescaped_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            take_until_unescaped('"', '\\').map(String::from).skip(token('"'))
+        }
+
+        let input = r#"a\"b"\\"\"""#;
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(
+            result.unwrap(),
+            [r#"a\"b"#, r#"\\"#, r#"\""#]
+        );
+    }
+
     fn take_until_range_committed(seq: PartialWithErrors<GenWouldBlock>) -> () {
         impl_decoder!{ TestParser, String,
             take_until_range("::").map(String::from).skip((token(':'), token(':')))
@@ -505,6 +596,73 @@ quickcheck! {
         assert_eq!(result.unwrap(), ["123", "456", "789"]);
     }
 
+    fn length_prefix_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            length_prefix(digit().map(|c| c.to_digit(10).unwrap() as usize)).map(String::from)
+        }
+
+        let input = "3abc2de0";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ["abc", "de", ""]);
+    }
+
+    fn then_partial_ref_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            many1(letter()).skip(char(':'))
+                .then_partial_ref(|name: &mut String| count_min_max(name.len(), name.len(), any()))
+                .map(|(name, body): (String, String)| format!("{}:{}", name, body))
+        }
+
+        let input = "ab:xyabc:123a:!";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ["ab:xy", "abc:123", "a:!"]);
+    }
+
+    fn number_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, u32,
+            number().skip(char(','))
+        }
+
+        let input = "123,4567,0,";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), [123, 4567, 0]);
+    }
+
+    fn delimited_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            sequence::delimited(char('('), char(')'), repeat::many(letter()))
+        }
+
+        let input = "(ab)(cde)()";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ["ab", "cde", ""]);
+    }
+
+    fn take_until_range_caseless_committed(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            take_until_range_caseless("ab").map(String::from).skip(take(2))
+        }
+
+        let input = "123AB456ab789aB";
+
+        let result = run_decoder(input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ["123", "456", "789"]);
+    }
+
     fn any_send_partial_state_do_not_forget_state(sizes: Vec<usize>, seq: PartialWithErrors<GenWouldBlock>) -> () {
         impl_decoder!{ TestParser, usize,
             any_send_partial_state(content_length().map(|bytes| bytes.len()))
@@ -592,6 +750,107 @@ quickcheck! {
         assert_eq!(result.unwrap(), ints);
     }
 
+    fn varint_test(ints: Vec<i64>, seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_byte_decoder!{ TestParser, i64,
+            num::signed_varint()
+        }
+
+        let input: Vec<u8> = ints.iter()
+            .flat_map(|&i| {
+                let mut v = Vec::new();
+                let mut i = i;
+                loop {
+                    let byte = (i & 0x7f) as u8;
+                    i >>= 7;
+                    if (i == 0 && byte & 0x40 == 0) || (i == -1 && byte & 0x40 != 0) {
+                        v.push(byte);
+                        break;
+                    }
+                    v.push(byte | 0x80);
+                }
+                v
+            })
+            .collect();
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ints);
+    }
+
+    fn fold_many_test(sizes: Vec<usize>, seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, usize,
+            repeat::fold_many(0, digit(), |sum, c: char| sum + c.to_digit(10).unwrap() as usize)
+                .skip(range("\r\n"))
+        }
+
+        let input : String = sizes
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}\r\n",
+                    ::std::iter::repeat('1').take(*s).collect::<String>()
+                )
+            })
+            .collect();
+
+        let result = run_decoder(input.as_bytes(), seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), sizes);
+    }
+
+    fn permutation_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            choice::permutation((
+                char('#').with(many1::<String, _, _>(digit())),
+                char('@').with(many1::<String, _, _>(letter())),
+            ))
+                .map(|(number, word)| format!("{}{}", number, word))
+                .skip(range("\r\n"))
+        }
+
+        let input = "#123@abc\r\n@de#45\r\n";
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), vec!["123abc".to_string(), "45de".to_string()]);
+    }
+
+    fn count_exact_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            repeat::count_exact::<_, _, 3>(many1::<String, _, _>(digit()).skip(char(',')))
+                .map(|[a, b, c]| format!("{}{}{}", a, b, c))
+                .skip(range("\r\n"))
+        }
+
+        let input = "1,22,333,\r\n4444,5,66,\r\n";
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), vec!["122333".to_string(), "4444566".to_string()]);
+    }
+
+    fn escaped_transform_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            repeat::escaped_transform::<String, _, _, _>(
+                take_while1(|c| c != '\\' && c != '\r'),
+                '\\',
+                choice::choice((token('n').map(|_| '\n'), token('\\'))),
+            )
+                .skip(range("\r\n"))
+        }
+
+        let input = "ab\\\\12\\nc\r\n\\nx\r\n";
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), vec!["ab\\12\nc".to_string(), "\nx".to_string()]);
+    }
+
     fn sep_end_by_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
         impl_decoder!{ TestParser, Vec<String>,
             repeat::sep_end_by((digit(), digit(), digit()).map(|(a, b, c)| vec![a, b, c].into_iter().collect()), no_partial(string("::")))
@@ -723,6 +982,99 @@ fn decode_async_std() {
     )
 }
 
+#[test]
+fn decode_async_std_bufferless() {
+    use combine::stream::buf_reader::BufReader;
+
+    quickcheck(
+        (|ops: PartialWithErrors<GenWouldBlock>| {
+            let buf = b"Content-Length: 5\r\n\r\nhello world";
+            async_std::task::block_on(async {
+                let mut read = BufReader::new(FuturesPartialAsyncRead::new(&buf[..], ops));
+                let mut decoder = combine::stream::Decoder::new_bufferless();
+                let len = combine::decode_futures_03!(
+                    decoder,
+                    &mut read,
+                    range(&b"Content-Length: "[..])
+                        .with(from_str(take_while1(|b: u8| b.is_ascii_digit())))
+                        .skip(range(&b"\r\n\r\n"[..])),
+                )
+                .map_err(|err| format!("{:?}", err))
+                .unwrap();
+                assert_eq!(len, 5);
+
+                // The rest of the input is read through the `futures::io` traits of `BufReader`
+                let mut body = vec![0; len];
+                read.read_exact(&mut body).await.unwrap();
+                assert_eq!(body, b"hello");
+
+                let mut rest = String::new();
+                read.read_to_string(&mut rest).await.unwrap();
+                assert_eq!(rest, " world");
+            })
+        }) as fn(_) -> _,
+    )
+}
+
+#[test]
+fn parse_async_std() {
+    quickcheck(
+        (|ops: PartialWithErrors<GenWouldBlock>| {
+            let buf = include_bytes!("../README.md");
+            async_std::task::block_on(async {
+                let read = FuturesPartialAsyncRead::new(&buf[..], ops);
+                let is_whitespace = |b: u8| b == b' ' || b == b'\r' || b == b'\n';
+                let result = combine::parse_futures_03!(read, {
+                    let word = many1(satisfy(|b| !is_whitespace(b)));
+                    sep_end_by(word, skip_many1(satisfy(is_whitespace)))
+                        .map(|words: Vec<Vec<u8>>| words.len())
+                })
+                .await;
+                assert!(matches!(result, Ok(WORDS_IN_README)));
+            })
+        }) as fn(_) -> _,
+    )
+}
+
+#[test]
+fn frames_async_std() {
+    quickcheck(
+        (|ops: PartialWithErrors<GenWouldBlock>| {
+            let buf = include_bytes!("../README.md");
+            async_std::task::block_on(async {
+                let read = FuturesPartialAsyncRead::new(&buf[..], ops);
+                let is_whitespace = |b: u8| b == b' ' || b == b'\r' || b == b'\n';
+                let frames = combine::stream::frames(
+                    |input, state| {
+                        let word = many1(satisfy(|b| !is_whitespace(b)));
+                        let skip_whitespace = skip_many(satisfy(is_whitespace));
+                        combine::stream::decode(
+                            (skip_whitespace, word).map(|(_, word): (_, Vec<u8>)| word),
+                            input,
+                            state,
+                        )
+                    },
+                    read,
+                );
+                futures::pin_mut!(frames);
+
+                let mut count = 0;
+                let mut errors = 0;
+                while let Some(word) = frames.next().await {
+                    match word {
+                        Ok(_) => count += 1,
+                        // The whitespace at the end of the file is not followed by a word
+                        Err(combine::stream::decoder::Error::Parse(_)) => errors += 1,
+                        Err(err) => panic!("{:?}", err),
+                    }
+                }
+                assert_eq!(count, WORDS_IN_README);
+                assert_eq!(errors, 1);
+            })
+        }) as fn(_) -> _,
+    )
+}
+
 #[tokio::main]
 async fn decode_loop() {
     use tokio::fs::File;
@@ -0,0 +1,43 @@
+use combine::{
+    many,
+    parser::char::{spaces, string},
+    EasyParser, Parser,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Parser)]
+enum Keyword {
+    #[token("in")]
+    In,
+    #[token("int")]
+    Int,
+    #[token("let")]
+    Let,
+}
+
+#[test]
+fn derive_parser_longest_first() {
+    let mut parser = many::<Vec<_>, _, _>(Keyword::parser().skip(spaces()));
+    assert_eq!(
+        parser.parse("let int in"),
+        Ok((vec![Keyword::Let, Keyword::Int, Keyword::In], ""))
+    );
+}
+
+#[test]
+fn derive_parser_display() {
+    assert_eq!(Keyword::Int.to_string(), "int");
+    assert_eq!(
+        Keyword::parser()
+            .map(|keyword| keyword.to_string())
+            .skip(string("!"))
+            .parse("let!"),
+        Ok(("let".to_string(), ""))
+    );
+}
+
+#[test]
+fn derive_parser_error() {
+    let err = Keyword::parser().easy_parse("x").unwrap_err();
+    assert_eq!(err.position.translate_position("x"), 0);
+    assert!(err.to_string().contains("`let`"), "{}", err);
+}
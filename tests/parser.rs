@@ -3,10 +3,13 @@ use combine::{
         byte::bytes_cmp,
         char::{digit, letter, string, string_cmp},
         choice::{choice, optional},
-        combinator::{attempt, no_partial, not_followed_by},
+        combinator::{attempt, look_ahead, no_partial, not_followed_by},
         error::unexpected,
         range::{self, range},
-        repeat::{count, count_min_max, many, sep_by, sep_end_by1, skip_until, take_until},
+        repeat::{
+            count, count_min_max, many, many1, sep_by, sep_end_by1, skip_until, take_until,
+            take_until_max,
+        },
         token::{any, eof, position, token, value, Token},
     },
     EasyParser, Parser,
@@ -25,6 +28,75 @@ fn tuple() {
     assert_eq!(parser.parse("1,2,z"), Ok((('1', ',', '2', ',', 'z'), "")));
 }
 
+#[test]
+fn tuple_20() {
+    let d = digit;
+    let mut parser = (
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+        d(),
+    )
+        .map(|(a, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, t)| (a, t));
+    assert_eq!(parser.parse("12345678901234567890!"), Ok((('1', '0'), "!")));
+}
+
+#[test]
+fn choice_tuple_32() {
+    let mut parser = choice((
+        token('a'),
+        token('b'),
+        token('c'),
+        token('d'),
+        token('e'),
+        token('f'),
+        token('g'),
+        token('h'),
+        token('i'),
+        token('j'),
+        token('k'),
+        token('l'),
+        token('m'),
+        token('n'),
+        token('o'),
+        token('p'),
+        token('q'),
+        token('r'),
+        token('s'),
+        token('t'),
+        token('u'),
+        token('v'),
+        token('w'),
+        token('x'),
+        token('y'),
+        token('z'),
+        token('0'),
+        token('1'),
+        token('2'),
+        token('3'),
+        token('4'),
+        token('5'),
+    ));
+    assert_eq!(parser.parse("5"), Ok(('5', "")));
+    assert!(parser.parse("6").is_err());
+}
+
 #[test]
 fn issue_99() {
     let result = any().map(|_| ()).or(eof()).parse("");
@@ -43,6 +115,31 @@ fn not_followed_by_does_not_consume_any_input() {
     assert!(parser.parse("aaa").is_err());
 }
 
+#[test]
+fn not_followed_by_any_output() {
+    let mut parser = digit().skip(not_followed_by(many1::<Vec<char>, _, _>(letter())));
+    assert_eq!(parser.parse("1 a"), Ok(('1', " a")));
+    assert!(parser.parse("1a").is_err());
+}
+
+combine::parser! {
+    fn keyword['a, 'b, Input](word: &'b str)(Input) -> impl &'a str
+    where [
+        Input: combine::RangeStream<Token = char, Range = &'a str>,
+        'b: 'a,
+    ]
+    {
+        range(word).skip(not_followed_by(letter()))
+    }
+}
+
+#[test]
+fn parser_macro_impl_lifetimes() {
+    let word = String::from("let");
+    assert_eq!(keyword(&word).parse("let x"), Ok(("let", " x")));
+    assert!(keyword(&word).parse("letter").is_err());
+}
+
 #[cfg(feature = "std")]
 mod tests_std {
 
@@ -440,6 +537,19 @@ mod tests_std {
         );
     }
 
+    #[test]
+    fn take_until_max_limit() {
+        let mut parser = take_until_max::<String, _, _>(2, attempt((char('a'), char('b'))));
+        assert_eq!(parser.parse("aaab"), Ok((String::from("aa"), "ab")));
+        assert!(parser.parse("aaaab").is_err());
+        assert_eq!(parser.parse("ab"), Ok((String::new(), "ab")));
+
+        let err = take_until_max::<String, _, _>(2, attempt((char('a'), char('b'))))
+            .easy_parse(position::Stream::new("aaaab"))
+            .unwrap_err();
+        assert_eq!(err.position, SourcePosition { line: 1, column: 3 });
+    }
+
     #[test]
     fn parser_macro_must_impl_parse_mode_issue_168() {
         assert_eq!(
@@ -667,4 +777,825 @@ mod tests_std {
             Err(vec![]),
         );
     }
+
+    #[test]
+    fn easy_error_kind() {
+        use combine::error::{ErrorKind, ParseError, StreamError};
+
+        let kind = |err: &Errors<char, &str, _>| ParseError::<char, &str, usize>::kind(err);
+
+        assert_eq!(kind(&Errors::end_of_input(0)), ErrorKind::Eof);
+        assert_eq!(
+            kind(&Errors::new(0, Error::Expected('a'.into()))),
+            ErrorKind::Unexpected
+        );
+        assert_eq!(
+            kind(&Errors::new(0, Error::Message("oops".into()))),
+            ErrorKind::Message
+        );
+        let io_err = std::io::Error::new(std::io::ErrorKind::Other, "io");
+        assert_eq!(kind(&Errors::new(0, Error::other(io_err))), ErrorKind::Io);
+        assert_eq!(
+            kind(&Errors::new(0, Error::other(std::fmt::Error))),
+            ErrorKind::Custom
+        );
+
+        let mut errors = Errors::new(0, Error::Message("oops".into()));
+        errors.add_error(Error::end_of_input());
+        assert_eq!(kind(&errors), ErrorKind::Eof);
+
+        let err = char('a').easy_parse("").unwrap_err();
+        assert_eq!(ParseError::<char, &str, _>::kind(&err), ErrorKind::Eof);
+    }
+
+    #[test]
+    fn expr_errors() {
+        use combine::parser::expr::{expr, Assoc};
+
+        let mut parser = expr(digit().map(|c| c.to_digit(10).unwrap() as i32))
+            .prefix(char('-'), 3, |x| -x)
+            .infix(char('+'), 1, Assoc::Left, |l, r| l + r)
+            .infix(char('*'), 2, Assoc::Left, |l, r| l * r);
+
+        assert_eq!(parser.easy_parse("-1+2*3x"), Ok((5, "x")));
+        assert_eq!(
+            parser.easy_parse("x").map_err(|err| err.errors),
+            Err(vec![
+                Error::Unexpected('x'.into()),
+                Error::Expected('-'.into()),
+                Error::Expected("digit".into()),
+            ])
+        );
+        assert_eq!(
+            parser.easy_parse("1+").map_err(|err| err.errors),
+            Err(vec![
+                Error::end_of_input(),
+                Error::Expected('-'.into()),
+                Error::Expected("digit".into()),
+            ])
+        );
+        assert_eq!(
+            parser.easy_parse("1+-x").map_err(|err| err.errors),
+            Err(vec![
+                Error::Unexpected('x'.into()),
+                Error::Expected('-'.into()),
+                Error::Expected("digit".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn left_rec_path() {
+        use combine::parser::{packrat::left_rec, repeat::many1};
+        use combine::{error::ParseError, parser, stream::packrat, Stream};
+
+        fn path<Input>() -> impl Parser<packrat::Stream<Input>, Output = Vec<String>>
+        where
+            Input: Stream<Token = char>,
+            Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+        {
+            let name = || many1(letter());
+            left_rec(
+                "path",
+                (
+                    parser(|input| path().parse_stream(input).into_result()),
+                    char('.'),
+                    name(),
+                )
+                    .map(|(mut path, _, name): (Vec<_>, _, String)| {
+                        path.push(name);
+                        path
+                    })
+                    .or(name().map(|name| vec![name])),
+            )
+        }
+
+        let result = path().parse(packrat::Stream::new("a.bc.d!"));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok((vec!["a".into(), "bc".into(), "d".into()], "!"))
+        );
+
+        let result = path().parse(packrat::Stream::new("a."));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok((vec!["a".into()], "."))
+        );
+
+        assert!(path().parse(packrat::Stream::new("!")).is_err());
+    }
+
+    #[test]
+    fn memo_caches_per_input() {
+        use combine::parser::{char::digit, combinator::memo, repeat::many1};
+        use combine::{parser, stream::packrat};
+        use std::cell::Cell;
+
+        let calls = Cell::new(0);
+        let number = memo(parser(|input| {
+            calls.set(calls.get() + 1);
+            many1::<String, _, _>(digit())
+                .parse_stream(input)
+                .into_result()
+        }));
+        let mut parser = attempt((number.clone(), char('%'))).or((number, char('!')));
+
+        let result = parser.parse(packrat::Stream::new("12!"));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok((("12".to_string(), '!'), ""))
+        );
+        assert_eq!(calls.get(), 1);
+
+        let result = parser.parse(packrat::Stream::new("345%"));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok((("345".to_string(), '%'), ""))
+        );
+        assert_eq!(calls.get(), 2);
+
+        // Failures are cached as well
+        assert!(parser.parse(packrat::Stream::new("x")).is_err());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn left_rec_rules_of_the_same_type() {
+        use combine::parser::{char::digit, packrat::left_rec, token::eof};
+        use combine::{error::ParseError, parser, stream::packrat, Stream};
+
+        fn chain<Input>(
+            name: &'static str,
+            op: char,
+        ) -> impl Parser<packrat::Stream<Input>, Output = String>
+        where
+            Input: Stream<Token = char>,
+            Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+        {
+            left_rec(
+                name,
+                (
+                    parser(move |input| chain(name, op).parse_stream(input).into_result()),
+                    char(op),
+                    digit(),
+                )
+                    .map(|(mut s, op, d): (String, _, _)| {
+                        s.push(op);
+                        s.push(d);
+                        s
+                    })
+                    .or(digit().map(|d| d.to_string())),
+            )
+        }
+
+        // Both rules memoize their result at the start of the input
+        let mut parser = attempt(chain("minus", '-').skip(eof())).or(chain("plus", '+'));
+        let result = parser.parse(packrat::Stream::new("1+2"));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok(("1+2".to_string(), ""))
+        );
+    }
+
+    #[test]
+    fn left_rec_rules_of_the_same_name_and_different_types() {
+        use combine::parser::{char::digit, packrat::left_rec, repeat::many1};
+        use combine::stream::packrat;
+
+        let mut parser =
+            attempt(left_rec("number", many1::<String, _, _>(digit())).skip(char('!')))
+                .or(left_rec("number", digit()).map(|d| d.to_string()));
+        let result = parser.parse(packrat::Stream::new("12"));
+        assert_eq!(
+            result.map(|(value, rest)| (value, rest.stream)),
+            Ok(("1".to_string(), "2"))
+        );
+    }
+
+    #[test]
+    fn token_by_error() {
+        use combine::parser::token::token_by;
+
+        let mut parser = token_by('a', |l: &char, r: &char| l.eq_ignore_ascii_case(r));
+        assert_eq!(parser.easy_parse("A"), Ok(('A', "")));
+        assert_eq!(
+            parser.easy_parse("b").map_err(|err| err.errors),
+            Err(vec![
+                Error::Unexpected('b'.into()),
+                Error::Expected('a'.into())
+            ])
+        );
+    }
+
+    #[test]
+    fn choice_of_boxed_parsers() {
+        let mut parsers: Vec<Box<dyn Parser<&str, Output = char, PartialState = ()>>> =
+            vec![Box::new(digit()), Box::new(token('+'))];
+        let mut parser = many::<String, _, _>(choice(&mut parsers[..]));
+        assert_eq!(parser.parse("1+2a"), Ok(("1+2".to_string(), "a")));
+    }
+
+    #[test]
+    fn shared_parser() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let shared = Rc::new(RefCell::new(digit()));
+        let mut parser = (shared.clone(), token(','), shared);
+        assert_eq!(parser.parse("1,2"), Ok((('1', ',', '2'), "")));
+        assert!(parser.parse("1,a").is_err());
+    }
+
+    #[test]
+    fn map_err_committed() {
+        let mut parser = (
+            char('a'),
+            char('b').map_err(|mut err: easy::Errors<char, &str, SourcePosition>| {
+                err.position = SourcePosition::default();
+                err
+            }),
+        );
+        let err = parser.easy_parse(position::Stream::new("ac")).unwrap_err();
+        assert_eq!(err.position, SourcePosition::default());
+        assert!(err.errors.contains(&Error::Expected('b'.into())));
+    }
+
+    #[test]
+    fn silent_discards_errors() {
+        let mut parser = look_ahead(digit())
+            .ensure(|_| false, "noise")
+            .silent()
+            .or(letter());
+        let err = parser.easy_parse(position::Stream::new("1")).unwrap_err();
+        assert_eq!(
+            err.errors,
+            vec![
+                Error::Unexpected('1'.into()),
+                Error::Expected("letter".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn owned_error_messages() {
+        use std::borrow::Cow;
+
+        let tag = "div";
+        let err = token('>')
+            .message(format!("unclosed <{}>", tag))
+            .expected(Cow::Borrowed("`>`"))
+            .easy_parse(position::Stream::new("a"))
+            .unwrap_err();
+        assert!(err
+            .errors
+            .contains(&Error::Message("unclosed <div>".to_string().into())));
+        assert!(err.errors.contains(&Error::Expected("`>`".into())));
+    }
+
+    #[test]
+    fn budget_limits_backtracking() {
+        use combine::stream::budget;
+
+        let mut parser = many::<Vec<_>, _, _>(
+            attempt((letter(), digit())).or(attempt(string("ab").map(|_| ('a', 'b')))),
+        );
+        let mut input =
+            budget::Stream::new(easy::Stream("abababababababababababab")).max_backtracks(10);
+        let err = parser.parse_stream(&mut input).into_result().unwrap_err();
+        assert!(input.is_exhausted());
+        assert!(err
+            .into_inner()
+            .error
+            .errors
+            .contains(&Error::Message("backtrack budget exceeded".into())));
+
+        let mut input = budget::Stream::new(easy::Stream("a1b2")).max_backtracks(10);
+        let result = parser.parse_stream(&mut input).into_result();
+        assert_eq!(
+            result.map(|(output, _)| output),
+            Ok(vec![('a', '1'), ('b', '2')])
+        );
+        assert!(!input.is_exhausted());
+    }
+
+    #[test]
+    fn interned_owned_strings() {
+        use combine::stream::state::{self, StringInterner};
+
+        let mut interner = StringInterner::new();
+        {
+            let mut parser =
+                sep_by::<Vec<_>, _, _, _>(many1::<String, _, _>(letter()).interned(), char(','));
+            let mut input = state::Stream {
+                stream: "ab,cd,ab",
+                state: &mut interner,
+            };
+            let result = parser.parse_stream(&mut input).into_result();
+            assert_eq!(result.map(|(output, _)| output), Ok(vec![0, 1, 0]));
+        }
+        assert_eq!(interner.resolve(1), Some("cd"));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn indent_blocks() {
+        use combine::parser::{
+            char::spaces,
+            indent::{aligned, block, dedent, indented, IndentStack},
+        };
+        use combine::stream::state;
+
+        let mut parser = spaces().with(block((
+            aligned(letter()).skip(spaces()),
+            indented(letter()).skip(spaces()),
+            dedent(),
+        )));
+        let mut input = state::Stream {
+            stream: position::Stream::new("  a\n    b\n c"),
+            state: IndentStack::new(),
+        };
+        let result = parser.parse_stream(&mut input).into_result();
+        assert_eq!(result.map(|t| t.0), Ok(('a', 'b', ())));
+        assert_eq!(input.state.depth(), 0);
+
+        let mut parser = block((aligned(letter()).skip(spaces()), aligned(letter())));
+        let mut input = state::Stream {
+            stream: easy::Stream(position::Stream::new("a\n b")),
+            state: IndentStack::new(),
+        };
+        let err = parser.parse_stream(&mut input).into_result().unwrap_err();
+        assert!(err.into_inner().error.errors.contains(&Error::Message(
+            "expected a line at column 1".to_string().into()
+        )));
+    }
+
+    #[test]
+    fn utf8_errors_are_reported_relative_to_the_range() {
+        use combine::parser::range::{take_while1, utf8};
+
+        let input = &b"> ab\xffc"[..];
+        let err = utf8(range(&b"> "[..]).with(take_while1(|b: u8| b != b' ')))
+            .easy_parse(input)
+            .unwrap_err();
+        assert_eq!(err.position.translate_position(input), 4);
+    }
+
+    #[test]
+    fn checksum_mismatch_is_reported_after_resuming() {
+        use combine::{
+            parser::range::{checksummed, take},
+            stream::PartialStream,
+        };
+
+        let sum = |bytes: &[u8]| bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+        let mut parser = checksummed(take(3), any(), sum);
+        let mut state = Default::default();
+        let buf = &b"\x01\x02\x03\x07rest"[..];
+
+        let mut input = easy::Stream(PartialStream(&buf[..3]));
+        assert!(parser.parse_with_state(&mut input, &mut state).is_err());
+        assert_eq!(input.0 .0, &b""[..]);
+
+        let mut input = easy::Stream(PartialStream(&buf[3..]));
+        let err = parser.parse_with_state(&mut input, &mut state).unwrap_err();
+        assert_eq!(err.position.translate_position(buf), 3);
+    }
+
+    #[test]
+    fn with_position_after_resuming() {
+        use combine::{
+            parser::char::{digit, spaces},
+            stream::PartialStream,
+        };
+
+        let mut parser = (
+            letter(),
+            spaces().with(many1::<String, _, _>(digit()).with_position()),
+            char(';'),
+        );
+        let mut state = Default::default();
+        let buf = "a 12;";
+
+        let mut input = PartialStream(&buf[..4]);
+        assert!(parser.parse_with_state(&mut input, &mut state).is_err());
+        assert_eq!(input.0, "");
+
+        let mut input = PartialStream(&buf[4..]);
+        let (_, (position, digits), _) = parser.parse_with_state(&mut input, &mut state).unwrap();
+        assert_eq!(position.translate_position(buf), 2);
+        assert_eq!(digits, "12");
+    }
+
+    #[test]
+    fn parse_iter_stops_when_no_input_is_consumed() {
+        let mut iter = many::<String, _, _>(letter()).parse_iter("123");
+        assert_eq!(iter.next(), Some(Ok(String::new())));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_input(), "123");
+    }
+
+    #[test]
+    fn incremental_invalidates_items_which_looked_ahead() {
+        use combine::parser::char::{spaces, string};
+        use combine::stream::incremental::Incremental;
+
+        let item = || {
+            attempt(string("ab!").map(|_| 'X'))
+                .or(letter())
+                .skip(spaces())
+        };
+
+        let mut incremental = Incremental::new();
+        assert_eq!(incremental.parse(item(), "ab a"), Ok(""));
+        assert_eq!(incremental.values().collect::<String>(), "aba");
+        assert_eq!(incremental.items()[0].span, 0..1);
+        assert_eq!(incremental.items()[0].lookahead, 3);
+
+        // Replacing the space changes the first item even though it ends before the edit
+        incremental.edit(2);
+        assert_eq!(incremental.resume_offset(), 0);
+        assert_eq!(incremental.parse(item(), "ab!a"), Ok(""));
+        assert_eq!(incremental.values().collect::<String>(), "Xa");
+    }
+
+    #[test]
+    fn incremental_errors_if_no_input_is_consumed() {
+        use combine::stream::incremental::Incremental;
+
+        let mut incremental = Incremental::new();
+        assert!(incremental
+            .parse(many::<String, _, _>(letter()), "ab1")
+            .is_err());
+        assert_eq!(incremental.values().collect::<Vec<_>>(), ["ab"]);
+    }
+}
+
+#[test]
+fn collect_into_small_vec() {
+    use combine::parser::{byte::num::be_u16, char::char};
+    use smallvec::SmallVec;
+
+    let result: Result<(SmallVec<[char; 4]>, _), _> = many(digit()).parse("123a");
+    assert_eq!(
+        result.map(|(v, rest)| (v.to_vec(), rest)),
+        Ok((vec!['1', '2', '3'], "a"))
+    );
+
+    let result: Result<(SmallVec<[u16; 2]>, _), _> =
+        count(3, be_u16()).parse(&b"\x00\x01\x00\x02\x00\x03"[..]);
+    let (values, _) = result.unwrap();
+    assert_eq!(&values[..], [1, 2, 3]);
+    assert!(values.spilled());
+
+    let result: Result<(SmallVec<[char; 4]>, _), _> = sep_by(digit(), char(',')).parse("1,2");
+    assert_eq!(
+        result.map(|(v, rest)| (v.to_vec(), rest)),
+        Ok((vec!['1', '2'], ""))
+    );
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn collect_into_array_vec() {
+    use arrayvec::ArrayVec;
+    use combine::{
+        parser,
+        parser::{char::char, repeat::collect_array_vec},
+        stream::easy,
+    };
+
+    let result: Result<(ArrayVec<char, 4>, _), _> = collect_array_vec(many(digit())).parse("123a");
+    assert_eq!(
+        result.map(|(v, rest)| (v.to_vec(), rest)),
+        Ok((vec!['1', '2', '3'], "a"))
+    );
+
+    let result: Result<(ArrayVec<char, 2>, _), _> =
+        collect_array_vec(count(2, digit())).parse("123");
+    assert_eq!(
+        result.map(|(v, rest)| (v.to_vec(), rest)),
+        Ok((vec!['1', '2'], "3"))
+    );
+
+    let result: Result<(ArrayVec<char, 2>, _), _> =
+        collect_array_vec(sep_by(digit(), char(','))).parse("1,2,3");
+    assert!(result.is_err());
+
+    // The repetition stops as soon as the capacity is exceeded
+    let calls = std::cell::Cell::new(0);
+    let counted_digit = parser(|input| {
+        calls.set(calls.get() + 1);
+        digit().parse_stream(input).into_result()
+    });
+    let result: Result<(ArrayVec<char, 2>, _), _> =
+        collect_array_vec(many(counted_digit)).parse("12345");
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 3);
+
+    let result: Result<(ArrayVec<char, 2>, _), _> =
+        collect_array_vec(many(digit())).easy_parse("123");
+    assert!(result.unwrap_err().errors.contains(&easy::Error::Message(
+        "expected at most 2 elements".to_string().into()
+    )));
+}
+
+#[test]
+fn lexer_stream_spans_and_errors() {
+    use combine::{
+        parser::char::spaces,
+        stream::{
+            lexer,
+            position::{self, SourcePosition},
+        },
+    };
+
+    let word = || many1::<String, _, _>(letter());
+    let pos = |column| SourcePosition { line: 1, column };
+
+    let tokens: Vec<_> = lexer::Stream::new(position::Stream::new("ab cd "), word(), spaces())
+        .map(|token| token.map(|token| (token.span, token.value)))
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            Ok((pos(1)..pos(3), "ab".to_string())),
+            Ok((pos(4)..pos(6), "cd".to_string())),
+        ]
+    );
+
+    let mut tokens = lexer::Stream::new(position::Stream::new("ab 1"), word(), spaces());
+    let result = many::<Vec<String>, _, _>(any())
+        .skip(eof())
+        .parse_stream(&mut tokens)
+        .into_result();
+    assert!(result.is_err());
+    assert!(tokens.lexer_error().is_some());
+    assert_eq!(tokens.input().positioner, pos(4));
+
+    // Lexing other tokens after backtracking keeps the error
+    let mut tokens = lexer::Stream::new(position::Stream::new("ab 1"), word(), spaces());
+    let result = attempt((any(), any()))
+        .map(|_| ())
+        .or(any().map(|_| ()))
+        .parse_stream(&mut tokens)
+        .into_result();
+    assert!(result.is_ok());
+    assert!(tokens.lexer_error().is_some());
+}
+
+#[test]
+fn spanned_slice_stream_reports_end_of_input_at_last_span() {
+    use combine::stream::SpannedSliceStream;
+
+    let tokens = [('a', 0..1), ('b', 4..6)];
+    let mut parser = (any(), any(), any());
+    let err = parser
+        .easy_parse(SpannedSliceStream::new(&tokens[..]))
+        .err()
+        .unwrap();
+    assert_eq!(err.position, 6);
+
+    let mut parser = (token(&'a'), token(&'a'));
+    let err = parser
+        .easy_parse(SpannedSliceStream::new(&tokens[..]))
+        .err()
+        .unwrap();
+    assert_eq!(err.position, 4);
+}
+
+#[test]
+fn event_nodes_of_backtracked_alternatives_are_removed() {
+    use combine::parser::event::{self, node, Event};
+
+    let mut events = Vec::new();
+    let result = event::parse(
+        choice((
+            attempt(node(1, (letter(), digit()).map(|_| String::new()))),
+            node(2, many1::<String, _, _>(letter())),
+        )),
+        "ab",
+        &mut events,
+    );
+    assert_eq!(result.map(|t| t.0), Ok("ab".to_string()));
+    assert_eq!(
+        events,
+        [Event::StartNode(2), Event::Token(0..2), Event::FinishNode]
+    );
+}
+
+#[test]
+fn reflect_recursive_rules() {
+    use combine::parser::reflect::Grammar;
+
+    combine::parser! {
+        fn expr[Input]()(Input) -> ()
+        where [Input: combine::Stream<Token = char>]
+        {
+            choice((
+                digit().map(|_| ()),
+                (token('('), expr(), token(')')).map(|_| ()),
+            ))
+        }
+    }
+
+    let grammar = Grammar::of::<&str, _>(&mut expr());
+    assert_eq!(
+        grammar.to_string(),
+        "expr ::= digit | '(' expr ')'\ndigit ::= <digit>\n"
+    );
+    assert!(grammar.to_dot().contains("-> \"rule expr\" [style=dashed]"));
+}
+
+#[test]
+fn reflect_rules_of_the_same_name_in_different_modules() {
+    use combine::parser::reflect::Grammar;
+
+    mod a {
+        combine::parser! {
+            pub fn item[Input]()(Input) -> char
+            where [Input: combine::Stream<Token = char>]
+            {
+                combine::parser::char::char('a')
+            }
+        }
+    }
+    mod b {
+        combine::parser! {
+            pub fn item[Input]()(Input) -> char
+            where [Input: combine::Stream<Token = char>]
+            {
+                combine::parser::char::char('b')
+            }
+        }
+    }
+
+    let grammar = Grammar::of::<&str, _>(&mut (a::item(), b::item()));
+    let modules: Vec<_> = grammar.rules.iter().map(|rule| rule.module).collect();
+    assert_eq!(modules.len(), 2);
+    assert!(modules[0].ends_with("::a"));
+    assert!(modules[1].ends_with("::b"));
+
+    assert_eq!(
+        grammar.to_string(),
+        "start ::= parser.a.item parser.b.item\nparser.a.item ::= 'a'\nparser.b.item ::= 'b'\n"
+    );
+    let dot = grammar.to_dot();
+    assert!(dot.contains("\"rule parser.a.item\" [label=\"parser.a.item\", shape=ellipse];"));
+    assert!(dot.contains("\"rule parser.b.item\" [label=\"parser.b.item\", shape=ellipse];"));
+    assert!(dot.contains("-> \"rule parser.a.item\" [style=dashed]"));
+    assert!(dot.contains("-> \"rule parser.b.item\" [style=dashed]"));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn check_partial_finds_parsers_which_do_not_resume() {
+    use combine::{
+        parser::function::parser,
+        stream::{MaybePartialStream, RangeStreamOnce},
+        test::check_partial,
+    };
+
+    // Unlike `take_while` this does not ask for more input when it reaches the end of a chunk
+    let word = || {
+        parser(|input: &mut MaybePartialStream<&str>| {
+            let word = input.uncons_while(|c: char| c.is_alphabetic());
+            word.map(|word| (word, combine::error::Commit::Commit(())))
+                .map_err(|err| combine::error::Commit::Commit(err.into()))
+        })
+    };
+
+    assert_eq!(check_partial(word, "abc", &[]), Ok(()));
+    let mismatch = check_partial(word, "abc", &[1]).unwrap_err();
+    assert_eq!(mismatch.splits, [1]);
+    assert_eq!(mismatch.whole, Ok(("abc", 3)));
+    assert_eq!(mismatch.chunked, Ok(("a", 1)));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn take_while_set_resumes_like_take_while() {
+    use combine::{
+        parser::{
+            byte_set::ByteSet,
+            range::{take_while1, take_while1_set, take_while_set},
+        },
+        test::assert_partial_equivalent,
+    };
+
+    let words = || {
+        (
+            take_while1_set(ByteSet::ASCII_ALPHABETIC),
+            take_while_set(ByteSet::ASCII_WHITESPACE),
+            take_while1(|c: char| c.is_alphabetic()),
+        )
+    };
+    assert_partial_equivalent(words, "héllo  wörld");
+    assert_partial_equivalent(words, "abcdefghijklmnopqrstuvwxyz0123 x");
+}
+
+#[test]
+fn many_of_single_tokens_tracks_positions() {
+    use combine::{
+        parser::{char::space, repeat::skip_many},
+        stream::position::{self, SourcePosition},
+    };
+
+    let mut parser = (
+        many::<String, _, _>(letter()),
+        skip_many(space()),
+        many::<Vec<_>, _, _>(token('!')),
+    );
+    let result = parser.easy_parse(position::Stream::new("ab \n  !!?"));
+    let (output, rest) = result.unwrap();
+    assert_eq!(output, ("ab".to_string(), (), vec!['!', '!']));
+    assert_eq!(rest.input, "?");
+    assert_eq!(rest.positioner, SourcePosition { line: 2, column: 5 });
+}
+
+#[test]
+fn end_of_input_errors_are_reported_after_backtracking() {
+    use combine::{
+        easy::{Error, Errors},
+        stream::position::{self, SourcePosition},
+    };
+
+    let end_of_input = |column, expected: &[char]| Errors {
+        position: SourcePosition { line: 1, column },
+        errors: Some(Error::end_of_input())
+            .into_iter()
+            .chain(expected.iter().map(|&c| Error::Expected(c.into())))
+            .collect(),
+    };
+
+    let mut parser = token('a').or(token('b'));
+    let result = parser.easy_parse(position::Stream::new(""));
+    assert_eq!(result.map(|(x, _)| x), Err(end_of_input(1, &['a', 'b'])));
+
+    let mut parser = (letter(), choice([token('a'), token('b')]));
+    let result = parser.easy_parse(position::Stream::new("x"));
+    assert_eq!(result.map(|(x, _)| x), Err(end_of_input(2, &['a', 'b'])));
+
+    let mut parser = (many1::<String, _, _>(token('1')), token('a').or(token('b')));
+    let result = parser.easy_parse(position::Stream::new("11"));
+    assert_eq!(
+        result.map(|(x, _)| x),
+        Err(end_of_input(3, &['1', 'a', 'b']))
+    );
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn many_of_single_tokens_resumes() {
+    use combine::{parser::repeat::skip_many, test::assert_partial_equivalent};
+
+    let words = || {
+        (
+            many::<String, _, _>(letter()),
+            skip_many(token(' ')),
+            many::<String, _, _>(digit()),
+        )
+    };
+    assert_partial_equivalent(words, "abc  123");
+    assert_partial_equivalent(words, "ab");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_errors_at_source_positions() {
+    use combine::{
+        easy,
+        stream::position::{self, SourcePosition},
+    };
+
+    let err = (letter(), token('\n'), digit())
+        .easy_parse(position::Stream::new("a\nb"))
+        .unwrap_err();
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(
+        json,
+        r#"{"position":{"line":2,"column":1},"errors":[{"kind":"unexpected","message":"b"},{"kind":"expected","message":"digit"}]}"#
+    );
+
+    let deserialized: easy::Errors<char, &str, SourcePosition> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.position, err.position);
+    assert_eq!(deserialized.to_string(), err.to_string());
+}
+
+#[cfg(feature = "nom")]
+#[test]
+fn from_nom_errors_commit_only_on_failure() {
+    use combine::{
+        parser::function::from_nom,
+        stream::position::{self, SourcePosition},
+    };
+    use nom_7::{
+        bytes::complete::tag, character::complete::digit1, combinator::cut, sequence::preceded,
+    };
+
+    let mut recoverable = from_nom(preceded(tag("#"), digit1)).or(range("#x"));
+    assert_eq!(recoverable.parse("#x"), Ok(("#x", "")));
+
+    let mut cut = from_nom(preceded(tag("#"), cut(digit1))).or(range("#x"));
+    let err = cut.easy_parse(position::Stream::new("#x")).unwrap_err();
+    assert_eq!(err.position, SourcePosition { line: 1, column: 2 });
 }
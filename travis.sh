@@ -9,4 +9,7 @@ cargo test --bench json --bench http -- --test
 cargo check --bench mp4 --features mp4
 
 cargo build --no-default-features
+# Check that nothing pulls in `std` (or `alloc`) on a target which lacks them
+rustup target add thumbv6m-none-eabi
+cargo build --no-default-features --target thumbv6m-none-eabi
 cargo test --no-default-features --examples